@@ -116,12 +116,23 @@ impl RepliconRenetServerPlugin {
     fn forward_server_events(
         mut renet_server_events: EventReader<renet::ServerEvent>,
         mut server_events: EventWriter<ServerEvent>,
+        mut replicon_server: ResMut<RepliconServer>,
+        channels: Res<RepliconChannels>,
     ) {
         for event in renet_server_events.read() {
             let replicon_event = match event {
-                renet::ServerEvent::ClientConnected { client_id } => ServerEvent::ClientConnected {
-                    client_id: ClientId::new(client_id.raw()),
-                },
+                renet::ServerEvent::ClientConnected { client_id } => {
+                    let client_id = ClientId::new(client_id.raw());
+                    for config in channels.get_server_configs() {
+                        replicon_server.set_max_payload_size(
+                            client_id,
+                            config.channel_id,
+                            max_payload_size(&config),
+                        );
+                    }
+
+                    ServerEvent::ClientConnected { client_id }
+                }
                 renet::ServerEvent::ClientDisconnected { client_id, reason } => {
                     ServerEvent::ClientDisconnected {
                         client_id: ClientId::new(client_id.raw()),
@@ -159,6 +170,11 @@ impl RepliconRenetServerPlugin {
             let client_id = renet::ClientId::from_raw(client_id.get());
             renet_server.send_message(client_id, channel_id, message)
         }
+
+        for client_id in replicon_server.drain_disconnects() {
+            let client_id = renet::ClientId::from_raw(client_id.get());
+            renet_server.disconnect(client_id);
+        }
     }
 }
 
@@ -236,6 +252,10 @@ impl RepliconRenetClientPlugin {
         for (channel_id, message) in replicon_client.drain_sent() {
             renet_client.send_message(channel_id, message)
         }
+
+        if replicon_client.take_disconnect_request() {
+            renet_client.disconnect();
+        }
     }
 }
 
@@ -268,6 +288,22 @@ impl RenetChannelsExt for RepliconChannels {
     }
 }
 
+/// Size of a single renet packet payload.
+///
+/// Larger unreliable messages are split into slices of this size,
+/// and the whole message is lost if any slice is lost.
+const SLICE_SIZE: usize = 1200;
+
+/// Returns the max size of a message that renet can send over a channel without splitting or rejecting it.
+fn max_payload_size(config: &ChannelConfig) -> usize {
+    match config.send_type {
+        SendType::Unreliable => SLICE_SIZE.min(config.max_memory_usage_bytes),
+        SendType::ReliableOrdered { .. } | SendType::ReliableUnordered { .. } => {
+            config.max_memory_usage_bytes
+        }
+    }
+}
+
 /// Converts replicon channels into renet channel configs.
 fn create_configs(channels: &[RepliconChannel], default_max_bytes: usize) -> Vec<ChannelConfig> {
     let mut channel_configs = Vec::with_capacity(channels.len());
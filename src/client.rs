@@ -1,7 +1,14 @@
+pub mod authority;
 pub mod confirm_history;
 pub mod diagnostics;
 pub mod events;
+pub mod interpolation;
+pub mod prediction;
+pub mod repair;
+pub mod replay;
 pub mod replicon_client;
+pub mod rpc;
+pub mod server_baselines;
 pub mod server_entity_map;
 
 use std::{io::Cursor, mem};
@@ -9,20 +16,25 @@ use std::{io::Cursor, mem};
 use bevy::{ecs::world::CommandQueue, prelude::*};
 use bincode::{DefaultOptions, Options};
 use bytes::Bytes;
-use varint_rs::VarintReader;
 
 use crate::core::{
     channels::{ReplicationChannel, RepliconChannels},
     command_markers::{CommandMarkers, EntityMarkers},
-    common_conditions::{client_connected, client_just_connected, client_just_disconnected},
+    common_conditions::{
+        client_connected, client_just_connected, client_just_disconnected, protocol_verified,
+    },
+    compression::{self, COMPRESSED_FLAG, COMPRESSED_UPDATE_BIT, INITIAL_SYNC_FLAG},
     ctx::{DespawnCtx, RemoveCtx, WriteCtx},
+    protocol::ProtocolHash,
     replication_registry::ReplicationRegistry,
+    replication_resources::ReplicationResources,
     replicon_tick::RepliconTick,
-    Replicated,
+    varint, Replicated,
 };
 use confirm_history::ConfirmHistory;
 use diagnostics::ClientStats;
 use replicon_client::RepliconClient;
+use server_baselines::ServerBaselines;
 use server_entity_map::ServerEntityMap;
 
 /// Client functionality and replication receiving.
@@ -36,6 +48,11 @@ impl Plugin for ClientPlugin {
             .init_resource::<ServerEntityMap>()
             .init_resource::<ServerInitTick>()
             .init_resource::<BufferedUpdates>()
+            .init_resource::<ServerBaselines>()
+            .init_resource::<ProtocolStatus>()
+            .init_resource::<InitialSyncStatus>()
+            .init_resource::<ReceiveErrorPolicy>()
+            .add_event::<ReceiveError>()
             .configure_sets(
                 PreUpdate,
                 (
@@ -56,11 +73,21 @@ impl Plugin for ClientPlugin {
             .add_systems(Startup, Self::setup_channels)
             .add_systems(
                 PreUpdate,
-                Self::receive_replication
-                    .map(Result::unwrap)
+                (
+                    Self::send_protocol_hash.run_if(client_just_connected),
+                    Self::receive_protocol_hash,
+                    Self::receive_replication.run_if(protocol_verified),
+                )
+                    .chain()
                     .in_set(ClientSet::Receive)
                     .run_if(client_connected),
             )
+            .add_systems(
+                PreUpdate,
+                Self::handle_receive_errors
+                    .after(ClientSet::Receive)
+                    .run_if(client_connected),
+            )
             .add_systems(PreUpdate, Self::reset.in_set(ClientSet::Reset));
     }
 }
@@ -83,41 +110,47 @@ impl ClientPlugin {
     ///
     /// Buffered entity update messages are processed last.
     ///
-    /// Acknowledgments for received entity update messages are sent back to the server.
+    /// Acknowledgments for applied entity update messages are sent back to the server.
+    ///
+    /// Messages that can't be applied are skipped and reported as [`ReceiveError`].
     ///
     /// See also [`ReplicationMessages`](crate::server::replication_messages::ReplicationMessages).
     pub(super) fn receive_replication(
         world: &mut World,
         mut queue: Local<CommandQueue>,
         mut entity_markers: Local<EntityMarkers>,
-    ) -> bincode::Result<()> {
+    ) {
         world.resource_scope(|world, mut client: Mut<RepliconClient>| {
             world.resource_scope(|world, mut entity_map: Mut<ServerEntityMap>| {
                 world.resource_scope(|world, mut buffered_updates: Mut<BufferedUpdates>| {
-                    world.resource_scope(|world, command_markers: Mut<CommandMarkers>| {
-                        world.resource_scope(|world, registry: Mut<ReplicationRegistry>| {
-                            let mut stats = world.remove_resource::<ClientStats>();
-                            let mut params = ReceiveParams {
-                                queue: &mut queue,
-                                entity_markers: &mut entity_markers,
-                                entity_map: &mut entity_map,
-                                stats: stats.as_mut(),
-                                command_markers: &command_markers,
-                                registry: &registry,
-                            };
-
-                            apply_replication(
-                                world,
-                                &mut params,
-                                &mut client,
-                                &mut buffered_updates,
-                            )?;
-
-                            if let Some(stats) = stats {
-                                world.insert_resource(stats);
-                            }
-
-                            Ok(())
+                    world.resource_scope(|world, mut server_baselines: Mut<ServerBaselines>| {
+                        world.resource_scope(|world, command_markers: Mut<CommandMarkers>| {
+                            world.resource_scope(|world, registry: Mut<ReplicationRegistry>| {
+                                let mut stats = world.remove_resource::<ClientStats>();
+                                let mut params = ReceiveParams {
+                                    queue: &mut queue,
+                                    entity_markers: &mut entity_markers,
+                                    entity_map: &mut entity_map,
+                                    server_baselines: &mut server_baselines,
+                                    stats: stats.as_mut(),
+                                    command_markers: &command_markers,
+                                    registry: &registry,
+                                };
+
+                                let mut errors = Vec::new();
+                                apply_replication(
+                                    world,
+                                    &mut params,
+                                    &mut client,
+                                    &mut buffered_updates,
+                                    &mut errors,
+                                );
+
+                                if let Some(stats) = stats {
+                                    world.insert_resource(stats);
+                                }
+                                world.send_event_batch(errors);
+                            })
                         })
                     })
                 })
@@ -125,28 +158,104 @@ impl ClientPlugin {
         })
     }
 
+    fn send_protocol_hash(mut client: ResMut<RepliconClient>, protocol_hash: Res<ProtocolHash>) {
+        debug!("sending {:?} to the server", *protocol_hash);
+        client.send(
+            ReplicationChannel::Protocol,
+            protocol_hash.get().to_le_bytes().to_vec(),
+        );
+    }
+
+    fn receive_protocol_hash(
+        mut client: ResMut<RepliconClient>,
+        mut protocol_status: ResMut<ProtocolStatus>,
+        protocol_hash: Res<ProtocolHash>,
+    ) {
+        for message in client.receive(ReplicationChannel::Protocol) {
+            let Ok(bytes) = (*message).try_into() else {
+                error!("received invalid protocol hash from the server");
+                continue;
+            };
+            let server_hash = ProtocolHash::new(u64::from_le_bytes(bytes));
+            if server_hash == *protocol_hash {
+                debug!("server confirmed {server_hash:?}");
+                *protocol_status = ProtocolStatus::Verified;
+            } else {
+                error!(
+                    "server's {server_hash:?} doesn't match client's {:?}, \
+                    make sure that replication rules, events and channels are registered in the same order",
+                    *protocol_hash
+                );
+                *protocol_status = ProtocolStatus::Mismatch { server_hash };
+            }
+        }
+    }
+
+    /// Logs [`ReceiveError`] events and applies [`ReceiveErrorPolicy`].
+    fn handle_receive_errors(
+        mut errors: EventReader<ReceiveError>,
+        mut client: ResMut<RepliconClient>,
+        policy: Res<ReceiveErrorPolicy>,
+    ) {
+        for error in errors.read() {
+            match *policy {
+                ReceiveErrorPolicy::Skip => {
+                    error!("skipping invalid {:?}: {}", error.message, error.error)
+                }
+                ReceiveErrorPolicy::Disconnect => {
+                    error!(
+                        "disconnecting due to invalid {:?}: {}",
+                        error.message, error.error
+                    );
+                    client.disconnect();
+                }
+                ReceiveErrorPolicy::PanicInDebug => {
+                    if cfg!(debug_assertions) {
+                        panic!("received invalid {:?}: {}", error.message, error.error);
+                    }
+                    error!("skipping invalid {:?}: {}", error.message, error.error);
+                }
+            }
+        }
+    }
+
     fn reset(
         mut init_tick: ResMut<ServerInitTick>,
         mut entity_map: ResMut<ServerEntityMap>,
         mut buffered_updates: ResMut<BufferedUpdates>,
+        mut server_baselines: ResMut<ServerBaselines>,
+        mut protocol_status: ResMut<ProtocolStatus>,
+        mut sync_status: ResMut<InitialSyncStatus>,
     ) {
         *init_tick = Default::default();
         entity_map.clear();
         buffered_updates.clear();
+        server_baselines.clear();
+        *protocol_status = Default::default();
+        *sync_status = Default::default();
     }
 }
 
 /// Reads all received messages and applies them.
 ///
-/// Sends acknowledgments for update messages back.
+/// Sends acknowledgments for applied update messages back.
+/// Messages that can't be applied are skipped and written into `errors`.
 fn apply_replication(
     world: &mut World,
     params: &mut ReceiveParams,
     client: &mut RepliconClient,
     buffered_updates: &mut BufferedUpdates,
-) -> bincode::Result<()> {
+    errors: &mut Vec<ReceiveError>,
+) {
     for message in client.receive(ReplicationChannel::Init) {
-        apply_init_message(world, params, &message)?;
+        if let Err(error) = apply_init_message(world, params, &message) {
+            // Apply commands from the part of the message that was read.
+            params.queue.apply(world);
+            errors.push(ReceiveError {
+                message: MessageKind::Init,
+                error,
+            });
+        }
     }
 
     // Unlike init messages, we read all updates first, sort them by tick
@@ -158,12 +267,23 @@ fn apply_replication(
     let acks_size = mem::size_of::<u16>() * client.received_count(ReplicationChannel::Update);
     let mut acks = Vec::with_capacity(acks_size);
     for message in client.receive(ReplicationChannel::Update) {
-        let update_index = read_update_message(params, buffered_updates, message)?;
-        bincode::serialize_into(&mut acks, &update_index)?;
+        if let Err(error) = read_update_message(params, buffered_updates, message) {
+            errors.push(ReceiveError {
+                message: MessageKind::Update,
+                error,
+            });
+        }
     }
-    client.send(ReplicationChannel::Init, acks);
 
-    apply_update_messages(world, params, buffered_updates, init_tick)
+    apply_update_messages(
+        world,
+        params,
+        buffered_updates,
+        init_tick,
+        &mut acks,
+        errors,
+    );
+    client.send(ReplicationChannel::Init, acks);
 }
 
 /// Applies [`InitMessage`](crate::server::replication_messages::InitMessage).
@@ -172,17 +292,41 @@ fn apply_init_message(
     params: &mut ReceiveParams,
     message: &[u8],
 ) -> bincode::Result<()> {
-    let end_pos: u64 = message.len().try_into().unwrap();
     let mut cursor = Cursor::new(message);
     if let Some(stats) = &mut params.stats {
         stats.messages += 1;
-        stats.bytes += end_pos;
+        stats.bytes += message.len() as u64;
     }
 
+    let flags = compression::read_flags(&mut cursor)?;
     let message_tick = bincode::deserialize_from(&mut cursor)?;
     trace!("applying init message for {message_tick:?}");
     world.resource_mut::<ServerInitTick>().0 = message_tick;
-    debug_assert!(cursor.position() < end_pos, "init message can't be empty");
+    if flags & INITIAL_SYNC_FLAG != 0 {
+        debug!("completed initial sync on {message_tick:?}");
+        *world.resource_mut::<InitialSyncStatus>() = InitialSyncStatus::Complete;
+    }
+
+    let payload = &message[cursor.position() as usize..];
+    let decompressed;
+    let payload = if flags & COMPRESSED_FLAG != 0 {
+        decompressed = compression::decompress(payload)?;
+        &decompressed
+    } else {
+        payload
+    };
+
+    let end_pos = payload.len() as u64;
+    let mut cursor = Cursor::new(payload);
+    if end_pos == 0 {
+        if flags & INITIAL_SYNC_FLAG == 0 {
+            return Err(bincode::ErrorKind::Custom(
+                "init message can't be empty without initial sync completion".into(),
+            )
+            .into());
+        }
+        return Ok(());
+    }
 
     apply_entity_mappings(world, params, &mut cursor)?;
     if cursor.position() == end_pos {
@@ -212,66 +356,112 @@ fn apply_init_message(
         &mut cursor,
         message_tick,
     )?;
+    if cursor.position() == end_pos {
+        return Ok(());
+    }
+
+    apply_resources(
+        world,
+        params,
+        ComponentsKind::Removal,
+        &mut cursor,
+        message_tick,
+    )?;
+    if cursor.position() == end_pos {
+        return Ok(());
+    }
+
+    apply_resources(
+        world,
+        params,
+        ComponentsKind::Insert,
+        &mut cursor,
+        message_tick,
+    )?;
 
     Ok(())
 }
 
 /// Reads and buffers [`UpdateMessage`](crate::server::replication_messages::UpdateMessage).
-///
-/// Returns update index to be used for acknowledgment.
 fn read_update_message(
     params: &mut ReceiveParams,
     buffered_updates: &mut BufferedUpdates,
     message: Bytes,
-) -> bincode::Result<u16> {
-    let end_pos: u64 = message.len().try_into().unwrap();
+) -> bincode::Result<()> {
+    let end_pos = message.len() as u64;
     let mut cursor = Cursor::new(&*message);
     if let Some(stats) = &mut params.stats {
         stats.messages += 1;
         stats.bytes += end_pos;
     }
 
-    let (init_tick, message_tick, update_index) = bincode::deserialize_from(&mut cursor)?;
+    let (init_tick, message_tick, update_index): (_, _, u16) =
+        bincode::deserialize_from(&mut cursor)?;
     trace!("received update message for {message_tick:?}");
+
+    let payload = message.slice(cursor.position() as usize..);
+    let compressed = update_index & COMPRESSED_UPDATE_BIT != 0;
+    let update_index = update_index & !COMPRESSED_UPDATE_BIT;
+    let payload = if compressed {
+        compression::decompress(&payload)?.into()
+    } else {
+        payload
+    };
+
     buffered_updates.insert(BufferedUpdate {
         init_tick,
         message_tick,
-        message: message.slice(cursor.position() as usize..),
+        update_index,
+        message: payload,
     });
 
-    Ok(update_index)
+    Ok(())
 }
 
-/// Applies updates from [`BufferedUpdates`].
+/// Applies updates from [`BufferedUpdates`] and writes indexes of applied updates into `acks`.
 ///
 /// If the update message can't be applied yet (because the init message with the
 /// corresponding tick hasn't arrived), it will be kept in the buffer.
+/// Messages that can't be applied are discarded without acknowledgment and written into `errors`.
 fn apply_update_messages(
     world: &mut World,
     params: &mut ReceiveParams,
     buffered_updates: &mut BufferedUpdates,
     init_tick: ServerInitTick,
-) -> bincode::Result<()> {
-    let mut result = Ok(());
+    acks: &mut Vec<u8>,
+    errors: &mut Vec<ReceiveError>,
+) {
     buffered_updates.0.retain(|update| {
         if update.init_tick > *init_tick {
             return true;
         }
 
         trace!("applying update message for {:?}", update.message_tick);
-        if let Err(e) = apply_update_components(
+        match apply_update_components(
             world,
             params,
             &mut Cursor::new(&*update.message),
             update.message_tick,
         ) {
-            result = Err(e);
+            Ok(true) => {
+                bincode::serialize_into(&mut *acks, &update.update_index)
+                    .expect("writing into a vector should never fail");
+            }
+            Ok(false) => trace!(
+                "skipping acknowledgment for update message for {:?}",
+                update.message_tick
+            ),
+            Err(error) => {
+                params.queue.apply(world);
+                errors.push(ReceiveError {
+                    message: MessageKind::Update,
+                    error,
+                });
+            }
         }
 
         false
     });
-
-    result
 }
 
 /// Applies received server mappings from client's pre-spawned entities.
@@ -280,14 +470,22 @@ fn apply_entity_mappings(
     params: &mut ReceiveParams,
     cursor: &mut Cursor<&[u8]>,
 ) -> bincode::Result<()> {
-    let mappings_len: u16 = bincode::deserialize_from(&mut *cursor)?;
-    if let Some(stats) = &mut params.stats {
-        stats.mappings += mappings_len as u32;
-    }
+    let mappings_len: usize = DefaultOptions::new().deserialize_from(&mut *cursor)?;
     for _ in 0..mappings_len {
         let server_entity = deserialize_entity(cursor)?;
         let client_entity = deserialize_entity(cursor)?;
 
+        if params
+            .entity_map
+            .get_by_server(server_entity)
+            .is_some_and(|existing_entity| existing_entity != client_entity)
+        {
+            return Err(bincode::ErrorKind::Custom(format!(
+                "received mapping from already mapped {server_entity:?} to {client_entity:?}"
+            ))
+            .into());
+        }
+
         if let Some(mut entity) = world.get_entity_mut(client_entity) {
             debug!("received mapping from {server_entity:?} to {client_entity:?}");
             entity.insert(Replicated);
@@ -297,6 +495,9 @@ fn apply_entity_mappings(
             debug!("received mapping from {server_entity:?} to {client_entity:?}, but the entity doesn't exists");
         }
     }
+    if let Some(stats) = &mut params.stats {
+        stats.mappings += mappings_len as u32;
+    }
     Ok(())
 }
 
@@ -308,10 +509,10 @@ fn apply_init_components(
     cursor: &mut Cursor<&[u8]>,
     message_tick: RepliconTick,
 ) -> bincode::Result<()> {
-    let entities_len: u16 = bincode::deserialize_from(&mut *cursor)?;
+    let entities_len: usize = DefaultOptions::new().deserialize_from(&mut *cursor)?;
     for _ in 0..entities_len {
         let server_entity = deserialize_entity(cursor)?;
-        let data_size: u16 = bincode::deserialize_from(&mut *cursor)?;
+        let data_size: usize = DefaultOptions::new().deserialize_from(&mut *cursor)?;
 
         let client_entity = params
             .entity_map
@@ -319,14 +520,23 @@ fn apply_init_components(
 
         let world_cell = world.as_unsafe_world_cell();
         // SAFETY: access is unique and used to obtain `EntityMut`, which is just a wrapper over `UnsafeEntityCell`.
-        let mut client_entity: EntityMut =
-            unsafe { world_cell.world_mut().entity_mut(client_entity).into() };
+        let mut client_entity: EntityMut = unsafe { world_cell.world_mut() }
+            .get_entity_mut(client_entity)
+            .ok_or_else(|| despawned_entity(server_entity, client_entity))?
+            .into();
         let mut commands = Commands::new_from_entities(params.queue, world_cell.entities());
         params
             .entity_markers
             .read(params.command_markers, &client_entity);
 
         if let Some(mut history) = client_entity.get_mut::<ConfirmHistory>() {
+            if message_tick < history.last_tick() {
+                return Err(bincode::ErrorKind::Custom(format!(
+                    "received init for {server_entity:?} with {message_tick:?} older than {:?}",
+                    history.last_tick()
+                ))
+                .into());
+            }
             history.set_last_tick(message_tick);
         } else {
             commands
@@ -334,10 +544,10 @@ fn apply_init_components(
                 .insert(ConfirmHistory::new(message_tick));
         }
 
-        let end_pos = cursor.position() + data_size as u64;
+        let end_pos = cursor.position().saturating_add(data_size as u64);
         let mut components_len = 0u32;
         while cursor.position() < end_pos {
-            let fns_id = DefaultOptions::new().deserialize_from(&mut *cursor)?;
+            let fns_id = params.registry.read_fns_id(cursor)?;
             let (component_fns, rule_fns) = params.registry.get(fns_id);
             match components_kind {
                 ComponentsKind::Insert => {
@@ -345,13 +555,31 @@ fn apply_init_components(
 
                     // SAFETY: `rule_fns` and `component_fns` were created for the same type.
                     unsafe {
-                        component_fns.write(
-                            &mut ctx,
-                            rule_fns,
-                            params.entity_markers,
-                            &mut client_entity,
-                            cursor,
-                        )?;
+                        if rule_fns.delta_compression() {
+                            let bytes = params
+                                .server_baselines
+                                .read(cursor, server_entity, fns_id, message_tick)?
+                                .ok_or_else(|| {
+                                    bincode::ErrorKind::Custom(format!(
+                                        "init message for {server_entity:?} references an unknown baseline"
+                                    ))
+                                })?;
+                            component_fns.write(
+                                &mut ctx,
+                                rule_fns,
+                                params.entity_markers,
+                                &mut client_entity,
+                                &mut Cursor::new(bytes),
+                            )?;
+                        } else {
+                            component_fns.write(
+                                &mut ctx,
+                                rule_fns,
+                                params.entity_markers,
+                                &mut client_entity,
+                                cursor,
+                            )?;
+                        }
                     }
                 }
                 ComponentsKind::Removal => {
@@ -373,6 +601,35 @@ fn apply_init_components(
     Ok(())
 }
 
+/// Deserializes replicated resources of `resources_kind` and applies them to the `world`.
+fn apply_resources(
+    world: &mut World,
+    params: &mut ReceiveParams,
+    resources_kind: ComponentsKind,
+    cursor: &mut Cursor<&[u8]>,
+    message_tick: RepliconTick,
+) -> bincode::Result<()> {
+    world.resource_scope(|world, resources: Mut<ReplicationResources>| {
+        let resources_len: usize = DefaultOptions::new().deserialize_from(&mut *cursor)?;
+        let mut commands = Commands::new_from_entities(params.queue, world.entities());
+        for _ in 0..resources_len {
+            let index = resources.read_id(cursor)?;
+            let resource_fns = resources.get(index);
+            match resources_kind {
+                ComponentsKind::Insert => {
+                    let mut ctx = WriteCtx::new(&mut commands, params.entity_map, message_tick);
+                    resource_fns.write(&mut ctx, cursor)?;
+                }
+                ComponentsKind::Removal => resource_fns.remove(&mut commands),
+            }
+        }
+
+        params.queue.apply(world);
+
+        Ok(())
+    })
+}
+
 /// Deserializes despawns and applies them to the `world`.
 fn apply_despawns(
     world: &mut World,
@@ -380,15 +637,13 @@ fn apply_despawns(
     cursor: &mut Cursor<&[u8]>,
     message_tick: RepliconTick,
 ) -> bincode::Result<()> {
-    let entities_len: u16 = bincode::deserialize_from(&mut *cursor)?;
-    if let Some(stats) = &mut params.stats {
-        stats.despawns += entities_len as u32;
-    }
+    let entities_len: usize = DefaultOptions::new().deserialize_from(&mut *cursor)?;
     for _ in 0..entities_len {
         // The entity might have already been despawned because of hierarchy or
         // with the last replication message, but the server might not yet have received confirmation
         // from the client and could include the deletion in the this message.
         let server_entity = deserialize_entity(cursor)?;
+        params.server_baselines.remove(server_entity);
         if let Some(client_entity) = params
             .entity_map
             .remove_by_server(server_entity)
@@ -398,6 +653,9 @@ fn apply_despawns(
             (params.registry.despawn)(&ctx, client_entity);
         }
     }
+    if let Some(stats) = &mut params.stats {
+        stats.despawns += entities_len as u32;
+    }
 
     Ok(())
 }
@@ -405,36 +663,43 @@ fn apply_despawns(
 ///  Deserializes replicated component updates and applies them to the `world`.
 ///
 /// Consumes all remaining bytes in the cursor.
+/// Returns `false` if the message shouldn't be acknowledged because some values with delta
+/// compression couldn't be reconstructed. This way the server won't use them as baselines.
 fn apply_update_components(
     world: &mut World,
     params: &mut ReceiveParams,
     cursor: &mut Cursor<&[u8]>,
     message_tick: RepliconTick,
-) -> bincode::Result<()> {
+) -> bincode::Result<bool> {
+    let mut acknowledge = true;
     let message_end = cursor.get_ref().len() as u64;
     while cursor.position() < message_end {
         let server_entity = deserialize_entity(cursor)?;
-        let data_size: u16 = bincode::deserialize_from(&mut *cursor)?;
+        let data_size: usize = DefaultOptions::new().deserialize_from(&mut *cursor)?;
 
         let Some(client_entity) = params.entity_map.get_by_server(server_entity) else {
             // Update could arrive after a despawn from init message.
             debug!("ignoring update received for unknown server's {server_entity:?}");
-            cursor.set_position(cursor.position() + data_size as u64);
+            cursor.set_position(cursor.position().saturating_add(data_size as u64));
             continue;
         };
 
         let world_cell = world.as_unsafe_world_cell();
         // SAFETY: access is unique and used to obtain `EntityMut`, which is just a wrapper over `UnsafeEntityCell`.
-        let mut client_entity: EntityMut =
-            unsafe { world_cell.world_mut().entity_mut(client_entity).into() };
+        let mut client_entity: EntityMut = unsafe { world_cell.world_mut() }
+            .get_entity_mut(client_entity)
+            .ok_or_else(|| despawned_entity(server_entity, client_entity))?
+            .into();
         let mut commands = Commands::new_from_entities(params.queue, world_cell.entities());
         params
             .entity_markers
             .read(params.command_markers, &client_entity);
 
-        let mut history = client_entity
-            .get_mut::<ConfirmHistory>()
-            .expect("all entities from update should have confirmed ticks");
+        let mut history = client_entity.get_mut::<ConfirmHistory>().ok_or_else(|| {
+            bincode::ErrorKind::Custom(format!(
+                "received update for {server_entity:?} before its init message"
+            ))
+        })?;
         let new_entity = message_tick > history.last_tick();
         if new_entity {
             history.set_last_tick(message_tick);
@@ -444,7 +709,17 @@ fn apply_update_components(
                     "ignoring outdated update for client's {:?}",
                     client_entity.id()
                 );
-                cursor.set_position(cursor.position() + data_size as u64);
+                let mut ctx =
+                    WriteCtx::new_unmapped(&mut commands, params.entity_map, message_tick);
+                acknowledge &= skip_update_components(
+                    &mut ctx,
+                    params.registry,
+                    params.server_baselines,
+                    cursor,
+                    data_size,
+                    server_entity,
+                )?;
+                params.queue.apply(world);
                 continue;
             }
 
@@ -454,40 +729,66 @@ fn apply_update_components(
                     "discarding update {ago} ticks old for client's {:?}",
                     client_entity.id()
                 );
-                cursor.set_position(cursor.position() + data_size as u64);
+                let mut ctx =
+                    WriteCtx::new_unmapped(&mut commands, params.entity_map, message_tick);
+                acknowledge &= skip_update_components(
+                    &mut ctx,
+                    params.registry,
+                    params.server_baselines,
+                    cursor,
+                    data_size,
+                    server_entity,
+                )?;
+                params.queue.apply(world);
                 continue;
             }
 
             history.set(ago);
         }
 
-        let end_pos = cursor.position() + data_size as u64;
+        let end_pos = cursor.position().saturating_add(data_size as u64);
         let mut components_count = 0u32;
         while cursor.position() < end_pos {
-            let fns_id = DefaultOptions::new().deserialize_from(&mut *cursor)?;
+            let fns_id = params.registry.read_fns_id(cursor)?;
             let (component_fns, rule_fns) = params.registry.get(fns_id);
             let mut ctx = WriteCtx::new(&mut commands, params.entity_map, message_tick);
-
-            // SAFETY: `rule_fns` and `component_fns` were created for the same type.
-            unsafe {
-                if new_entity {
-                    component_fns.write(
-                        &mut ctx,
-                        rule_fns,
-                        params.entity_markers,
-                        &mut client_entity,
-                        cursor,
-                    )?;
-                } else {
-                    component_fns.consume_or_write(
-                        &mut ctx,
-                        rule_fns,
-                        params.entity_markers,
-                        params.command_markers,
-                        &mut client_entity,
-                        cursor,
-                    )?;
+            let mut apply = |cursor: &mut Cursor<&[u8]>| {
+                // SAFETY: `rule_fns` and `component_fns` were created for the same type.
+                unsafe {
+                    if new_entity {
+                        component_fns.write(
+                            &mut ctx,
+                            rule_fns,
+                            params.entity_markers,
+                            &mut client_entity,
+                            cursor,
+                        )
+                    } else {
+                        component_fns.consume_or_write(
+                            &mut ctx,
+                            rule_fns,
+                            params.entity_markers,
+                            params.command_markers,
+                            &mut client_entity,
+                            cursor,
+                        )
+                    }
                 }
+            };
+
+            if rule_fns.delta_compression() {
+                match params
+                    .server_baselines
+                    .read(cursor, server_entity, fns_id, message_tick)?
+                {
+                    Some(bytes) => apply(&mut Cursor::new(bytes))?,
+                    None => {
+                        acknowledge = false;
+                        continue;
+                    }
+                }
+            } else {
+                apply(cursor)?;
             }
 
             components_count += 1;
@@ -501,7 +802,43 @@ fn apply_update_components(
         params.queue.apply(world);
     }
 
-    Ok(())
+    Ok(acknowledge)
+}
+
+/// Skips outdated component updates of an entity.
+///
+/// Values with delta compression are still remembered because the server
+/// will use them as baselines after the acknowledgment.
+/// Returns `false` if some of them couldn't be reconstructed.
+fn skip_update_components(
+    ctx: &mut WriteCtx,
+    registry: &ReplicationRegistry,
+    server_baselines: &mut ServerBaselines,
+    cursor: &mut Cursor<&[u8]>,
+    data_size: usize,
+    server_entity: Entity,
+) -> bincode::Result<bool> {
+    let end_pos = cursor.position().saturating_add(data_size as u64);
+    if !registry.has_delta_compression() {
+        cursor.set_position(end_pos);
+        return Ok(true);
+    }
+
+    let mut acknowledge = true;
+    while cursor.position() < end_pos {
+        let fns_id = registry.read_fns_id(cursor)?;
+        let (component_fns, rule_fns) = registry.get(fns_id);
+        if rule_fns.delta_compression() {
+            acknowledge &= server_baselines
+                .read(cursor, server_entity, fns_id, ctx.message_tick)?
+                .is_some();
+        } else {
+            // SAFETY: `rule_fns` and `component_fns` were created for the same type.
+            unsafe { component_fns.consume(ctx, rule_fns, cursor)? };
+        }
+    }
+
+    Ok(acknowledge)
 }
 
 /// Deserializes `entity` from compressed index and generation.
@@ -509,17 +846,33 @@ fn apply_update_components(
 /// For details see
 /// [`ReplicationBuffer::write_entity`](crate::server::replication_message::replication_buffer::write_entity).
 fn deserialize_entity(cursor: &mut Cursor<&[u8]>) -> bincode::Result<Entity> {
-    let flagged_index: u64 = cursor.read_u64_varint()?;
+    let flagged_index = varint::read_u64(cursor)?;
     let has_generation = (flagged_index & 1) > 0;
     let generation = if has_generation {
-        cursor.read_u32_varint()? + 1
+        varint::read_u32(cursor)?
+            .checked_add(1)
+            .ok_or_else(invalid_entity)?
     } else {
         1u32
     };
+    let index: u32 = (flagged_index >> 1)
+        .try_into()
+        .map_err(|_| invalid_entity())?;
+
+    let bits = (generation as u64) << 32 | index as u64;
 
-    let bits = (generation as u64) << 32 | (flagged_index >> 1);
+    Entity::try_from_bits(bits).map_err(|_| invalid_entity())
+}
+
+fn invalid_entity() -> bincode::Error {
+    bincode::ErrorKind::Custom("received invalid entity".into()).into()
+}
 
-    Ok(Entity::from_bits(bits))
+fn despawned_entity(server_entity: Entity, client_entity: Entity) -> bincode::Error {
+    bincode::ErrorKind::Custom(format!(
+        "server's {server_entity:?} is mapped to despawned {client_entity:?}"
+    ))
+    .into()
 }
 
 /// Borrowed resources from the world and locals.
@@ -529,14 +882,15 @@ struct ReceiveParams<'a> {
     queue: &'a mut CommandQueue,
     entity_markers: &'a mut EntityMarkers,
     entity_map: &'a mut ServerEntityMap,
+    server_baselines: &'a mut ServerBaselines,
     stats: Option<&'a mut ClientStats>,
     command_markers: &'a CommandMarkers,
     registry: &'a ReplicationRegistry,
 }
 
-/// Type of components replication.
+/// Type of components or resources replication.
 ///
-/// Parameter for [`apply_components`].
+/// Parameter for [`apply_init_components`] and [`apply_resources`].
 enum ComponentsKind {
     Insert,
     Removal,
@@ -588,15 +942,88 @@ pub enum ClientSet {
     ///
     /// Runs in [`PreUpdate`] when the client just disconnected.
     ///
-    /// You may want to disable this set if you want to preserve client replication state across reconnects.
-    /// In that case, you need to manually repair the client state (or use something like
-    /// [`bevy_replicon_repair`](https://docs.rs/bevy_replicon_repair)).
+    /// To preserve client replication state across reconnects, add [`RepairPlugin`](repair::RepairPlugin).
+    /// Alternatively, you can disable this set and manually repair the client state.
     ///
     /// If this set is disabled and you don't want to repair client state, then you need to manually clean up
     /// the client after a disconnect or when reconnecting.
     Reset,
 }
 
+/// Result of the [`ProtocolHash`] check with the server.
+///
+/// Replication and server events are not applied until the server responds with a matching hash.
+///
+/// If [`ClientSet::Reset`] is disabled, then this needs to be reset manually.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolStatus {
+    /// The server hasn't responded yet.
+    #[default]
+    Pending,
+    /// The server has the same protocol.
+    Verified,
+    /// The server has a different protocol and will disconnect the client.
+    Mismatch { server_hash: ProtocolHash },
+}
+
+/// An event that indicates that a message from the server can't be applied.
+///
+/// Emitted instead of panicking, the rest of the message is skipped.
+/// See also [`ReceiveErrorPolicy`].
+#[derive(Event, Debug)]
+pub struct ReceiveError {
+    pub message: MessageKind,
+    pub error: bincode::Error,
+}
+
+/// Type of the message from [`ReceiveError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    /// Message from [`ReplicationChannel::Init`].
+    Init,
+    /// Message from [`ReplicationChannel::Update`].
+    Update,
+    /// Values corrected by the server from [`ReplicationChannel::Authority`].
+    Authority,
+    /// A server event.
+    Event {
+        /// Type name of the event.
+        event_name: &'static str,
+    },
+}
+
+/// Controls handling of [`ReceiveError`].
+///
+/// Insert this resource to change the policy.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReceiveErrorPolicy {
+    /// Log the error and continue.
+    Skip,
+    /// Log the error and request a disconnect via [`RepliconClient::disconnect`].
+    #[default]
+    Disconnect,
+    /// Panic in debug builds and behave like [`Self::Skip`] in release.
+    ///
+    /// Useful to catch mismatched serialization during development.
+    PanicInDebug,
+}
+
+/// Progress of receiving the initial world state from the server.
+///
+/// The server reports completion when all entities visible to the client have been sent.
+/// See also [`ConnectedClient::set_initial_sync_budget`](crate::server::connected_clients::ConnectedClient::set_initial_sync_budget)
+/// and [`initial_sync_complete`](crate::core::common_conditions::initial_sync_complete).
+///
+/// If [`ClientSet::Reset`] is disabled, then this needs to be reset manually.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitialSyncStatus {
+    /// The server is still sending the world.
+    #[default]
+    Pending,
+    /// All entities visible to the client have been received.
+    Complete,
+}
+
 /// Last received tick for init message from server.
 ///
 /// In other words, last [`RepliconTick`] with a removal, insertion, spawn or despawn.
@@ -635,6 +1062,9 @@ pub(super) struct BufferedUpdate {
     /// The tick this update corresponds to.
     message_tick: RepliconTick,
 
+    /// Index of the update for acknowledgment.
+    update_index: u16,
+
     /// Update data.
     message: Bytes,
 }
@@ -0,0 +1,519 @@
+use std::{
+    any,
+    fmt::{self, Debug, Formatter},
+    io::{Cursor, ErrorKind, Write},
+    marker::PhantomData,
+};
+
+use bevy::{ecs::entity::EntityHashMap, prelude::*, utils::HashMap};
+use bincode::{DefaultOptions, Options};
+use bytes::Bytes;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{
+    replicon_client::RepliconClient, server_entity_map::ServerEntityMap, ClientSet, MessageKind,
+    ReceiveError, ServerInitTick,
+};
+use crate::{
+    core::{
+        channels::ReplicationChannel,
+        command_markers::AppMarkerExt,
+        common_conditions::*,
+        ctx::{SerializeCtx, WriteCtx},
+        replication_registry::{
+            command_fns::{default_remove, default_write},
+            rule_fns::RuleFns,
+            FnsId, ReplicationRegistry,
+        },
+        replication_rules::{AppRuleExt, ReplicationRule, ReplicationRules},
+        ClientId,
+    },
+    server::{
+        authority::{self, ReceivedChanges},
+        ServerSet,
+    },
+};
+
+/// An extension trait for [`App`] for replicating components from clients with authority.
+pub trait AppAuthorityExt {
+    /// Same as [`AppRuleExt::replicate`], but also allows clients with [`Authority<C>`] to send changes of `C`.
+    ///
+    /// All changes are accepted.
+    /// See also [`Self::replicate_from_client_with`].
+    fn replicate_from_client<C>(&mut self) -> &mut Self
+    where
+        C: Component + Serialize + DeserializeOwned,
+    {
+        self.replicate_from_client_with(RuleFns::<C>::default(), accept_all::<C>)
+    }
+
+    /**
+    Same as [`Self::replicate_from_client`], but uses the specified functions for serialization
+    and validates each received change with `validate`.
+
+    Clients send changes over [`ReplicationChannel::Authority`] using the same `rule_fns` that
+    the server uses for replication. The server applies the validated value and replicates it
+    to other clients as usual. The owner ignores replicated values of the component after its insertion
+    and receives only values that the server clamped or rejected.
+
+    Entities inside components are not mapped, so components with entities are not supported.
+
+    # Examples
+
+    ```
+    use bevy::prelude::*;
+    use bevy_replicon::{
+        client::authority::{ValidateCtx, Validation},
+        core::replication_registry::rule_fns::RuleFns,
+        prelude::*,
+    };
+    use serde::{Deserialize, Serialize};
+
+    # let mut app = App::new();
+    # app.add_plugins(RepliconPlugins);
+    app.replicate_from_client_with(RuleFns::<Speed>::default(), validate_speed);
+
+    fn validate_speed(_ctx: &ValidateCtx, _current: &Speed, new: &Speed) -> Validation<Speed> {
+        const MAX_SPEED: f32 = 10.0;
+        if new.0.is_nan() {
+            Validation::Reject
+        } else if new.0 > MAX_SPEED {
+            Validation::Clamp(Speed(MAX_SPEED))
+        } else {
+            Validation::Accept
+        }
+    }
+
+    #[derive(Component, Deserialize, Serialize)]
+    struct Speed(f32);
+    ```
+    */
+    fn replicate_from_client_with<C: Component>(
+        &mut self,
+        rule_fns: RuleFns<C>,
+        validate: ValidateFn<C>,
+    ) -> &mut Self;
+}
+
+impl AppAuthorityExt for App {
+    fn replicate_from_client_with<C: Component>(
+        &mut self,
+        rule_fns: RuleFns<C>,
+        validate: ValidateFn<C>,
+    ) -> &mut Self {
+        let rule =
+            self.world_mut()
+                .resource_scope(|world, mut registry: Mut<ReplicationRegistry>| {
+                    registry.register_rule_fns(world, rule_fns)
+                });
+        let fns_id = rule.fns_id();
+
+        self.world_mut()
+            .resource_mut::<ReplicationRules>()
+            .insert(ReplicationRule::new(vec![rule]));
+        self.world_mut()
+            .resource_mut::<ReceivedChanges>()
+            .insert(fns_id, Vec::new());
+        self.world_mut()
+            .resource_mut::<ReceivedCorrections>()
+            .insert(fns_id, Vec::new());
+
+        self.replicate::<Authority<C>>()
+            .register_marker::<LocalAuthority<C>>()
+            .set_marker_fns::<LocalAuthority<C>, C>(write_owned::<C>, default_remove::<C>)
+            .insert_resource(AuthorityFns {
+                fns_id,
+                rule_fns,
+                validate,
+            })
+            .init_resource::<SentChanges<C>>()
+            .add_systems(
+                PreUpdate,
+                (
+                    authority::apply_changes::<C>
+                        .after(authority::receive)
+                        .in_set(ServerSet::Receive)
+                        .run_if(server_running),
+                    (
+                        update_local_authority::<C>,
+                        apply_corrections::<C>.after(ClientAuthorityPlugin::receive),
+                    )
+                        .after(ClientSet::Receive)
+                        .run_if(client_connected),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                send_changes::<C>
+                    .in_set(ClientSet::Send)
+                    .run_if(client_connected)
+                    .run_if(protocol_verified),
+            )
+    }
+}
+
+/// Replication of components from clients with [`Authority`].
+///
+/// Requires [`ClientPlugin`](super::ClientPlugin) for clients
+/// and [`ServerPlugin`](crate::server::ServerPlugin) for the server.
+pub struct ClientAuthorityPlugin;
+
+impl Plugin for ClientAuthorityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReceivedChanges>()
+            .init_resource::<ReceivedCorrections>()
+            .add_systems(
+                PreUpdate,
+                (
+                    authority::receive
+                        .in_set(ServerSet::Receive)
+                        .run_if(server_running),
+                    Self::receive
+                        .after(ClientSet::Receive)
+                        .run_if(client_connected),
+                ),
+            );
+    }
+}
+
+impl ClientAuthorityPlugin {
+    /// Drains corrections from the server and groups them by replication functions.
+    fn receive(
+        mut client: ResMut<RepliconClient>,
+        mut corrections: ResMut<ReceivedCorrections>,
+        mut errors: EventWriter<ReceiveError>,
+        registry: Res<ReplicationRegistry>,
+    ) {
+        for message in client.receive(ReplicationChannel::Authority) {
+            let mut cursor = Cursor::new(&*message);
+            let result = registry.read_fns_id(&mut cursor).and_then(|fns_id| {
+                corrections.get_mut(&fns_id).ok_or_else(|| {
+                    bincode::ErrorKind::Custom(
+                        "received replication functions ID that isn't registered for authority"
+                            .into(),
+                    )
+                    .into()
+                })
+            });
+
+            match result {
+                Ok(messages) => messages.push(message.slice(cursor.position() as usize..)),
+                Err(error) => {
+                    errors.send(ReceiveError {
+                        message: MessageKind::Authority,
+                        error,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Grants a client authority over component `C` on this entity.
+///
+/// Insert it on server. The component is replicated to clients, and the client with the matching
+/// [`ClientId`] sends changes of `C` to the server. The server ignores changes from other clients.
+///
+/// The owner keeps its local value of `C`, so changes of `C` made on server are not applied
+/// on the owner. Use [`Validation`] to correct its values.
+///
+/// Component `C` should be registered via [`AppAuthorityExt::replicate_from_client`].
+#[derive(Component, Deserialize, Serialize)]
+#[serde(bound = "")]
+pub struct Authority<C: Component> {
+    client_id: ClientId,
+    #[serde(skip)]
+    marker: PhantomData<C>,
+}
+
+impl<C: Component> Authority<C> {
+    /// Creates a new instance for the client.
+    pub fn new(client_id: ClientId) -> Self {
+        Self {
+            client_id,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the client with authority.
+    pub fn client_id(&self) -> ClientId {
+        self.client_id
+    }
+}
+
+impl<C: Component> Clone for Authority<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: Component> Copy for Authority<C> {}
+
+impl<C: Component> Debug for Authority<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Authority")
+            .field("client_id", &self.client_id)
+            .finish()
+    }
+}
+
+/// Context for [`ValidateFn`].
+#[non_exhaustive]
+pub struct ValidateCtx {
+    /// Client that sent the change.
+    pub client_id: ClientId,
+
+    /// Server entity on which the component changed.
+    pub entity: Entity,
+}
+
+/// Result of a [`ValidateFn`].
+pub enum Validation<C> {
+    /// Apply the received value.
+    Accept,
+    /// Apply the specified value instead of the received one.
+    ///
+    /// The value will be sent back to the client.
+    Clamp(C),
+    /// Keep the current value.
+    ///
+    /// The current value will be sent back to the client.
+    Reject,
+}
+
+/// Signature of validation functions for changes received from clients.
+///
+/// Accepts the current value on server and the received value.
+pub type ValidateFn<C> = fn(&ValidateCtx, &C, &C) -> Validation<C>;
+
+/// Default validation function that accepts all changes.
+pub fn accept_all<C: Component>(_ctx: &ValidateCtx, _current: &C, _new: &C) -> Validation<C> {
+    Validation::Accept
+}
+
+/// Functions for a component replicated from clients.
+#[derive(Resource)]
+pub(crate) struct AuthorityFns<C: Component> {
+    pub(crate) fns_id: FnsId,
+    pub(crate) rule_fns: RuleFns<C>,
+    pub(crate) validate: ValidateFn<C>,
+}
+
+/// Corrections received from the server grouped by replication functions.
+///
+/// Reused between frames to avoid allocations.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct ReceivedCorrections(HashMap<FnsId, Vec<Bytes>>);
+
+/// Serialized values of `C` that were last sent to the server or received as corrections.
+#[derive(Resource, Deref, DerefMut)]
+struct SentChanges<C>(#[deref] EntityHashMap<Vec<u8>>, PhantomData<C>);
+
+impl<C> Default for SentChanges<C> {
+    fn default() -> Self {
+        Self(Default::default(), PhantomData)
+    }
+}
+
+/// Marks entities on which this client has [`Authority<C>`].
+///
+/// Present only on client.
+#[derive(Component)]
+struct LocalAuthority<C>(PhantomData<C>);
+
+/// Inserts or removes [`LocalAuthority<C>`] according to received [`Authority<C>`].
+fn update_local_authority<C: Component>(
+    mut commands: Commands,
+    mut removed_authority: RemovedComponents<Authority<C>>,
+    client: Res<RepliconClient>,
+    authorities: Query<(Entity, &Authority<C>), Changed<Authority<C>>>,
+) {
+    for entity in removed_authority.read() {
+        if let Some(mut entity) = commands.get_entity(entity) {
+            entity.remove::<LocalAuthority<C>>();
+        }
+    }
+
+    for (entity, authority) in &authorities {
+        if Some(authority.client_id) == client.id() {
+            commands
+                .entity(entity)
+                .insert(LocalAuthority::<C>(PhantomData));
+        } else {
+            commands.entity(entity).remove::<LocalAuthority<C>>();
+        }
+    }
+}
+
+/// Ignores replicated values of `C` on entities owned by this client.
+///
+/// The server doesn't send values from the owner back, so the received values are outdated.
+/// The component is still inserted if missing. Values that the server clamped or rejected
+/// are received separately by [`apply_corrections`].
+fn write_owned<C: Component>(
+    ctx: &mut WriteCtx,
+    rule_fns: &RuleFns<C>,
+    entity: &mut EntityMut,
+    cursor: &mut Cursor<&[u8]>,
+) -> bincode::Result<()> {
+    if entity.contains::<C>() {
+        rule_fns.deserialize(ctx, cursor)?;
+        Ok(())
+    } else {
+        default_write(ctx, rule_fns, entity, cursor)
+    }
+}
+
+/// Applies values of `C` that the server clamped or rejected.
+fn apply_corrections<C: Component>(
+    mut commands: Commands,
+    mut corrections: ResMut<ReceivedCorrections>,
+    mut sent: ResMut<SentChanges<C>>,
+    mut entity_map: ResMut<ServerEntityMap>,
+    mut errors: EventWriter<ReceiveError>,
+    fns: Res<AuthorityFns<C>>,
+    init_tick: Res<ServerInitTick>,
+    mut components: Query<&mut C>,
+) {
+    let Some(messages) = corrections.get_mut(&fns.fns_id) else {
+        return;
+    };
+
+    for message in messages.drain(..) {
+        let mut cursor = Cursor::new(&*message);
+        while cursor.position() < message.len() as u64 {
+            let (server_entity, bytes) = match read_change(&mut cursor) {
+                Ok(change) => change,
+                Err(error) => {
+                    errors.send(ReceiveError {
+                        message: MessageKind::Authority,
+                        error,
+                    });
+                    break;
+                }
+            };
+
+            let Some(&entity) = entity_map.to_client().get(&server_entity) else {
+                debug!("ignoring correction for unknown {server_entity:?}");
+                continue;
+            };
+            let Ok(mut component) = components.get_mut(entity) else {
+                debug!("ignoring correction for {entity:?} without component");
+                continue;
+            };
+
+            let mut ctx = WriteCtx::new_unmapped(&mut commands, &mut entity_map, **init_tick);
+            match fns.rule_fns.deserialize(&mut ctx, &mut Cursor::new(bytes)) {
+                Ok(corrected) => {
+                    trace!(
+                        "applying corrected `{}` for {entity:?}",
+                        any::type_name::<C>()
+                    );
+                    *component = corrected;
+                    // Don't send the corrected value back.
+                    sent.insert(entity, bytes.to_vec());
+                }
+                Err(error) => {
+                    errors.send(ReceiveError {
+                        message: MessageKind::Authority,
+                        error,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Sends changes of components owned by this client.
+///
+/// Values are sent only if their serialized bytes differ from the last sent,
+/// so corrections received from the server aren't sent again.
+fn send_changes<C: Component>(
+    mut sent: ResMut<SentChanges<C>>,
+    mut client: ResMut<RepliconClient>,
+    mut removed_authority: RemovedComponents<Authority<C>>,
+    registry: Res<ReplicationRegistry>,
+    fns: Res<AuthorityFns<C>>,
+    entity_map: Res<ServerEntityMap>,
+    init_tick: Res<ServerInitTick>,
+    components: Query<(Entity, &C, Ref<Authority<C>>), Changed<C>>,
+) {
+    for entity in removed_authority.read() {
+        sent.remove(&entity);
+    }
+
+    let Some(client_id) = client.id() else {
+        return;
+    };
+
+    let ctx = SerializeCtx {
+        server_tick: **init_tick,
+    };
+    let mut message = Cursor::new(Vec::new());
+    for (entity, component, authority) in &components {
+        if authority.client_id != client_id {
+            continue;
+        }
+        let Some(&server_entity) = entity_map.to_server().get(&entity) else {
+            continue;
+        };
+
+        let mut bytes = Cursor::new(Vec::new());
+        if let Err(e) = fns.rule_fns.serialize(&ctx, component, &mut bytes) {
+            error!(
+                "unable to serialize `{}` for {entity:?}: {e}",
+                any::type_name::<C>()
+            );
+            continue;
+        }
+        let bytes = bytes.into_inner();
+
+        // Skip values that are just inserted by the server.
+        let changed = !authority.is_added() && sent.get(&entity) != Some(&bytes);
+        if changed {
+            if message.get_ref().is_empty() {
+                registry
+                    .write_fns_id(&mut message, fns.fns_id)
+                    .expect("writing into a vector should never fail");
+            }
+            write_change(&mut message, server_entity, &bytes)
+                .expect("writing into a vector should never fail");
+        }
+
+        sent.insert(entity, bytes);
+    }
+
+    if !message.get_ref().is_empty() {
+        trace!("sending changes of `{}`", any::type_name::<C>());
+        client.send(ReplicationChannel::Authority, message.into_inner());
+    }
+}
+
+/// Writes server entity and serialized component.
+pub(crate) fn write_change(
+    cursor: &mut Cursor<Vec<u8>>,
+    entity: Entity,
+    bytes: &[u8],
+) -> bincode::Result<()> {
+    DefaultOptions::new().serialize_into(&mut *cursor, &entity)?;
+    DefaultOptions::new().serialize_into(&mut *cursor, &bytes.len())?;
+    cursor.write_all(bytes)?;
+    Ok(())
+}
+
+/// Reads data written by [`write_change`].
+pub(crate) fn read_change<'a>(
+    cursor: &mut Cursor<&'a [u8]>,
+) -> bincode::Result<(Entity, &'a [u8])> {
+    let entity = DefaultOptions::new().deserialize_from(&mut *cursor)?;
+    let len: usize = DefaultOptions::new().deserialize_from(&mut *cursor)?;
+    let start = cursor.position() as usize;
+    let end = start.saturating_add(len);
+    let bytes = cursor
+        .get_ref()
+        .get(start..end)
+        .ok_or_else(|| Box::new(bincode::ErrorKind::Io(ErrorKind::UnexpectedEof.into())))?;
+    cursor.set_position(end as u64);
+
+    Ok((entity, bytes))
+}
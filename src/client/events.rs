@@ -1,12 +1,17 @@
 mod event_data;
 
-use std::io::Cursor;
+use std::{
+    any::{self, TypeId},
+    hash::{Hash, Hasher},
+    io::Cursor,
+};
 
 use bevy::{
     ecs::{entity::MapEntities, event::ManualEventReader},
     prelude::*,
 };
 use bincode::{DefaultOptions, Options};
+use bytes::Bytes;
 use serde::{de::DeserializeOwned, Serialize};
 
 use super::{replicon_client::RepliconClient, server_entity_map::ServerEntityMap, ClientSet};
@@ -15,9 +20,16 @@ use crate::{
         channels::{RepliconChannel, RepliconChannels},
         common_conditions::*,
         ctx::{ClientSendCtx, ServerReceiveCtx},
+        protocol::StableIds,
         ClientId,
     },
-    server::{replicon_server::RepliconServer, ServerSet},
+    server::{
+        connected_clients::ConnectedClients,
+        event_limit::{EventLimit, EventLimitExceeded, EventLimiter},
+        replicon_server::RepliconServer,
+        server_tick::ServerTick,
+        ServerSet,
+    },
 };
 use event_data::ClientEventData;
 
@@ -115,6 +127,41 @@ pub trait ClientEventAppExt {
         serialize: SerializeFn<E>,
         deserialize: DeserializeFn<E>,
     ) -> &mut Self;
+
+    /**
+    Applies quotas to a registered client event `E` on server.
+
+    Messages from a client over the quotas are dropped without deserialization.
+    Events sent locally in listen-server mode are not limited.
+
+    # Panics
+
+    Panics if `E` wasn't registered as a client event.
+
+    # Examples
+
+    ```
+    use bevy::prelude::*;
+    use bevy_replicon::{
+        server::event_limit::{EventLimit, LimitAction},
+        prelude::*,
+    };
+    use serde::{Deserialize, Serialize};
+
+    # let mut app = App::new();
+    # app.add_plugins((MinimalPlugins, RepliconPlugins));
+    app.add_client_event::<Chat>(ChannelKind::Ordered)
+        .limit_client_event::<Chat>(EventLimit {
+            events_per_second: Some(5),
+            bytes_per_tick: Some(1024),
+            action: LimitAction::DropAndEmit,
+        });
+
+    #[derive(Deserialize, Event, Serialize)]
+    struct Chat(String);
+    ```
+    */
+    fn limit_client_event<E: Event>(&mut self, limit: EventLimit) -> &mut Self;
 }
 
 impl ClientEventAppExt for App {
@@ -128,20 +175,49 @@ impl ClientEventAppExt for App {
             .add_event::<FromClient<E>>()
             .init_resource::<ClientEventReader<E>>();
 
-        let channel_id = self
-            .world_mut()
-            .resource_mut::<RepliconChannels>()
-            .create_client_channel(channel.into());
-
         self.world_mut()
             .resource_scope(|world, mut event_registry: Mut<ClientEventRegistry>| {
-                event_registry.0.push(ClientEventData::new(
+                let mut channels = world.resource_mut::<RepliconChannels>();
+                let channel = channel.into();
+                let (channel_id, stable_id) = if event_registry.shared_channels.is_empty() {
+                    (channels.create_client_channel(channel), None)
+                } else {
+                    let channel_id = channels.shared_client_channel(channel.kind);
+                    let index = event_registry.events.len();
+                    let stable_id =
+                        event_registry
+                            .stable_ids
+                            .register(any::type_name::<E>(), 0, index);
+                    (channel_id, Some(stable_id))
+                };
+
+                event_registry.events.push(ClientEventData::new(
                     world.components(),
                     channel_id,
+                    stable_id,
                     serialize,
                     deserialize,
                 ));
+                event_registry.received.push(Default::default());
+                event_registry.limiters.push(None);
+            });
+
+        self
+    }
+
+    fn limit_client_event<E: Event>(&mut self, limit: EventLimit) -> &mut Self {
+        let mut event_registry = self.world_mut().resource_mut::<ClientEventRegistry>();
+        let index = event_registry
+            .events
+            .iter()
+            .position(|event_data| event_data.type_id() == TypeId::of::<E>())
+            .unwrap_or_else(|| {
+                panic!(
+                    "event `{}` should be previously registered as a client event",
+                    any::type_name::<E>()
+                )
             });
+        event_registry.limiters[index] = Some(EventLimiter::new(limit));
 
         self
     }
@@ -156,6 +232,7 @@ pub struct ClientEventsPlugin;
 impl Plugin for ClientEventsPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ClientEventRegistry>()
+            .add_event::<EventLimitExceeded>()
             .add_systems(
                 PreUpdate,
                 (
@@ -189,7 +266,7 @@ impl ClientEventsPlugin {
                         };
 
                         let world_cell = world.as_unsafe_world_cell();
-                        for event_data in &event_registry.0 {
+                        for event_data in &event_registry.events {
                             // SAFETY: both resources mutably borrowed uniquely.
                             let (events, reader) = unsafe {
                                 let events = world_cell
@@ -220,21 +297,36 @@ impl ClientEventsPlugin {
     fn receive(world: &mut World) {
         world.resource_scope(|world, mut server: Mut<RepliconServer>| {
             world.resource_scope(|world, registry: Mut<AppTypeRegistry>| {
-                world.resource_scope(|world, event_registry: Mut<ClientEventRegistry>| {
+                world.resource_scope(|world, mut event_registry: Mut<ClientEventRegistry>| {
                     let mut ctx = ServerReceiveCtx {
                         registry: &registry.read(),
                     };
 
-                    for event_data in &event_registry.0 {
+                    let event_registry = &mut *event_registry;
+                    event_registry.read_messages(&mut server);
+                    event_registry.apply_limits(world);
+
+                    let mut violations = Vec::new();
+                    for (event_data, messages) in event_registry
+                        .events
+                        .iter()
+                        .zip(&mut event_registry.received)
+                    {
                         let client_events = world
                             .get_resource_mut_by_id(event_data.client_events_id())
                             .expect("client events shouldn't be removed");
 
                         // SAFETY: passed pointer was obtained using this event data.
                         unsafe {
-                            event_data.receive(&mut ctx, client_events.into_inner(), &mut server)
+                            event_data.receive(
+                                &mut ctx,
+                                client_events.into_inner(),
+                                messages,
+                                &mut violations,
+                            )
                         };
                     }
+                    world.send_event_batch(violations);
                 });
             });
         });
@@ -243,7 +335,7 @@ impl ClientEventsPlugin {
     fn resend_locally(world: &mut World) {
         world.resource_scope(|world, event_registry: Mut<ClientEventRegistry>| {
             let world_cell = world.as_unsafe_world_cell();
-            for event_data in &event_registry.0 {
+            for event_data in &event_registry.events {
                 // SAFETY: both resources mutably borrowed uniquely.
                 let (client_events, events) = unsafe {
                     let client_events = world_cell
@@ -265,7 +357,7 @@ impl ClientEventsPlugin {
 
     fn reset(world: &mut World) {
         world.resource_scope(|world, event_registry: Mut<ClientEventRegistry>| {
-            for event_data in &event_registry.0 {
+            for event_data in &event_registry.events {
                 let events = world
                     .get_resource_mut_by_id(event_data.events_id())
                     .expect("events shouldn't be removed");
@@ -278,8 +370,106 @@ impl ClientEventsPlugin {
 }
 
 /// Registered client events.
-#[derive(Resource, Default)]
-struct ClientEventRegistry(Vec<ClientEventData>);
+#[derive(Resource)]
+pub(crate) struct ClientEventRegistry {
+    events: Vec<ClientEventData>,
+
+    /// Channels shared by all events.
+    ///
+    /// Empty unless [`IdPolicy::TypeHash`](crate::core::IdPolicy::TypeHash) is used.
+    shared_channels: Vec<u8>,
+
+    /// Stable IDs for [`Self::events`] if [`Self::shared_channels`] are used.
+    stable_ids: StableIds,
+
+    /// Received messages for each element in [`Self::events`].
+    ///
+    /// Reused between frames to avoid allocations.
+    received: Vec<Vec<(ClientId, Bytes)>>,
+
+    /// Quotas for each element in [`Self::events`].
+    limiters: Vec<Option<EventLimiter>>,
+}
+
+impl ClientEventRegistry {
+    /// Drains received messages and groups them by events.
+    fn read_messages(&mut self, server: &mut RepliconServer) {
+        if self.shared_channels.is_empty() {
+            for (event_data, messages) in self.events.iter().zip(&mut self.received) {
+                messages.extend(server.receive(event_data.channel_id()));
+            }
+        } else {
+            for &channel_id in &self.shared_channels {
+                for (client_id, message) in server.receive(channel_id) {
+                    match self.stable_ids.split_message(&message) {
+                        Some((index, message)) => self.received[index].push((client_id, message)),
+                        None => debug!("ignoring event with unknown ID from {client_id:?}"),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Drops received messages that exceed limits and performs the configured actions.
+    fn apply_limits(&mut self, world: &mut World) {
+        let elapsed = world.resource::<Time>().elapsed();
+        let tick = **world.resource::<ServerTick>();
+        let connected_clients = world.resource::<ConnectedClients>();
+        let mut exceeded = Vec::new();
+        for ((event_data, messages), limiter) in self
+            .events
+            .iter()
+            .zip(&mut self.received)
+            .zip(&mut self.limiters)
+        {
+            let Some(limiter) = limiter else {
+                continue;
+            };
+
+            limiter.retain_clients(|client_id| connected_clients.get_client(client_id).is_some());
+            limiter.apply(
+                event_data.type_name(),
+                messages,
+                elapsed,
+                tick,
+                &mut exceeded,
+            );
+        }
+
+        world.send_event_batch(exceeded);
+    }
+
+    /// Hashes names of registered events and their channels.
+    ///
+    /// Events registered with stable IDs are ignored because
+    /// unknown events are skipped during receiving.
+    ///
+    /// See also [`ProtocolHash`](crate::core::protocol::ProtocolHash).
+    pub(crate) fn hash_events(&self, hasher: &mut impl Hasher) {
+        if !self.shared_channels.is_empty() {
+            return;
+        }
+
+        self.events.len().hash(hasher);
+        for event_data in &self.events {
+            event_data.type_name().hash(hasher);
+            event_data.channel_id().hash(hasher);
+        }
+    }
+}
+
+impl FromWorld for ClientEventRegistry {
+    fn from_world(world: &mut World) -> Self {
+        let channels = world.resource::<RepliconChannels>();
+        Self {
+            events: Default::default(),
+            shared_channels: channels.shared_client_channels().to_vec(),
+            stable_ids: Default::default(),
+            received: Default::default(),
+            limiters: Default::default(),
+        }
+    }
+}
 
 /// Tracks read events for [`ClientEventPlugin::send`].
 ///
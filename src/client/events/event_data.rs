@@ -1,6 +1,6 @@
 use std::{
     any::{self, TypeId},
-    io::Cursor,
+    io::{Cursor, Write},
     mem,
 };
 
@@ -9,6 +9,7 @@ use bevy::{
     prelude::*,
     ptr::{Ptr, PtrMut},
 };
+use bytes::Bytes;
 
 use super::{ClientEventReader, DeserializeFn, FromClient, SerializeFn};
 use crate::{
@@ -17,7 +18,7 @@ use crate::{
         ctx::{ClientSendCtx, ServerReceiveCtx},
         ClientId,
     },
-    server::replicon_server::RepliconServer,
+    server::{ProtocolViolation, ViolationKind},
 };
 
 /// Type-erased functions and metadata for a registered client event.
@@ -39,6 +40,9 @@ pub(super) struct ClientEventData {
     /// Used channel.
     channel_id: u8,
 
+    /// ID written before each message for [`IdPolicy::TypeHash`](crate::core::IdPolicy::TypeHash).
+    stable_id: Option<u32>,
+
     send: SendFn,
     receive: ReceiveFn,
     resend_locally: ResendLocallyFn,
@@ -51,6 +55,7 @@ impl ClientEventData {
     pub(super) fn new<E: Event>(
         components: &Components,
         channel_id: u8,
+        stable_id: Option<u32>,
         serialize: SerializeFn<E>,
         deserialize: DeserializeFn<E>,
     ) -> Self {
@@ -85,6 +90,7 @@ impl ClientEventData {
             reader_id,
             client_events_id,
             channel_id,
+            stable_id,
             send: send::<E>,
             receive: receive::<E>,
             resend_locally: resend_locally::<E>,
@@ -94,6 +100,18 @@ impl ClientEventData {
         }
     }
 
+    pub(super) fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub(super) fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub(super) fn channel_id(&self) -> u8 {
+        self.channel_id
+    }
+
     pub(super) fn events_id(&self) -> ComponentId {
         self.events_id
     }
@@ -122,7 +140,9 @@ impl ClientEventData {
         (self.send)(self, ctx, events, reader, client);
     }
 
-    /// Receives an event from a client.
+    /// Receives events from clients by draining `messages`.
+    ///
+    /// Messages that can't be deserialized are reported to `violations`.
     ///
     /// # Safety
     ///
@@ -132,9 +152,10 @@ impl ClientEventData {
         &self,
         ctx: &mut ServerReceiveCtx,
         client_events: PtrMut,
-        server: &mut RepliconServer,
+        messages: &mut Vec<(ClientId, Bytes)>,
+        violations: &mut Vec<ProtocolViolation>,
     ) {
-        (self.receive)(self, ctx, client_events, server);
+        (self.receive)(self, ctx, client_events, messages, violations);
     }
 
     /// Drains events `E` and re-emits them as [`FromClient<E>`].
@@ -188,6 +209,15 @@ impl ClientEventData {
         (deserialize)(ctx, cursor)
     }
 
+    /// Writes the stable ID if the event was registered with it.
+    fn write_stable_id(&self, writer: impl Write) -> bincode::Result<()> {
+        if let Some(stable_id) = self.stable_id {
+            bincode::serialize_into(writer, &stable_id)?;
+        }
+
+        Ok(())
+    }
+
     fn check_type<C: Event>(&self) {
         debug_assert_eq!(
             self.type_id,
@@ -203,7 +233,13 @@ impl ClientEventData {
 type SendFn = unsafe fn(&ClientEventData, &mut ClientSendCtx, &Ptr, PtrMut, &mut RepliconClient);
 
 /// Signature of client event receiving functions.
-type ReceiveFn = unsafe fn(&ClientEventData, &mut ServerReceiveCtx, PtrMut, &mut RepliconServer);
+type ReceiveFn = unsafe fn(
+    &ClientEventData,
+    &mut ServerReceiveCtx,
+    PtrMut,
+    &mut Vec<(ClientId, Bytes)>,
+    &mut Vec<ProtocolViolation>,
+);
 
 /// Signature of client event resending functions.
 type ResendLocallyFn = unsafe fn(PtrMut, PtrMut);
@@ -228,7 +264,8 @@ unsafe fn send<E: Event>(
     for event in reader.read(events.deref()) {
         let mut cursor = Default::default();
         event_data
-            .serialize::<E>(ctx, event, &mut cursor)
+            .write_stable_id(&mut cursor)
+            .and_then(|_| event_data.serialize::<E>(ctx, event, &mut cursor))
             .expect("client event should be serializable");
 
         trace!("sending event `{}`", any::type_name::<E>());
@@ -246,10 +283,11 @@ unsafe fn receive<E: Event>(
     event_data: &ClientEventData,
     ctx: &mut ServerReceiveCtx,
     events: PtrMut,
-    server: &mut RepliconServer,
+    messages: &mut Vec<(ClientId, Bytes)>,
+    violations: &mut Vec<ProtocolViolation>,
 ) {
     let events: &mut Events<FromClient<E>> = events.deref_mut();
-    for (client_id, message) in server.receive(event_data.channel_id) {
+    for (client_id, message) in messages.drain(..) {
         let mut cursor = Cursor::new(&*message);
         match event_data.deserialize::<E>(ctx, &mut cursor) {
             Ok(event) => {
@@ -259,7 +297,13 @@ unsafe fn receive<E: Event>(
                 );
                 events.send(FromClient { client_id, event });
             }
-            Err(e) => debug!("unable to deserialize event from {client_id:?}: {e}"),
+            Err(error) => violations.push(ProtocolViolation {
+                client_id,
+                kind: ViolationKind::InvalidEvent {
+                    event_name: any::type_name::<E>(),
+                    error,
+                },
+            }),
         }
     }
 }
@@ -0,0 +1,440 @@
+use std::{collections::VecDeque, io::Cursor};
+
+use bevy::prelude::*;
+
+use super::{confirm_history::ConfirmHistory, ClientSet, ServerInitTick};
+use crate::core::{
+    command_markers::{AppMarkerExt, MarkerConfig},
+    common_conditions::client_connected,
+    ctx::{RemoveCtx, WriteCtx},
+    replication_registry::rule_fns::RuleFns,
+    replicon_tick::RepliconTick,
+};
+
+/// An extension trait for [`App`] for registering interpolated components.
+pub trait AppInterpolationExt {
+    /// Enables snapshot interpolation for component `C` on entities with [`Interpolated`].
+    ///
+    /// Received values of `C` won't be written into the component directly.
+    /// Instead, they will be buffered in [`SnapshotBuffer<C>`] and the component will be
+    /// updated every frame with a value interpolated for [`InterpolationTime::render_tick`].
+    ///
+    /// The component should be registered for replication separately.
+    /// Requires [`InterpolationPlugin`].
+    fn interpolate<C: Component + Interpolate + Clone>(&mut self) -> &mut Self;
+}
+
+impl AppInterpolationExt for App {
+    fn interpolate<C: Component + Interpolate + Clone>(&mut self) -> &mut Self {
+        self.set_marker_fns::<Interpolated, C>(write_snapshot::<C>, remove_interpolated::<C>)
+            .add_systems(
+                PreUpdate,
+                interpolate::<C>
+                    .after(InterpolationPlugin::update_time)
+                    .run_if(client_connected),
+            )
+    }
+}
+
+/**
+Snapshot interpolation for replicated components.
+
+The plugin estimates the current server tick from received ticks and elapsed time and
+renders entities with [`Interpolated`] at [`Self::delay`] ticks behind it. Values of components
+registered via [`AppInterpolationExt::interpolate`] are buffered per tick and blended
+between the two snapshots around the render time using the [`Interpolate`] trait.
+
+Since values are buffered, late updates are also accepted. So the delay should cover
+the tick interval and the expected jitter of the connection.
+
+# Examples
+
+```
+use bevy::prelude::*;
+use bevy_replicon::{client::interpolation::*, prelude::*};
+
+# let mut app = App::new();
+app.add_plugins((
+    MinimalPlugins,
+    RepliconPlugins.set(ServerPlugin {
+        tick_policy: TickPolicy::MaxTickRate(20),
+        ..Default::default()
+    }),
+    InterpolationPlugin {
+        tick_rate: 20,
+        ..Default::default()
+    },
+))
+.replicate::<Transform>()
+.interpolate::<Transform>();
+```
+**/
+pub struct InterpolationPlugin {
+    /// Expected server ticks per second.
+    ///
+    /// Used to advance the estimated server tick between received updates.
+    /// Should match [`TickPolicy::MaxTickRate`](crate::server::TickPolicy::MaxTickRate) on server.
+    pub tick_rate: u16,
+
+    /// Number of ticks behind the estimated server tick at which entities are rendered.
+    ///
+    /// Can be fractional.
+    pub delay: f32,
+}
+
+impl Default for InterpolationPlugin {
+    fn default() -> Self {
+        Self {
+            tick_rate: 30,
+            delay: 2.0,
+        }
+    }
+}
+
+impl Plugin for InterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        assert!(self.tick_rate > 0, "tick rate should be positive");
+        assert!(self.delay >= 0.0, "delay can't be negative");
+
+        app.insert_resource(InterpolationTime::new(self.tick_rate, self.delay))
+            .register_marker_with::<Interpolated>(MarkerConfig {
+                need_history: true,
+                ..Default::default()
+            })
+            .add_systems(
+                PreUpdate,
+                (
+                    Self::update_time
+                        .after(ClientSet::Receive)
+                        .run_if(client_connected),
+                    Self::reset.in_set(ClientSet::Reset),
+                ),
+            );
+    }
+}
+
+impl InterpolationPlugin {
+    fn update_time(
+        time: Res<Time>,
+        init_tick: Res<ServerInitTick>,
+        entities: Query<&ConfirmHistory, (With<Interpolated>, Changed<ConfirmHistory>)>,
+        mut interpolation_time: ResMut<InterpolationTime>,
+    ) {
+        interpolation_time.receive(**init_tick);
+        for history in &entities {
+            interpolation_time.receive(history.last_tick());
+        }
+
+        interpolation_time.advance(time.delta_seconds());
+    }
+
+    fn reset(mut interpolation_time: ResMut<InterpolationTime>) {
+        interpolation_time.reset();
+    }
+}
+
+/// Estimated server time and the render time for interpolated entities.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct InterpolationTime {
+    tick_rate: f32,
+    delay: f32,
+
+    /// Last tick received from server.
+    received_tick: RepliconTick,
+
+    /// Estimated current server tick.
+    estimated_tick: RepliconTick,
+
+    /// Fraction of a tick elapsed since [`Self::estimated_tick`].
+    estimated_overstep: f32,
+
+    /// Tick at which entities are rendered.
+    render_tick: RepliconTick,
+
+    /// Fraction of a tick between [`Self::render_tick`] and the next tick.
+    render_overstep: f32,
+}
+
+impl InterpolationTime {
+    fn new(tick_rate: u16, delay: f32) -> Self {
+        Self {
+            tick_rate: tick_rate as f32,
+            delay,
+            received_tick: Default::default(),
+            estimated_tick: Default::default(),
+            estimated_overstep: 0.0,
+            render_tick: Default::default(),
+            render_overstep: 0.0,
+        }
+    }
+
+    /// Returns the estimated current server tick.
+    pub fn estimated_tick(&self) -> RepliconTick {
+        self.estimated_tick
+    }
+
+    /// Returns the tick at which interpolated entities are rendered.
+    ///
+    /// See also [`Self::render_overstep`].
+    pub fn render_tick(&self) -> RepliconTick {
+        self.render_tick
+    }
+
+    /// Returns the fraction of a tick in range `[0, 1)` between [`Self::render_tick`] and the next tick.
+    pub fn render_overstep(&self) -> f32 {
+        self.render_overstep
+    }
+
+    /// Updates the last received tick if the given tick is newer.
+    fn receive(&mut self, tick: RepliconTick) {
+        if tick > self.received_tick {
+            self.received_tick = tick;
+        }
+    }
+
+    /// Advances the estimated server tick by elapsed time and updates the render time.
+    ///
+    /// The estimate is snapped to the received tick if the server is ahead, and limited by
+    /// the delay if no updates arrive, to avoid rendering too far beyond received data.
+    fn advance(&mut self, delta_secs: f32) {
+        let received_tick = self.received_tick;
+        self.estimated_overstep += delta_secs * self.tick_rate;
+        let elapsed_ticks = self.estimated_overstep.floor();
+        self.estimated_tick += elapsed_ticks as u32;
+        self.estimated_overstep -= elapsed_ticks;
+
+        let max_ahead = self.delay.ceil() as u32;
+        if received_tick > self.estimated_tick {
+            trace!("snapping estimated tick to received {received_tick:?}");
+            self.estimated_tick = received_tick;
+            self.estimated_overstep = 0.0;
+        } else if self.estimated_tick - received_tick > max_ahead {
+            self.estimated_tick = received_tick + max_ahead;
+            self.estimated_overstep = 0.0;
+        }
+
+        let offset = self.estimated_overstep - self.delay;
+        let whole_offset = offset.floor();
+        self.render_tick = self.estimated_tick - (-whole_offset) as u32;
+        self.render_overstep = offset - whole_offset;
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.tick_rate as u16, self.delay);
+    }
+}
+
+/// Marks entity for snapshot interpolation.
+///
+/// Components registered via [`AppInterpolationExt::interpolate`] on entities with this marker
+/// will be buffered in [`SnapshotBuffer`] and interpolated every frame.
+///
+/// Insert it on client, for example, when an entity is received.
+#[derive(Component, Default, Debug, Clone, Copy)]
+pub struct Interpolated;
+
+/// Linear interpolation between two values.
+///
+/// Implement it for a component to use it with [`AppInterpolationExt::interpolate`].
+pub trait Interpolate {
+    /// Returns a value between `self` and `other` at `t` in range `[0, 1]`.
+    fn interpolate(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Interpolate for Vec2 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.lerp(*other, t)
+    }
+}
+
+impl Interpolate for Vec3 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.lerp(*other, t)
+    }
+}
+
+impl Interpolate for Quat {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.slerp(*other, t)
+    }
+}
+
+impl Interpolate for Transform {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self {
+            translation: self.translation.interpolate(&other.translation, t),
+            rotation: self.rotation.interpolate(&other.rotation, t),
+            scale: self.scale.interpolate(&other.scale, t),
+        }
+    }
+}
+
+/// Received values of an interpolated component sorted by their ticks.
+///
+/// Present only on client.
+#[derive(Component, Debug)]
+pub struct SnapshotBuffer<C>(VecDeque<(RepliconTick, C)>);
+
+impl<C: Interpolate + Clone> SnapshotBuffer<C> {
+    fn new(tick: RepliconTick, value: C) -> Self {
+        Self([(tick, value)].into())
+    }
+
+    /// Returns buffered snapshots sorted by their ticks.
+    pub fn snapshots(&self) -> &VecDeque<(RepliconTick, C)> {
+        &self.0
+    }
+
+    /// Inserts a value received for a tick, replacing the existing value for it.
+    ///
+    /// Drops the oldest snapshot if the buffer is full.
+    fn insert(&mut self, tick: RepliconTick, value: C) {
+        let index = self.0.partition_point(|&(other_tick, _)| other_tick < tick);
+        match self.0.get_mut(index) {
+            Some((other_tick, other_value)) if *other_tick == tick => *other_value = value,
+            _ => self.0.insert(index, (tick, value)),
+        }
+
+        if self.0.len() > MAX_SNAPSHOTS {
+            self.0.pop_front();
+        }
+    }
+
+    /// Removes snapshots that are no longer needed for rendering at the given tick.
+    ///
+    /// Keeps the last snapshot before the tick to interpolate from it.
+    fn prune(&mut self, render_tick: RepliconTick) {
+        while self.0.get(1).is_some_and(|&(tick, _)| tick <= render_tick) {
+            self.0.pop_front();
+        }
+    }
+
+    /// Returns a value interpolated between snapshots around the given render time.
+    ///
+    /// Returns the closest snapshot if the time is outside of the buffered range.
+    fn sample(&self, render_tick: RepliconTick, overstep: f32) -> Option<C> {
+        let index = self.0.partition_point(|&(tick, _)| tick <= render_tick);
+        if index == 0 {
+            return self.0.front().map(|(_, value)| value.clone());
+        }
+
+        let (from_tick, from) = &self.0[index - 1];
+        let Some((to_tick, to)) = self.0.get(index) else {
+            return Some(from.clone());
+        };
+
+        let t = ((render_tick - *from_tick) as f32 + overstep) / (*to_tick - *from_tick) as f32;
+        Some(from.interpolate(to, t))
+    }
+}
+
+/// Maximum number of buffered snapshots per component.
+///
+/// Matches the number of ticks tracked by [`ConfirmHistory`].
+const MAX_SNAPSHOTS: usize = u64::BITS as usize;
+
+/// Buffers received value in [`SnapshotBuffer<C>`] instead of writing it into the component.
+///
+/// Inserts the component with the received value if it's the first value.
+fn write_snapshot<C: Component + Interpolate + Clone>(
+    ctx: &mut WriteCtx,
+    rule_fns: &RuleFns<C>,
+    entity: &mut EntityMut,
+    cursor: &mut Cursor<&[u8]>,
+) -> bincode::Result<()> {
+    let component: C = rule_fns.deserialize(ctx, cursor)?;
+    if let Some(mut buffer) = entity.get_mut::<SnapshotBuffer<C>>() {
+        buffer.insert(ctx.message_tick, component);
+    } else {
+        let buffer = SnapshotBuffer::new(ctx.message_tick, component.clone());
+        ctx.commands.entity(entity.id()).insert((component, buffer));
+    }
+
+    Ok(())
+}
+
+/// Removes component `C` and its snapshots.
+fn remove_interpolated<C: Component>(ctx: &mut RemoveCtx, entity: &mut EntityMut) {
+    ctx.commands
+        .entity(entity.id())
+        .remove::<(C, SnapshotBuffer<C>)>();
+}
+
+fn interpolate<C: Component + Interpolate + Clone>(
+    interpolation_time: Res<InterpolationTime>,
+    mut entities: Query<(&mut C, &mut SnapshotBuffer<C>), With<Interpolated>>,
+) {
+    let render_tick = interpolation_time.render_tick();
+    let overstep = interpolation_time.render_overstep();
+    for (mut component, mut buffer) in &mut entities {
+        buffer.prune(render_tick);
+        if let Some(value) = buffer.sample(render_tick, overstep) {
+            *component = value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sampling() {
+        let mut buffer = SnapshotBuffer::new(RepliconTick::new(2), 0.0);
+        buffer.insert(RepliconTick::new(6), 8.0);
+        buffer.insert(RepliconTick::new(4), 4.0);
+
+        let ticks: Vec<_> = buffer
+            .snapshots()
+            .iter()
+            .map(|&(tick, _)| tick.get())
+            .collect();
+        assert_eq!(ticks, [2, 4, 6], "snapshots should be sorted");
+
+        assert_eq!(buffer.sample(RepliconTick::new(1), 0.5), Some(0.0));
+        assert_eq!(buffer.sample(RepliconTick::new(3), 0.0), Some(2.0));
+        assert_eq!(buffer.sample(RepliconTick::new(4), 0.5), Some(5.0));
+        assert_eq!(buffer.sample(RepliconTick::new(7), 0.0), Some(8.0));
+
+        buffer.prune(RepliconTick::new(5));
+        assert_eq!(buffer.snapshots().len(), 2);
+        assert_eq!(buffer.sample(RepliconTick::new(5), 0.0), Some(6.0));
+    }
+
+    #[test]
+    fn time() {
+        let mut time = InterpolationTime::new(10, 1.5);
+
+        time.receive(RepliconTick::new(10));
+        time.advance(0.0);
+        assert_eq!(time.estimated_tick(), RepliconTick::new(10));
+        assert_eq!(time.render_tick(), RepliconTick::new(8));
+        assert_eq!(time.render_overstep(), 0.5);
+
+        time.advance(0.15);
+        assert_eq!(time.estimated_tick(), RepliconTick::new(11));
+        assert_eq!(time.render_tick(), RepliconTick::new(10));
+        assert!((time.render_overstep() - 0.0).abs() < 1e-4);
+
+        time.advance(1.0);
+        assert_eq!(
+            time.estimated_tick(),
+            RepliconTick::new(12),
+            "estimate should be limited by the delay"
+        );
+
+        time.receive(RepliconTick::new(20));
+        time.advance(0.0);
+        assert_eq!(
+            time.estimated_tick(),
+            RepliconTick::new(20),
+            "estimate should snap to the received tick"
+        );
+    }
+}
@@ -0,0 +1,476 @@
+use std::{collections::VecDeque, io::Cursor, time::Duration};
+
+use bevy::{ecs::schedule::ScheduleLabel, prelude::*};
+
+use super::{confirm_history::ConfirmHistory, ClientSet};
+use crate::core::{
+    command_markers::AppMarkerExt,
+    common_conditions::client_connected,
+    ctx::{RemoveCtx, WriteCtx},
+    replication_registry::rule_fns::RuleFns,
+    replicon_tick::RepliconTick,
+};
+use crate::server::TickPolicy;
+
+/// An extension trait for [`App`] for registering predicted components.
+pub trait AppPredictionExt {
+    /// Enables prediction for component `C` on entities with [`Predicted`].
+    ///
+    /// Received values of `C` won't be written into the component directly.
+    /// Instead, they will be stored as confirmed values in [`PredictionHistory<C>`]
+    /// and compared against predictions once the server confirms the tick for the entity.
+    ///
+    /// The component should be registered for replication separately.
+    /// Requires [`PredictionPlugin`].
+    fn predict<C: Component + Clone + PartialEq>(&mut self) -> &mut Self;
+}
+
+impl AppPredictionExt for App {
+    fn predict<C: Component + Clone + PartialEq>(&mut self) -> &mut Self {
+        self.set_marker_fns::<Predicted, C>(write_confirmed::<C>, remove_predicted::<C>)
+            .add_systems(
+                PreUpdate,
+                verify::<C>
+                    .before(PredictionPlugin::run)
+                    .after(ClientSet::Receive)
+                    .run_if(client_connected),
+            )
+            .add_systems(PredictionRestore, restore::<C>)
+            .add_systems(PredictionRecord, record::<C>)
+    }
+}
+
+/**
+Client-side prediction with rollback.
+
+After receiving replication, the plugin advances [`PredictionTick`] at the server tick rate
+from [`PredictionPlugin::tick_policy`] and runs [`PredictionSchedule`] once for each new tick.
+Put simulation systems for entities with [`Predicted`] there.
+After each run, values of components registered via [`AppPredictionExt::predict`]
+are recorded into [`PredictionHistory`].
+
+When the server confirms a tick for a predicted entity in [`ConfirmHistory`], the confirmed value
+is compared against the prediction for this tick. On mismatch, all predicted components are
+rolled back to their confirmed values and [`PredictionSchedule`] is re-run for all unconfirmed ticks.
+
+The prediction tick starts from the last confirmed tick. If the server gets ahead or the prediction
+gets too far ahead of the server, the prediction tick jumps to the confirmed tick.
+
+# Examples
+
+```
+use bevy::prelude::*;
+use bevy_replicon::{client::prediction::*, prelude::*};
+use serde::{Deserialize, Serialize};
+
+# let mut app = App::new();
+app.add_plugins((MinimalPlugins, RepliconPlugins, PredictionPlugin::default()))
+    .replicate::<Position>()
+    .predict::<Position>()
+    .add_systems(PredictionSchedule, move_players);
+
+fn move_players(mut players: Query<&mut Position, With<Predicted>>) {
+    for mut position in &mut players {
+        // Read inputs for the current `PredictionTick` here.
+        position.0 += 1.0;
+    }
+}
+
+#[derive(Component, Deserialize, Serialize, Clone, PartialEq)]
+struct Position(f32);
+```
+**/
+pub struct PredictionPlugin {
+    /// Rate at which [`PredictionTick`] is advanced.
+    ///
+    /// Should match [`ServerPlugin::tick_policy`](crate::server::ServerPlugin::tick_policy),
+    /// so each prediction tick corresponds to a server tick.
+    /// With [`TickPolicy::EveryFrame`] and [`TickPolicy::Manual`] the tick is advanced once per frame.
+    pub tick_policy: TickPolicy,
+}
+
+impl Default for PredictionPlugin {
+    fn default() -> Self {
+        Self {
+            tick_policy: TickPolicy::MaxTickRate(30),
+        }
+    }
+}
+
+impl Plugin for PredictionPlugin {
+    fn build(&self, app: &mut App) {
+        let timestep = match self.tick_policy {
+            TickPolicy::MaxTickRate(max_tick_rate) => {
+                Some(Duration::from_millis(1000 / max_tick_rate as u64))
+            }
+            TickPolicy::EveryFrame | TickPolicy::Manual => None,
+        };
+
+        app.init_resource::<PredictionTick>()
+            .init_resource::<Rollback>()
+            .insert_resource(PredictionClock {
+                timestep,
+                accumulated: Duration::ZERO,
+            })
+            .init_schedule(PredictionSchedule)
+            .init_schedule(PredictionRestore)
+            .init_schedule(PredictionRecord)
+            .register_marker::<Predicted>()
+            .add_systems(
+                PreUpdate,
+                (
+                    Self::run.after(ClientSet::Receive).run_if(client_connected),
+                    Self::reset.in_set(ClientSet::Reset),
+                ),
+            );
+    }
+}
+
+impl PredictionPlugin {
+    /// Performs requested rollback and runs the simulation for ticks that are due.
+    fn run(world: &mut World) {
+        let current_tick = **world.resource::<PredictionTick>();
+        if let Some(tick) = world.resource_mut::<Rollback>().0.take() {
+            world.resource_mut::<PredictionTick>().0 = tick;
+            world.run_schedule(PredictionRestore);
+
+            if tick < current_tick && current_tick - tick > MAX_PREDICTED_TICKS as u32 {
+                debug!("resetting from {current_tick:?} to confirmed {tick:?} because the prediction is too far ahead");
+            } else if tick < current_tick {
+                debug!("rolling back from {current_tick:?} to {tick:?}");
+                let mut resimulated_tick = tick;
+                while resimulated_tick < current_tick {
+                    resimulated_tick += 1;
+                    world.resource_mut::<PredictionTick>().0 = resimulated_tick;
+                    world.run_schedule(PredictionSchedule);
+                    world.run_schedule(PredictionRecord);
+                }
+            } else {
+                debug!("jumping from {current_tick:?} to confirmed {tick:?}");
+            }
+        }
+
+        let delta = world.resource::<Time>().delta();
+        let steps = world.resource_mut::<PredictionClock>().advance(delta);
+        for _ in 0..steps {
+            let mut tick = world.resource_mut::<PredictionTick>();
+            tick.0 += 1;
+            trace!("predicting {:?}", tick.0);
+
+            world.run_schedule(PredictionSchedule);
+            world.run_schedule(PredictionRecord);
+        }
+    }
+
+    fn reset(
+        mut tick: ResMut<PredictionTick>,
+        mut rollback: ResMut<Rollback>,
+        mut clock: ResMut<PredictionClock>,
+    ) {
+        *tick = Default::default();
+        rollback.0 = None;
+        clock.accumulated = Duration::ZERO;
+    }
+}
+
+/// Schedule with simulation systems for predicted entities.
+///
+/// Runs in [`PreUpdate`] once for each new [`PredictionTick`]
+/// and once per each unconfirmed tick on rollback.
+#[derive(ScheduleLabel, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PredictionSchedule;
+
+/// Restores confirmed values of predicted components.
+#[derive(ScheduleLabel, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct PredictionRestore;
+
+/// Records predicted values after a simulation step.
+#[derive(ScheduleLabel, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct PredictionRecord;
+
+/// Tick which is currently simulated by [`PredictionSchedule`].
+#[derive(Resource, Default, Debug, Clone, Copy, Deref)]
+pub struct PredictionTick(RepliconTick);
+
+/// Accumulates frame time to advance [`PredictionTick`] at the server tick rate.
+#[derive(Resource)]
+struct PredictionClock {
+    /// Time between ticks or `None` to advance once per frame.
+    timestep: Option<Duration>,
+    accumulated: Duration,
+}
+
+impl PredictionClock {
+    /// Accumulates `delta` and returns the number of ticks to simulate.
+    ///
+    /// Limited to [`MAX_PREDICTED_TICKS`] to avoid falling behind after long frames.
+    fn advance(&mut self, delta: Duration) -> u32 {
+        let Some(timestep) = self.timestep else {
+            return 1;
+        };
+
+        self.accumulated += delta;
+        let steps = (self.accumulated.as_nanos() / timestep.as_nanos()) as u32;
+        if steps > MAX_PREDICTED_TICKS as u32 {
+            self.accumulated = Duration::ZERO;
+            return MAX_PREDICTED_TICKS as u32;
+        }
+        self.accumulated -= timestep * steps;
+
+        steps
+    }
+}
+
+/// The earliest tick from which the simulation should be re-run.
+#[derive(Resource, Default)]
+struct Rollback(Option<RepliconTick>);
+
+impl Rollback {
+    fn request(&mut self, tick: RepliconTick) {
+        match self.0 {
+            Some(rollback_tick) if rollback_tick <= tick => (),
+            _ => self.0 = Some(tick),
+        }
+    }
+}
+
+/// Marks entity as predicted on client.
+///
+/// Components registered via [`AppPredictionExt::predict`] will be predicted for it.
+#[derive(Component, Default, Debug, Clone, Copy)]
+pub struct Predicted;
+
+/// Predicted and confirmed values of component `C`.
+///
+/// Automatically inserted on entities with [`Predicted`] when `C` is received from the server.
+#[derive(Component)]
+pub struct PredictionHistory<C> {
+    /// Predicted values for unconfirmed ticks in ascending order.
+    predicted: VecDeque<(RepliconTick, C)>,
+
+    /// The last received value.
+    confirmed: (RepliconTick, C),
+
+    /// The last tick from [`ConfirmHistory`] that was compared against prediction.
+    verified_tick: RepliconTick,
+}
+
+impl<C: Clone + PartialEq> PredictionHistory<C> {
+    fn new(tick: RepliconTick, component: C) -> Self {
+        Self {
+            predicted: Default::default(),
+            confirmed: (tick, component),
+            verified_tick: Default::default(),
+        }
+    }
+
+    /// Returns the last received value and its tick.
+    pub fn confirmed(&self) -> (RepliconTick, &C) {
+        let (tick, component) = &self.confirmed;
+        (*tick, component)
+    }
+
+    /// Returns the last tick for which the value is known from the server.
+    ///
+    /// It could be newer than the tick from [`Self::confirmed`] if the server
+    /// confirmed the entity without changes in this component.
+    pub fn confirmed_tick(&self) -> RepliconTick {
+        if self.verified_tick > self.confirmed.0 {
+            self.verified_tick
+        } else {
+            self.confirmed.0
+        }
+    }
+
+    /// Returns the predicted value for a tick.
+    pub fn predicted(&self, tick: RepliconTick) -> Option<&C> {
+        self.predicted
+            .iter()
+            .find(|&&(predicted_tick, _)| predicted_tick == tick)
+            .map(|(_, component)| component)
+    }
+
+    /// Stores a received value if it's newer then the confirmed one.
+    fn confirm(&mut self, tick: RepliconTick, component: C) {
+        if tick >= self.confirmed.0 {
+            self.confirmed = (tick, component);
+        }
+    }
+
+    /// Stores a predicted value for a tick, replacing all values for this and later ticks.
+    fn record(&mut self, tick: RepliconTick, component: C) {
+        while self
+            .predicted
+            .back()
+            .is_some_and(|&(predicted_tick, _)| predicted_tick >= tick)
+        {
+            self.predicted.pop_back();
+        }
+        if self.predicted.len() == MAX_PREDICTED_TICKS {
+            self.predicted.pop_front();
+        }
+        self.predicted.push_back((tick, component));
+    }
+
+    /// Compares the confirmed value against prediction for this tick and removes
+    /// predictions that are no longer needed.
+    ///
+    /// Returns `true` if the prediction was correct.
+    fn verify(&mut self, tick: RepliconTick) -> bool {
+        let correct = self
+            .predicted(tick)
+            .is_some_and(|predicted| *predicted == self.confirmed.1);
+
+        while self
+            .predicted
+            .front()
+            .is_some_and(|&(predicted_tick, _)| predicted_tick <= tick)
+        {
+            self.predicted.pop_front();
+        }
+        self.verified_tick = tick;
+
+        correct
+    }
+}
+
+/// Max number of stored predicted values.
+///
+/// Matches the number of ticks tracked by [`ConfirmHistory`].
+const MAX_PREDICTED_TICKS: usize = u64::BITS as usize;
+
+/// Stores received value in [`PredictionHistory<C>`] instead of writing it into the component.
+///
+/// If the entity has no history yet, the value is also written into the component.
+fn write_confirmed<C: Component + Clone + PartialEq>(
+    ctx: &mut WriteCtx,
+    rule_fns: &RuleFns<C>,
+    entity: &mut EntityMut,
+    cursor: &mut Cursor<&[u8]>,
+) -> bincode::Result<()> {
+    let component = rule_fns.deserialize(ctx, cursor)?;
+    if let Some(mut history) = entity.get_mut::<PredictionHistory<C>>() {
+        history.confirm(ctx.message_tick, component);
+    } else {
+        let history = PredictionHistory::new(ctx.message_tick, component.clone());
+        ctx.commands
+            .entity(entity.id())
+            .insert((component, history));
+    }
+
+    Ok(())
+}
+
+/// Removes component `C` and its history.
+fn remove_predicted<C: Component>(ctx: &mut RemoveCtx, entity: &mut EntityMut) {
+    ctx.commands
+        .entity(entity.id())
+        .remove::<(C, PredictionHistory<C>)>();
+}
+
+/// Compares confirmed values against predictions and requests rollback on mismatch.
+fn verify<C: Component + Clone + PartialEq>(
+    mut rollback: ResMut<Rollback>,
+    mut entities: Query<(Entity, &ConfirmHistory, &mut PredictionHistory<C>), With<Predicted>>,
+) {
+    for (entity, confirm_history, mut history) in &mut entities {
+        let tick = confirm_history.last_tick();
+        if tick <= history.verified_tick {
+            continue;
+        }
+
+        if !history.verify(tick) {
+            debug!("misprediction for {entity:?} at {tick:?}");
+            rollback.request(tick);
+        }
+    }
+}
+
+/// Sets predicted components to their values at the rollback tick.
+///
+/// Uses confirmed values for entities that were confirmed at this or a later tick
+/// and predicted values for the rest.
+fn restore<C: Component + Clone + PartialEq>(
+    tick: Res<PredictionTick>,
+    mut entities: Query<(&mut C, &PredictionHistory<C>), With<Predicted>>,
+) {
+    for (mut component, history) in &mut entities {
+        if history.confirmed_tick() >= **tick {
+            *component = history.confirmed.1.clone();
+        } else if let Some(predicted) = history.predicted(**tick) {
+            *component = predicted.clone();
+        }
+    }
+}
+
+/// Stores predicted values after a simulation step.
+///
+/// Values of entities that were confirmed at this or a later tick are reset to the confirmed values.
+fn record<C: Component + Clone + PartialEq>(
+    tick: Res<PredictionTick>,
+    mut entities: Query<(&mut C, &mut PredictionHistory<C>), With<Predicted>>,
+) {
+    for (mut component, mut history) in &mut entities {
+        if history.confirmed_tick() >= **tick {
+            *component = history.confirmed.1.clone();
+        } else {
+            history.record(**tick, component.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verification() {
+        let mut history = PredictionHistory::new(RepliconTick::new(0), 0);
+        for tick in 1..=3 {
+            history.record(RepliconTick::new(tick), tick);
+        }
+
+        history.confirm(RepliconTick::new(1), 1);
+        assert!(history.verify(RepliconTick::new(1)));
+        assert_eq!(history.predicted.len(), 2);
+
+        history.confirm(RepliconTick::new(2), 5);
+        assert!(!history.verify(RepliconTick::new(2)));
+        assert_eq!(history.predicted.len(), 1);
+
+        history.confirm(RepliconTick::new(1), 10);
+        assert_eq!(
+            history.confirmed(),
+            (RepliconTick::new(2), &5),
+            "older values should be ignored"
+        );
+    }
+
+    #[test]
+    fn clock() {
+        let mut clock = PredictionClock {
+            timestep: Some(Duration::from_millis(100)),
+            accumulated: Duration::ZERO,
+        };
+        assert_eq!(clock.advance(Duration::from_millis(60)), 0);
+        assert_eq!(clock.advance(Duration::from_millis(60)), 1);
+        assert_eq!(clock.advance(Duration::from_millis(280)), 3);
+        assert_eq!(
+            clock.advance(Duration::from_secs(60)),
+            MAX_PREDICTED_TICKS as u32
+        );
+        assert_eq!(clock.accumulated, Duration::ZERO);
+    }
+
+    #[test]
+    fn rerecording() {
+        let mut history = PredictionHistory::new(RepliconTick::new(0), 0);
+        for tick in 1..=3 {
+            history.record(RepliconTick::new(tick), tick);
+        }
+
+        history.record(RepliconTick::new(2), 10);
+        assert_eq!(history.predicted(RepliconTick::new(2)), Some(&10));
+        assert_eq!(history.predicted(RepliconTick::new(3)), None);
+    }
+}
@@ -0,0 +1,224 @@
+use std::mem;
+
+use bevy::{
+    ecs::{component::Tick, world::CommandQueue},
+    prelude::*,
+};
+
+use super::{
+    confirm_history::ConfirmHistory, replicon_client::RepliconClient,
+    server_entity_map::ServerEntityMap, ClientPlugin, ClientSet, ServerInitTick,
+};
+use crate::core::{
+    command_markers::{CommandMarkers, EntityMarkers},
+    common_conditions::{client_just_connected, initial_sync_complete},
+    ctx::{DespawnCtx, RemoveCtx},
+    replication_registry::ReplicationRegistry,
+    ClientId,
+};
+
+/// Preserves replicated entities across reconnects.
+///
+/// By default [`ClientSet::Reset`] clears the entity mapping on disconnect, so entities
+/// received in the previous session are left orphaned and the server spawns new ones after reconnect.
+///
+/// With this plugin the mapping is kept and restored if the client reconnects with the same [`ClientId`].
+/// The server resends the full state to reconnected clients, so existing entities receive
+/// the current values. When the initial sync completes, entities that weren't sent again are despawned
+/// and [`RepairDespawn`] is emitted for each of them. Replicated components that weren't sent again
+/// are removed from the remaining entities. If the ID differs, all preserved entities are
+/// despawned right after the connection.
+///
+/// See [`ServerPlugin::initial_sync_budget`](crate::server::ServerPlugin::initial_sync_budget)
+/// for when the initial sync completes.
+pub struct RepairPlugin;
+
+impl Plugin for RepairPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReconnectRepair>()
+            .add_event::<RepairDespawn>()
+            .add_systems(
+                PreUpdate,
+                (
+                    preserve
+                        .in_set(ClientSet::Reset)
+                        .before(ClientPlugin::reset),
+                    start
+                        .after(ClientSet::ReceivePackets)
+                        .before(ClientSet::Receive)
+                        .run_if(client_just_connected),
+                    finish
+                        .after(ClientSet::Receive)
+                        .before(ClientSet::SyncHierarchy)
+                        .run_if(initial_sync_complete),
+                ),
+            );
+    }
+}
+
+/// Takes the entity mapping before it's cleared by [`ClientPlugin::reset`].
+fn preserve(mut repair: ResMut<ReconnectRepair>, mut entity_map: ResMut<ServerEntityMap>) {
+    debug!(
+        "preserving {} entities after disconnect",
+        entity_map.to_client().len()
+    );
+    repair.entity_map = mem::take(&mut *entity_map);
+    repair.repair_tick = None;
+}
+
+/// Restores the preserved mapping if the client ID matches the previous session.
+fn start(world: &mut World) {
+    let client_id = world.resource::<RepliconClient>().id();
+    let change_tick = world.change_tick();
+    let mut repair = world.resource_mut::<ReconnectRepair>();
+    let previous_id = mem::replace(&mut repair.client_id, client_id);
+    let entity_map = mem::take(&mut repair.entity_map);
+    if entity_map.to_client().is_empty() {
+        return;
+    }
+
+    if previous_id == client_id {
+        debug!(
+            "repairing {} entities for reconnected {client_id:?}",
+            entity_map.to_client().len()
+        );
+        repair.repair_tick = Some(change_tick);
+        *world.resource_mut::<ServerEntityMap>() = entity_map;
+    } else {
+        debug!("despawning preserved entities because the client ID changed from {previous_id:?} to {client_id:?}");
+        let entities: Vec<_> = entity_map
+            .to_client()
+            .iter()
+            .map(|(&server_entity, &client_entity)| (server_entity, client_entity))
+            .collect();
+        despawn(world, entities);
+    }
+}
+
+/// Despawns entities and removes replicated components that weren't confirmed by the server after reconnect.
+fn finish(world: &mut World) {
+    let Some(repair_tick) = world.resource_mut::<ReconnectRepair>().repair_tick.take() else {
+        return;
+    };
+
+    let this_run = world.change_tick();
+    let registry = world.resource::<ReplicationRegistry>();
+    let mut stale = Vec::new();
+    let mut stale_components = Vec::new();
+    for (&server_entity, &client_entity) in world.resource::<ServerEntityMap>().to_client() {
+        let Some(entity) = world.get_entity(client_entity) else {
+            continue;
+        };
+        let confirmed = match entity.get_change_ticks::<ConfirmHistory>() {
+            Some(ticks) => ticks.is_changed(repair_tick, this_run),
+            None => true,
+        };
+        if !confirmed {
+            stale.push((server_entity, client_entity));
+            continue;
+        }
+
+        // All replicated components of a confirmed entity were written during the resync.
+        for (index, &(_, component_id)) in registry.components().enumerate() {
+            let written = match entity.get_change_ticks_by_id(component_id) {
+                Some(ticks) => ticks.is_changed(repair_tick, this_run),
+                None => true,
+            };
+            if !written {
+                stale_components.push((client_entity, index));
+            }
+        }
+    }
+    let mut entity_map = world.resource_mut::<ServerEntityMap>();
+    for &(server_entity, _) in &stale {
+        entity_map.remove_by_server(server_entity);
+    }
+
+    debug!("despawning {} entities after repair", stale.len());
+    despawn(world, stale);
+
+    debug!(
+        "removing {} components after repair",
+        stale_components.len()
+    );
+    remove(world, stale_components);
+}
+
+fn despawn(world: &mut World, entities: Vec<(Entity, Entity)>) {
+    let despawn_fn = world.resource::<ReplicationRegistry>().despawn;
+    let ctx = DespawnCtx {
+        message_tick: **world.resource::<ServerInitTick>(),
+    };
+    for (server_entity, client_entity) in entities {
+        world.send_event(RepairDespawn {
+            server_entity,
+            client_entity,
+        });
+        if let Some(entity) = world.get_entity_mut(client_entity) {
+            (despawn_fn)(&ctx, entity);
+        }
+    }
+}
+
+/// Removes components by their indices in [`ReplicationRegistry::components`].
+fn remove(world: &mut World, components: Vec<(Entity, usize)>) {
+    if components.is_empty() {
+        return;
+    }
+
+    let mut entity_markers = EntityMarkers::from_world(world);
+    world.resource_scope(|world, registry: Mut<ReplicationRegistry>| {
+        world.resource_scope(|world, command_markers: Mut<CommandMarkers>| {
+            let message_tick = **world.resource::<ServerInitTick>();
+            let mut queue = CommandQueue::default();
+            let world_cell = world.as_unsafe_world_cell();
+            for (client_entity, index) in components {
+                // SAFETY: access is unique and used to obtain `EntityMut`, which is just a wrapper over `UnsafeEntityCell`.
+                let Some(entity) = unsafe { world_cell.world_mut() }.get_entity_mut(client_entity)
+                else {
+                    continue;
+                };
+                let mut entity: EntityMut = entity.into();
+                entity_markers.read(&command_markers, &entity);
+
+                let (component_fns, _) = registry
+                    .components()
+                    .nth(index)
+                    .expect("index should be obtained from the same registry");
+                let mut commands = Commands::new_from_entities(&mut queue, world_cell.entities());
+                let mut ctx = RemoveCtx::new(&mut commands, message_tick);
+                component_fns.remove(&mut ctx, &entity_markers, &mut entity);
+            }
+
+            queue.apply(world);
+        });
+    });
+}
+
+/// State of [`RepairPlugin`].
+#[derive(Resource, Default)]
+struct ReconnectRepair {
+    /// Client ID from the last connection.
+    client_id: Option<ClientId>,
+
+    /// Mapping preserved after disconnect.
+    entity_map: ServerEntityMap,
+
+    /// Change tick at which the mapping was restored.
+    ///
+    /// Entities whose [`ConfirmHistory`] wasn't changed after it are despawned.
+    repair_tick: Option<Tick>,
+}
+
+/// An event that indicates that a preserved entity was despawned after reconnect
+/// because the server no longer replicates it.
+///
+/// Emitted by [`RepairPlugin`] right before the despawn.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct RepairDespawn {
+    /// Entity on server.
+    pub server_entity: Entity,
+
+    /// Despawned entity on client.
+    pub client_entity: Entity,
+}
@@ -0,0 +1,187 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::{
+    replicon_client::{RepliconClient, RepliconClientStatus},
+    server_baselines::ServerBaselines,
+    server_entity_map::ServerEntityMap,
+    BufferedUpdates, ClientSet, ProtocolStatus, ServerInitTick,
+};
+use crate::core::{protocol::ProtocolHash, recording::Recording};
+
+/// Plays a [`Recording`] without a server.
+///
+/// Acts as a messaging backend: messages from [`Replay`] are inserted into [`RepliconClient`]
+/// according to their timestamps and all messages sent by the client are discarded.
+/// Shouldn't be used together with a real messaging backend.
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            play.in_set(ClientSet::ReceivePackets)
+                .run_if(resource_exists::<Replay>),
+        );
+    }
+}
+
+/// Inserts messages from [`Replay`] that are due into [`RepliconClient`].
+fn play(world: &mut World) {
+    world.resource_scope(|world, mut replay: Mut<Replay>| {
+        let replay = &mut *replay;
+        let delta = world.resource::<Time>().delta();
+        let mut client = world.resource_mut::<RepliconClient>();
+        client.drain_sent().for_each(drop);
+
+        if !replay.started {
+            replay.started = true;
+            client.set_status(RepliconClientStatus::Connected { client_id: None });
+
+            let protocol_hash = *world.resource::<ProtocolHash>();
+            let server_hash = replay.recording.protocol_hash();
+            let status = if server_hash == protocol_hash {
+                ProtocolStatus::Verified
+            } else {
+                error!(
+                    "recorded {server_hash:?} doesn't match client's {protocol_hash:?}, \
+                    make sure that replication rules, events and channels are registered in the same order"
+                );
+                ProtocolStatus::Mismatch { server_hash }
+            };
+            *world.resource_mut::<ProtocolStatus>() = status;
+        }
+
+        if let Some(position) = replay.seek.take() {
+            reset_world(world);
+            replay.position = position;
+            replay.next_index = 0;
+            if let Some(index) = replay.recording.keyframe_before(position) {
+                debug!("seeking to {position:?} from keyframe {index}");
+                let keyframe = &replay.recording.messages()[index];
+                world
+                    .resource_mut::<RepliconClient>()
+                    .insert_received(keyframe.channel_id, keyframe.message.clone());
+                replay.next_index = index + 1;
+            }
+        } else if !replay.paused {
+            replay.position += delta.mul_f64(replay.speed as f64);
+        }
+
+        let mut client = world.resource_mut::<RepliconClient>();
+        for message in &replay.recording.messages()[replay.next_index..] {
+            if message.timestamp > replay.position {
+                break;
+            }
+
+            if !message.keyframe {
+                client.insert_received(message.channel_id, message.message.clone());
+            }
+            replay.next_index += 1;
+        }
+    });
+}
+
+/// Despawns all replicated entities and clears received state to apply a keyframe.
+fn reset_world(world: &mut World) {
+    let entities: Vec<_> = world
+        .resource::<ServerEntityMap>()
+        .to_client()
+        .values()
+        .copied()
+        .collect();
+    for entity in entities {
+        if let Some(entity) = world.get_entity_mut(entity) {
+            entity.despawn_recursive();
+        }
+    }
+
+    world.resource_mut::<ServerEntityMap>().clear();
+    world.resource_mut::<BufferedUpdates>().clear();
+    world.resource_mut::<ServerBaselines>().clear();
+    *world.resource_mut::<ServerInitTick>() = Default::default();
+}
+
+/// Playback state of a [`Recording`].
+///
+/// Insert it as a resource to start playing. Available only with [`ReplayPlugin`].
+#[derive(Resource)]
+pub struct Replay {
+    recording: Recording,
+    position: Duration,
+    next_index: usize,
+    speed: f32,
+    paused: bool,
+    seek: Option<Duration>,
+    started: bool,
+}
+
+impl Replay {
+    /// Creates a new playback that starts from the beginning.
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            recording,
+            position: Duration::ZERO,
+            next_index: 0,
+            speed: 1.0,
+            paused: false,
+            seek: None,
+            started: false,
+        }
+    }
+
+    /// Returns the played recording.
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Stops advancing the playback position.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Continues advancing the playback position.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Returns `true` if the playback is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Sets playback speed multiplier.
+    ///
+    /// By default it's 1.0.
+    pub fn set_speed(&mut self, speed: f32) {
+        assert!(speed >= 0.0, "speed can't be negative, but it's {speed}");
+        self.speed = speed;
+    }
+
+    /// Returns playback speed multiplier.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Moves the playback to `position`.
+    ///
+    /// Replicated entities are despawned and the world is restored from the nearest keyframe
+    /// before the position. Then all messages between the keyframe and the position are applied.
+    /// If there is no keyframe before the position, the recording is applied from the beginning.
+    ///
+    /// Performed on the next [`ClientSet::ReceivePackets`].
+    pub fn seek(&mut self, position: Duration) {
+        self.seek = Some(position);
+    }
+
+    /// Returns current playback position.
+    pub fn position(&self) -> Duration {
+        self.seek.unwrap_or(self.position)
+    }
+
+    /// Returns `true` if all messages were played.
+    pub fn is_finished(&self) -> bool {
+        self.next_index >= self.recording.messages().len()
+    }
+}
@@ -1,3 +1,5 @@
+use std::mem;
+
 use bevy::prelude::*;
 use bytes::Bytes;
 
@@ -14,6 +16,8 @@ use crate::core::ClientId;
 /// - For sending messages, [`Self::drain_sent`] should be used to drain all sent messages.
 /// A system to forward Replicon messages to the backend should run in
 /// [`ClientSet::SendPackets`](super::ClientSet::SendPackets).
+/// - For disconnecting requested by Replicon, [`Self::take_disconnect_request`] should be used.
+///   It should be called in [`ClientSet::SendPackets`](super::ClientSet::SendPackets) after sending messages.
 #[derive(Resource, Default)]
 pub struct RepliconClient {
     /// Client connection status.
@@ -27,6 +31,9 @@ pub struct RepliconClient {
 
     /// List of sent messages and their channels since the last tick.
     sent_messages: Vec<(u8, Bytes)>,
+
+    /// Indicates if the client should be disconnected.
+    disconnect_requested: bool,
 }
 
 impl RepliconClient {
@@ -89,11 +96,28 @@ impl RepliconClient {
                 channel_messages.clear();
             }
             self.sent_messages.clear();
+            self.disconnect_requested = false;
         }
 
         self.status = status;
     }
 
+    /// Requests a disconnect from the server.
+    ///
+    /// The disconnect will be performed by the messaging backend after sending all pending messages.
+    /// See also [`Self::take_disconnect_request`].
+    pub fn disconnect(&mut self) {
+        debug!("requesting disconnect");
+        self.disconnect_requested = true;
+    }
+
+    /// Returns `true` if a disconnect was requested and resets the request.
+    ///
+    /// Should be called only from the messaging backend.
+    pub fn take_disconnect_request(&mut self) -> bool {
+        mem::take(&mut self.disconnect_requested)
+    }
+
     /// Returns the current client status.
     ///
     /// See also [`Self::set_status`].
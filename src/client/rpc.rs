@@ -0,0 +1,279 @@
+use std::{marker::PhantomData, time::Duration};
+
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{events::ClientEventAppExt, ClientSet};
+use crate::{
+    core::channels::RepliconChannel,
+    server::{
+        events::ServerEventAppExt,
+        rpc::{self, ClientRequest, Reply},
+        ServerSet,
+    },
+};
+
+/// Timeout used by [`Requests::send`].
+pub const DEFAULT_RPC_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An extension trait for [`App`] for creating request/response pairs.
+pub trait AppRpcExt {
+    /**
+    Registers a request that clients send to the server and the response to it.
+
+    Internally registers a client event for requests and a server event for responses
+    over the same `channel`, so it must be called on both the client and the server in the same order.
+    Each request and response type can be used only in one pair.
+
+    Send requests on client with [`Requests`]. The server receives them as [`ClientRequest`] events
+    and answers with [`Reply`] events. Entities inside requests and responses are not mapped.
+
+    # Examples
+
+    ```
+    use bevy::prelude::*;
+    use bevy_replicon::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    # let mut app = App::new();
+    # app.add_plugins((MinimalPlugins, RepliconPlugins));
+    app.add_rpc::<ScoreRequest, Score>(ChannelKind::Ordered)
+        .add_systems(
+            Update,
+            (
+                send_request.run_if(client_just_connected),
+                receive_response,
+                reply.run_if(server_running),
+            ),
+        );
+
+    fn send_request(mut requests: Requests<ScoreRequest, Score>) {
+        requests.send(ScoreRequest);
+    }
+
+    fn receive_response(mut commands: Commands, results: Query<(Entity, &RpcResult<Score>)>) {
+        for (entity, result) in &results {
+            match result {
+                RpcResult::Response(score) => info!("received {score:?}"),
+                RpcResult::Timeout => info!("the server didn't respond"),
+            }
+            commands.entity(entity).despawn();
+        }
+    }
+
+    fn reply(mut requests: EventReader<ClientRequest<ScoreRequest>>, mut replies: EventWriter<Reply<Score>>) {
+        for request in requests.read() {
+            replies.send(Reply {
+                token: request.token,
+                response: Score(42),
+            });
+        }
+    }
+
+    #[derive(Deserialize, Serialize)]
+    struct ScoreRequest;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct Score(u32);
+    ```
+    */
+    fn add_rpc<Req, Resp>(&mut self, channel: impl Into<RepliconChannel>) -> &mut Self
+    where
+        Req: Send + Sync + Serialize + DeserializeOwned + 'static,
+        Resp: Send + Sync + Serialize + DeserializeOwned + 'static;
+}
+
+impl AppRpcExt for App {
+    fn add_rpc<Req, Resp>(&mut self, channel: impl Into<RepliconChannel>) -> &mut Self
+    where
+        Req: Send + Sync + Serialize + DeserializeOwned + 'static,
+        Resp: Send + Sync + Serialize + DeserializeOwned + 'static,
+    {
+        let channel = channel.into();
+        self.add_client_event::<RequestMessage<Req>>(channel.clone())
+            .add_server_event::<ResponseMessage<Resp>>(channel)
+            .add_event::<ClientRequest<Req>>()
+            .add_event::<Reply<Resp>>()
+            .init_resource::<PendingRequests<Req, Resp>>()
+            .add_systems(
+                PreUpdate,
+                (
+                    reset::<Req, Resp>.in_set(ClientSet::Reset),
+                    receive_responses::<Req, Resp>.after(ClientSet::Receive),
+                    rpc::receive_requests::<Req>.after(ServerSet::Receive),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                rpc::send_replies::<Resp>.before(ServerSet::Send),
+            )
+    }
+}
+
+/// Resolves pending requests with received responses and timeouts.
+fn receive_responses<Req: Send + Sync + 'static, Resp: Send + Sync + 'static>(
+    mut commands: Commands,
+    mut response_events: ResMut<Events<ResponseMessage<Resp>>>,
+    mut pending_requests: ResMut<PendingRequests<Req, Resp>>,
+    time: Res<Time>,
+) {
+    for ResponseMessage { id, response } in response_events.drain() {
+        let Some((entity, _)) = pending_requests.requests.remove(&id) else {
+            debug!("ignoring response for unknown or timed out {id:?}");
+            continue;
+        };
+
+        if let Some(mut entity) = commands.get_entity(entity) {
+            entity
+                .remove::<PendingResponse<Resp>>()
+                .insert(RpcResult::Response(response));
+        }
+    }
+
+    let elapsed = time.elapsed();
+    pending_requests
+        .requests
+        .retain(|id, &mut (entity, deadline)| {
+            let Some(mut entity) = commands.get_entity(entity) else {
+                return false;
+            };
+
+            if deadline > elapsed {
+                return true;
+            }
+
+            debug!("{id:?} timed out");
+            entity
+                .remove::<PendingResponse<Resp>>()
+                .insert(RpcResult::<Resp>::Timeout);
+            false
+        });
+}
+
+/// Resolves all pending requests with [`RpcResult::Timeout`] on disconnect.
+///
+/// Responses for them will never arrive.
+fn reset<Req: Send + Sync + 'static, Resp: Send + Sync + 'static>(
+    mut commands: Commands,
+    mut pending_requests: ResMut<PendingRequests<Req, Resp>>,
+) {
+    for (id, (entity, _)) in pending_requests.requests.drain() {
+        debug!("resolving {id:?} as timed out due to disconnect");
+        if let Some(mut entity) = commands.get_entity(entity) {
+            entity
+                .remove::<PendingResponse<Resp>>()
+                .insert(RpcResult::<Resp>::Timeout);
+        }
+    }
+}
+
+/// A [`SystemParam`] for sending requests registered with [`AppRpcExt::add_rpc`] from client.
+///
+/// Each request spawns an entity with [`PendingResponse`]. When the response arrives
+/// or the request times out, the component is replaced with [`RpcResult`].
+/// Despawn the entity to discard the request. On disconnect all pending requests
+/// resolve with [`RpcResult::Timeout`].
+///
+/// Requests are sent in [`ClientSet::Send`]. Like client events, in listen-server mode
+/// they are received on the same app with [`ClientId::SERVER`](crate::core::ClientId::SERVER).
+#[derive(SystemParam)]
+pub struct Requests<'w, 's, Req: Send + Sync + 'static, Resp: Send + Sync + 'static> {
+    commands: Commands<'w, 's>,
+    request_events: EventWriter<'w, RequestMessage<Req>>,
+    pending_requests: ResMut<'w, PendingRequests<Req, Resp>>,
+    time: Res<'w, Time>,
+}
+
+impl<Req: Send + Sync + 'static, Resp: Send + Sync + 'static> Requests<'_, '_, Req, Resp> {
+    /// Sends a request with [`DEFAULT_RPC_TIMEOUT`].
+    ///
+    /// See also [`Self::send_with_timeout`].
+    pub fn send(&mut self, request: Req) -> Entity {
+        self.send_with_timeout(request, DEFAULT_RPC_TIMEOUT)
+    }
+
+    /// Sends a request and returns the entity that will receive [`RpcResult`].
+    ///
+    /// If the response doesn't arrive within `timeout`, the request resolves with [`RpcResult::Timeout`].
+    pub fn send_with_timeout(&mut self, request: Req, timeout: Duration) -> Entity {
+        let id = RequestId(self.pending_requests.next_id);
+        self.pending_requests.next_id = self.pending_requests.next_id.wrapping_add(1);
+
+        let entity = self
+            .commands
+            .spawn(PendingResponse::<Resp> {
+                id,
+                marker: PhantomData,
+            })
+            .id();
+        let deadline = self.time.elapsed() + timeout;
+        self.pending_requests
+            .requests
+            .insert(id, (entity, deadline));
+        self.request_events.send(RequestMessage { id, request });
+
+        entity
+    }
+}
+
+/// Requests waiting for responses.
+#[derive(Resource)]
+struct PendingRequests<Req, Resp> {
+    next_id: u32,
+
+    /// Entities and deadlines of sent requests.
+    requests: HashMap<RequestId, (Entity, Duration)>,
+
+    marker: PhantomData<(Req, Resp)>,
+}
+
+impl<Req, Resp> Default for PendingRequests<Req, Resp> {
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            requests: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Identifies a request among others of the same type sent by a client.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct RequestId(u32);
+
+/// A component for an entity spawned by [`Requests`] until the request is resolved.
+#[derive(Component)]
+pub struct PendingResponse<Resp> {
+    id: RequestId,
+    marker: PhantomData<Resp>,
+}
+
+impl<Resp> PendingResponse<Resp> {
+    /// Returns the ID of the sent request.
+    pub fn id(&self) -> RequestId {
+        self.id
+    }
+}
+
+/// A component inserted into a request entity after it's resolved.
+#[derive(Component, Debug)]
+pub enum RpcResult<Resp> {
+    /// The server responded.
+    Response(Resp),
+    /// The response didn't arrive in time.
+    Timeout,
+}
+
+/// Client event for sending a request over the network.
+#[derive(Deserialize, Event, Serialize)]
+pub(crate) struct RequestMessage<Req> {
+    pub(crate) id: RequestId,
+    pub(crate) request: Req,
+}
+
+/// Server event for sending a response over the network.
+#[derive(Deserialize, Event, Serialize)]
+pub(crate) struct ResponseMessage<Resp> {
+    pub(crate) id: RequestId,
+    pub(crate) response: Resp,
+}
@@ -0,0 +1,120 @@
+use std::io::Cursor;
+
+use bevy::{ecs::entity::EntityHashMap, prelude::*};
+
+use crate::core::{
+    delta_compression::{self, ComponentBytes, MAX_BASELINE_AGE},
+    replication_registry::FnsId,
+    replicon_tick::RepliconTick,
+};
+
+/// Received serialized values of components with delta compression for each server entity.
+///
+/// Used to reconstruct values from diffs sent by the server.
+///
+/// If [`ClientSet::Reset`](super::ClientSet::Reset) is disabled, then this needs to be cleaned up manually
+/// with [`Self::clear`].
+///
+/// See also [`RuleFns::with_delta_compression`](crate::core::replication_registry::rule_fns::RuleFns::with_delta_compression).
+#[derive(Default, Resource)]
+pub struct ServerBaselines(EntityHashMap<Vec<ComponentValues>>);
+
+impl ServerBaselines {
+    /// Reads component bytes written by the server and remembers the reconstructed value at `tick`.
+    ///
+    /// Returns [`None`] if the value was sent as a diff against an unknown baseline.
+    pub(super) fn read(
+        &mut self,
+        cursor: &mut Cursor<&[u8]>,
+        server_entity: Entity,
+        fns_id: FnsId,
+        tick: RepliconTick,
+    ) -> bincode::Result<Option<&[u8]>> {
+        let components = self.0.entry(server_entity).or_default();
+        let index = match components
+            .iter()
+            .position(|component| component.fns_id == fns_id)
+        {
+            Some(index) => index,
+            None => {
+                components.push(ComponentValues {
+                    fns_id,
+                    values: Default::default(),
+                });
+                components.len() - 1
+            }
+        };
+        let component = &mut components[index];
+
+        let bytes = match delta_compression::read_bytes(cursor)? {
+            ComponentBytes::Full(bytes) => bytes.to_vec(),
+            ComponentBytes::Delta {
+                baseline_tick,
+                diff,
+            } => {
+                let Some((_, baseline)) = component
+                    .values
+                    .iter()
+                    .find(|&&(value_tick, _)| value_tick == baseline_tick)
+                else {
+                    debug!(
+                        "received a diff for {server_entity:?} against unknown {baseline_tick:?}"
+                    );
+                    return Ok(None);
+                };
+
+                let mut bytes = Vec::new();
+                delta_compression::apply_diff(baseline, diff, &mut bytes)?;
+
+                // The server never references baselines older than the last acknowledged one.
+                component
+                    .values
+                    .retain(|&(value_tick, _)| value_tick >= baseline_tick);
+
+                bytes
+            }
+        };
+
+        Ok(Some(component.insert(tick, bytes)))
+    }
+
+    /// Removes all values for a server entity.
+    pub(super) fn remove(&mut self, server_entity: Entity) {
+        self.0.remove(&server_entity);
+    }
+
+    /// Removes all values.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// Received values for a component.
+struct ComponentValues {
+    fns_id: FnsId,
+
+    /// Serialized values with their ticks, sorted by tick.
+    values: Vec<(RepliconTick, Vec<u8>)>,
+}
+
+impl ComponentValues {
+    /// Inserts a value at `tick` and removes values too old to be used as baselines.
+    fn insert(&mut self, tick: RepliconTick, bytes: Vec<u8>) -> &[u8] {
+        let last_tick = self
+            .values
+            .last()
+            .map(|&(last_tick, _)| last_tick)
+            .filter(|&last_tick| last_tick > tick)
+            .unwrap_or(tick);
+        self.values.retain(|&(value_tick, _)| {
+            value_tick != tick && last_tick - value_tick < MAX_BASELINE_AGE
+        });
+
+        let index = self
+            .values
+            .partition_point(|&(value_tick, _)| value_tick < tick);
+        self.values.insert(index, (tick, bytes));
+
+        &self.values[index].1
+    }
+}
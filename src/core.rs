@@ -1,31 +1,88 @@
 pub mod channels;
 pub mod command_markers;
 pub mod common_conditions;
+pub(crate) mod compression;
 pub mod ctx;
+pub(crate) mod delta_compression;
+pub mod protocol;
+pub mod recording;
 pub mod replication_registry;
+pub mod replication_resources;
 pub mod replication_rules;
 pub mod replicon_tick;
+pub(crate) mod varint;
 
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use channels::RepliconChannels;
 use command_markers::CommandMarkers;
+use protocol::ProtocolHash;
 use replication_registry::ReplicationRegistry;
+use replication_resources::ReplicationResources;
 use replication_rules::ReplicationRules;
 
-pub struct RepliconCorePlugin;
+#[derive(Default)]
+pub struct RepliconCorePlugin {
+    /// Identification of replication rules and events in messages.
+    ///
+    /// Should be the same on the client and the server.
+    pub id_policy: IdPolicy,
+}
 
 impl Plugin for RepliconCorePlugin {
     fn build(&self, app: &mut App) {
+        let mut channels = RepliconChannels::default();
+        if self.id_policy == IdPolicy::TypeHash {
+            channels.create_shared_event_channels();
+        }
+
         app.register_type::<Replicated>()
-            .init_resource::<RepliconChannels>()
-            .init_resource::<ReplicationRegistry>()
+            .insert_resource(self.id_policy)
+            .insert_resource(channels)
+            .insert_resource(ReplicationRegistry::with_id_policy(self.id_policy))
+            .insert_resource(ReplicationResources::with_id_policy(self.id_policy))
             .init_resource::<ReplicationRules>()
-            .init_resource::<CommandMarkers>();
+            .init_resource::<CommandMarkers>()
+            .add_systems(Startup, ProtocolHash::init);
     }
 }
 
+/// Identification of replication rules and events in messages.
+///
+/// See also [`RepliconCorePlugin::id_policy`].
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdPolicy {
+    /// Identify by registration order.
+    ///
+    /// Produces the smallest messages, but the client and the server
+    /// need to register rules and events in the same order.
+    /// Each event uses its own channel.
+    #[default]
+    RegistrationOrder,
+
+    /// Identify by a hash of the type name.
+    ///
+    /// Rules and events can be registered in any order. Events from the other side
+    /// that aren't registered locally are ignored, so builds that have additional events
+    /// can still interoperate.
+    ///
+    /// Each component ID takes 4 bytes in messages. If multiple rules are registered
+    /// for the same component, only their relative order matters. Since type names are used,
+    /// the client and the server should be built with the same compiler version.
+    ///
+    /// Events are sent over shared channels, one for each [`ChannelKind`](channels::ChannelKind).
+    /// Only [`RepliconChannel::kind`](channels::RepliconChannel::kind) is used from the channel
+    /// passed at the event registration. Events of different types are not ordered relative to each other.
+    /// Custom channels should be created with
+    /// [`RepliconChannels::create_named_server_channel`](channels::RepliconChannels::create_named_server_channel)
+    /// and [`RepliconChannels::create_named_client_channel`](channels::RepliconChannels::create_named_client_channel)
+    /// to get IDs that don't depend on the registration order.
+    ///
+    /// Registration panics on hash collision.
+    TypeHash,
+}
+
 #[deprecated(note = "use `Replicated` instead")]
 pub type Replication = Replicated;
 
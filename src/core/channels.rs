@@ -15,6 +15,15 @@ pub enum ReplicationChannel {
     ///
     /// This is an unreliable channel.
     Update,
+    /// For exchanging [`ProtocolHash`](super::protocol::ProtocolHash) after connection.
+    ///
+    /// This is an ordered reliable channel.
+    Protocol,
+    /// For sending changes of components from clients with
+    /// [`Authority`](crate::client::authority::Authority).
+    ///
+    /// This is an ordered reliable channel.
+    Authority,
 }
 
 impl From<ReplicationChannel> for RepliconChannel {
@@ -22,6 +31,8 @@ impl From<ReplicationChannel> for RepliconChannel {
         match value {
             ReplicationChannel::Init => ChannelKind::Ordered.into(),
             ReplicationChannel::Update => ChannelKind::Unreliable.into(),
+            ReplicationChannel::Protocol => ChannelKind::Ordered.into(),
+            ReplicationChannel::Authority => ChannelKind::Ordered.into(),
         }
     }
 }
@@ -41,6 +52,22 @@ pub struct RepliconChannels {
     /// Same as [`Self::server`], but for client.
     client: Vec<RepliconChannel>,
 
+    /// Names of server channels created with [`Self::create_named_server_channel`] in sorted order.
+    ///
+    /// Named channels are stored after unnamed ones in [`Self::server`] in the same order.
+    server_names: Vec<&'static str>,
+
+    /// Same as [`Self::server_names`], but for client.
+    client_names: Vec<&'static str>,
+
+    /// IDs of server channels shared by all events, indexed by [`ChannelKind`].
+    ///
+    /// Empty unless [`IdPolicy::TypeHash`](super::IdPolicy::TypeHash) is used.
+    shared_server: Vec<u8>,
+
+    /// Same as [`Self::shared_server`], but for client.
+    shared_client: Vec<u8>,
+
     /// Stores the default max memory usage bytes for all channels.
     ///
     /// This value will be used instead of [`None`].
@@ -55,11 +82,19 @@ impl Default for RepliconChannels {
             server: vec![
                 ReplicationChannel::Init.into(),
                 ReplicationChannel::Update.into(),
+                ReplicationChannel::Protocol.into(),
+                ReplicationChannel::Authority.into(),
             ],
             client: vec![
                 ReplicationChannel::Init.into(),
                 ReplicationChannel::Update.into(),
+                ReplicationChannel::Protocol.into(),
+                ReplicationChannel::Authority.into(),
             ],
+            server_names: Default::default(),
+            client_names: Default::default(),
+            shared_server: Default::default(),
+            shared_client: Default::default(),
             default_max_bytes: 5 * 1024 * 1024,
         }
     }
@@ -73,30 +108,130 @@ impl RepliconChannels {
 
     /// Creates a new server channel and returns its ID.
     ///
+    /// The ID depends on the registration order. For channels that can be registered
+    /// in any order use [`Self::create_named_server_channel`].
+    ///
     /// # Panics
     ///
-    /// Panics if the number of events exceeds [`u8::MAX`].
+    /// Panics if the number of channels exceeds [`u8::MAX`].
     pub fn create_server_channel(&mut self, channel: RepliconChannel) -> u8 {
-        if self.server.len() == u8::MAX as usize {
-            panic!("number of server channels shouldn't exceed `u8::MAX`");
-        }
-
-        self.server.push(channel);
-        self.server.len() as u8 - 1
+        create_channel(&mut self.server, &self.server_names, channel, "server")
     }
 
     /// Creates a new client channel and returns its ID.
     ///
+    /// See also [`Self::create_server_channel`].
+    ///
     /// # Panics
     ///
-    /// Panics if the number of events exceeds [`u8::MAX`].
+    /// Panics if the number of channels exceeds [`u8::MAX`].
     pub fn create_client_channel(&mut self, channel: RepliconChannel) -> u8 {
-        if self.client.len() == u8::MAX as usize {
-            panic!("number of client channels shouldn't exceed `u8::MAX`");
+        create_channel(&mut self.client, &self.client_names, channel, "client")
+    }
+
+    /// Creates a new server channel identified by `name`.
+    ///
+    /// Named channels are placed after all channels created with [`Self::create_server_channel`]
+    /// and sorted by name, so their IDs don't depend on the registration order. It makes them suitable
+    /// for [`IdPolicy::TypeHash`](super::IdPolicy::TypeHash) where plugins can be added in any order.
+    ///
+    /// Since creating a channel may shift IDs of other named channels, the ID should be obtained with
+    /// [`Self::server_channel_id`] after all channels are created, for example, inside systems.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a channel with this name was already created or the number of channels exceeds [`u8::MAX`].
+    pub fn create_named_server_channel(&mut self, name: &'static str, channel: RepliconChannel) {
+        create_named_channel(
+            &mut self.server,
+            &mut self.server_names,
+            name,
+            channel,
+            "server",
+        );
+    }
+
+    /// Same as [`Self::create_named_server_channel`], but for client.
+    pub fn create_named_client_channel(&mut self, name: &'static str, channel: RepliconChannel) {
+        create_named_channel(
+            &mut self.client,
+            &mut self.client_names,
+            name,
+            channel,
+            "client",
+        );
+    }
+
+    /// Returns ID of a server channel created with [`Self::create_named_server_channel`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no channel with this name.
+    pub fn server_channel_id(&self, name: &str) -> u8 {
+        channel_id(&self.server, &self.server_names, name, "server")
+    }
+
+    /// Same as [`Self::server_channel_id`], but for client.
+    pub fn client_channel_id(&self, name: &str) -> u8 {
+        channel_id(&self.client, &self.client_names, name, "client")
+    }
+
+    /// Returns names of channels created with [`Self::create_named_server_channel`] in the order of their IDs.
+    pub(crate) fn server_names(&self) -> &[&'static str] {
+        &self.server_names
+    }
+
+    /// Same as [`Self::server_names`], but for client.
+    pub(crate) fn client_names(&self) -> &[&'static str] {
+        &self.client_names
+    }
+
+    /// Creates server and client channels for each [`ChannelKind`] that will be shared by all events.
+    ///
+    /// Used for [`IdPolicy::TypeHash`](super::IdPolicy::TypeHash).
+    pub(super) fn create_shared_event_channels(&mut self) {
+        for kind in [
+            ChannelKind::Unreliable,
+            ChannelKind::Unordered,
+            ChannelKind::Ordered,
+        ] {
+            let server_id = self.create_server_channel(kind.into());
+            self.shared_server.push(server_id);
+            let client_id = self.create_client_channel(kind.into());
+            self.shared_client.push(client_id);
         }
+    }
+
+    /// Returns ID of a server channel shared by all events with the given kind.
+    ///
+    /// # Panics
+    ///
+    /// Panics if shared channels weren't created.
+    pub(crate) fn shared_server_channel(&self, kind: ChannelKind) -> u8 {
+        *self
+            .shared_server
+            .get(kind as usize)
+            .expect("shared channels should be created for the type hash ID policy")
+    }
 
-        self.client.push(channel);
-        self.client.len() as u8 - 1
+    /// Same as [`Self::shared_server_channel`], but for client.
+    pub(crate) fn shared_client_channel(&self, kind: ChannelKind) -> u8 {
+        *self
+            .shared_client
+            .get(kind as usize)
+            .expect("shared channels should be created for the type hash ID policy")
+    }
+
+    /// Returns IDs of server channels shared by all events.
+    ///
+    /// Empty unless [`IdPolicy::TypeHash`](super::IdPolicy::TypeHash) is used.
+    pub(crate) fn shared_server_channels(&self) -> &[u8] {
+        &self.shared_server
+    }
+
+    /// Same as [`Self::shared_server_channels`], but for client.
+    pub(crate) fn shared_client_channels(&self) -> &[u8] {
+        &self.shared_client
     }
 
     /// Returns a mutable reference to a server channel.
@@ -128,6 +263,50 @@ impl RepliconChannels {
     }
 }
 
+/// Inserts an unnamed channel before all named channels.
+fn create_channel(
+    channels: &mut Vec<RepliconChannel>,
+    names: &[&'static str],
+    channel: RepliconChannel,
+    side: &str,
+) -> u8 {
+    if channels.len() == u8::MAX as usize {
+        panic!("number of {side} channels shouldn't exceed `u8::MAX`");
+    }
+
+    let index = channels.len() - names.len();
+    channels.insert(index, channel);
+    index as u8
+}
+
+/// Inserts a named channel according to the sorting order of its name.
+fn create_named_channel(
+    channels: &mut Vec<RepliconChannel>,
+    names: &mut Vec<&'static str>,
+    name: &'static str,
+    channel: RepliconChannel,
+    side: &str,
+) {
+    if channels.len() == u8::MAX as usize {
+        panic!("number of {side} channels shouldn't exceed `u8::MAX`");
+    }
+
+    let name_index = match names.binary_search(&name) {
+        Ok(_) => panic!("{side} channel `{name}` shouldn't be created more than once"),
+        Err(index) => index,
+    };
+    names.insert(name_index, name);
+    channels.insert(channels.len() + 1 - names.len() + name_index, channel);
+}
+
+fn channel_id(channels: &[RepliconChannel], names: &[&'static str], name: &str, side: &str) -> u8 {
+    let name_index = names
+        .binary_search(&name)
+        .unwrap_or_else(|_| panic!("{side} channel `{name}` should be created"));
+
+    (channels.len() - names.len() + name_index) as u8
+}
+
 /// Channel configuration.
 #[derive(Clone)]
 pub struct RepliconChannel {
@@ -167,3 +346,48 @@ impl From<ChannelKind> for RepliconChannel {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_channels() {
+        let mut channels1 = RepliconChannels::default();
+        channels1.create_named_server_channel("b", ChannelKind::Ordered.into());
+        channels1.create_named_server_channel("a", ChannelKind::Unreliable.into());
+        let unnamed_id = channels1.create_server_channel(ChannelKind::Unordered.into());
+
+        let mut channels2 = RepliconChannels::default();
+        channels2.create_server_channel(ChannelKind::Unordered.into());
+        channels2.create_named_server_channel("a", ChannelKind::Unreliable.into());
+        channels2.create_named_server_channel("b", ChannelKind::Ordered.into());
+
+        for channels in [&channels1, &channels2] {
+            assert_eq!(channels.server_channel_id("a"), unnamed_id + 1);
+            assert_eq!(channels.server_channel_id("b"), unnamed_id + 2);
+            let kinds: Vec<_> = channels
+                .server_channels()
+                .iter()
+                .skip(unnamed_id as usize)
+                .map(|channel| channel.kind)
+                .collect();
+            assert_eq!(
+                kinds,
+                [
+                    ChannelKind::Unordered,
+                    ChannelKind::Unreliable,
+                    ChannelKind::Ordered
+                ]
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn duplicate_name() {
+        let mut channels = RepliconChannels::default();
+        channels.create_named_client_channel("a", ChannelKind::Ordered.into());
+        channels.create_named_client_channel("a", ChannelKind::Ordered.into());
+    }
+}
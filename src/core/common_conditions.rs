@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 
-use crate::{client::replicon_client::RepliconClient, server::replicon_server::RepliconServer};
+use crate::{
+    client::{replicon_client::RepliconClient, InitialSyncStatus, ProtocolStatus},
+    server::replicon_server::RepliconServer,
+};
 
 /// Returns `true` if the server is running.
 pub fn server_running(server: Option<Res<RepliconServer>>) -> bool {
@@ -27,6 +30,21 @@ pub fn client_connected(client: Option<Res<RepliconClient>>) -> bool {
     client.filter(|client| client.is_connected()).is_some()
 }
 
+/// Returns `true` when the server confirmed that the client has the same protocol.
+///
+/// See also [`ProtocolHash`](super::protocol::ProtocolHash).
+pub fn protocol_verified(status: Option<Res<ProtocolStatus>>) -> bool {
+    status.is_some_and(|status| *status == ProtocolStatus::Verified)
+}
+
+/// Returns `true` when the client received all entities visible to it after connecting.
+///
+/// Useful to hide a loading screen.
+/// See also [`InitialSyncStatus`].
+pub fn initial_sync_complete(status: Option<Res<InitialSyncStatus>>) -> bool {
+    status.is_some_and(|status| *status == InitialSyncStatus::Complete)
+}
+
 /// Returns `true` if the server stopped on this tick.
 pub fn server_just_stopped(
     mut last_running: Local<bool>,
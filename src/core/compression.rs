@@ -0,0 +1,334 @@
+use std::io::{self, Cursor};
+
+use varint_rs::VarintWriter;
+
+use super::varint;
+
+/// Header flag that indicates that the init message payload is compressed.
+///
+/// Written into the first byte of each init message.
+pub(crate) const COMPRESSED_FLAG: u8 = 0b1;
+
+/// Bit of the update index that indicates that the update message payload is compressed.
+///
+/// Update indices wrap around before reaching it, so update messages
+/// don't need a separate flags byte.
+pub(crate) const COMPRESSED_UPDATE_BIT: u16 = 1 << 15;
+
+/// Header flag that indicates that the message completes the initial world sync for the client.
+///
+/// Written only into init messages.
+pub(crate) const INITIAL_SYNC_FLAG: u8 = 0b10;
+
+/// Minimum length of a match that can be referenced instead of written as literals.
+const MIN_MATCH: usize = 4;
+
+/// Number of bits in a hash for the match table.
+const HASH_LOG: u32 = 12;
+
+/// Max distance between a match and the current position.
+const MAX_OFFSET: usize = u16::MAX as usize;
+
+/// Value of a length nibble in a token that indicates that the length continues in the next bytes.
+const LEN_EXTENDED: usize = 0xF;
+
+/// Compresses payloads of replication messages.
+///
+/// Reuses allocated memory from older messages.
+#[derive(Default)]
+pub(crate) struct MessageCompressor {
+    /// Last positions of hashed 4-byte sequences.
+    table: Vec<u32>,
+
+    /// Buffer for the compressed payload.
+    buffer: Vec<u8>,
+}
+
+impl MessageCompressor {
+    /// Compresses `payload` if its size is at least `threshold`.
+    ///
+    /// Returns [`None`] if the payload should be sent as is,
+    /// which also happens if the compressed data isn't smaller.
+    /// The caller is responsible for marking the message as compressed.
+    pub(crate) fn compress(
+        &mut self,
+        payload: &[u8],
+        threshold: Option<usize>,
+    ) -> bincode::Result<Option<&[u8]>> {
+        if threshold.is_some_and(|threshold| payload.len() >= threshold) {
+            self.buffer.clear();
+            compress(payload, &mut self.buffer, &mut self.table)?;
+            if self.buffer.len() < payload.len() {
+                return Ok(Some(&self.buffer));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Reads flags of an init message.
+///
+/// Returns an error on unknown flags.
+pub(crate) fn read_flags(cursor: &mut Cursor<&[u8]>) -> bincode::Result<u8> {
+    let flags: u8 = bincode::deserialize_from(&mut *cursor)?;
+    if flags & !(COMPRESSED_FLAG | INITIAL_SYNC_FLAG) != 0 {
+        return Err(Box::new(bincode::ErrorKind::Custom(format!(
+            "unknown message flags {flags:#b}"
+        ))));
+    }
+
+    Ok(flags)
+}
+
+/// Writes `input` compressed with an LZ4-style algorithm.
+///
+/// The data starts with the uncompressed length followed by sequences.
+/// Each sequence contains a token with literal and match lengths, literals and an offset of the match.
+/// Lengths that don't fit into the token are continued in the following bytes.
+/// The last sequence contains only literals.
+fn compress(input: &[u8], output: &mut Vec<u8>, table: &mut Vec<u32>) -> bincode::Result<()> {
+    if input.len() > u32::MAX as usize {
+        return Err(Box::new(bincode::ErrorKind::SizeLimit));
+    }
+
+    output.write_usize_varint(input.len())?;
+
+    table.clear();
+    table.resize(1 << HASH_LOG, u32::MAX);
+
+    let mut anchor = 0;
+    let mut pos = 0;
+    while pos + MIN_MATCH <= input.len() {
+        let sequence = &input[pos..pos + MIN_MATCH];
+        let hash = hash(sequence);
+        let candidate = table[hash];
+        table[hash] = pos as u32;
+
+        if candidate != u32::MAX {
+            let candidate = candidate as usize;
+            if pos - candidate <= MAX_OFFSET && input[candidate..candidate + MIN_MATCH] == *sequence
+            {
+                let match_len = MIN_MATCH
+                    + input[candidate + MIN_MATCH..]
+                        .iter()
+                        .zip(&input[pos + MIN_MATCH..])
+                        .take_while(|(a, b)| a == b)
+                        .count();
+
+                write_sequence(
+                    output,
+                    &input[anchor..pos],
+                    Some((pos - candidate, match_len)),
+                );
+                pos += match_len;
+                anchor = pos;
+                continue;
+            }
+        }
+
+        pos += 1;
+    }
+
+    write_sequence(output, &input[anchor..], None);
+
+    Ok(())
+}
+
+/// Decompresses data written by [`compress`].
+///
+/// Returns an error if the data is malformed.
+pub(crate) fn decompress(input: &[u8]) -> bincode::Result<Vec<u8>> {
+    let mut cursor = Cursor::new(input);
+    let len = varint::read_usize(&mut cursor)?;
+    // Each input byte can't produce more than 255 output bytes.
+    if len > input.len().saturating_mul(u8::MAX as usize) {
+        return Err(invalid_data());
+    }
+
+    let mut output = Vec::with_capacity(len);
+    loop {
+        let token = read_byte(&mut cursor)?;
+
+        let literal_len = read_len(&mut cursor, (token >> 4) as usize)?;
+        let start = cursor.position() as usize;
+        let literals = start
+            .checked_add(literal_len)
+            .and_then(|end| input.get(start..end))
+            .ok_or_else(unexpected_eof)?;
+        if output.len() + literals.len() > len {
+            return Err(invalid_data());
+        }
+        output.extend_from_slice(literals);
+        cursor.set_position((start + literal_len) as u64);
+
+        if output.len() == len {
+            break;
+        }
+
+        let offset =
+            u16::from_le_bytes([read_byte(&mut cursor)?, read_byte(&mut cursor)?]) as usize;
+        if offset == 0 || offset > output.len() {
+            return Err(invalid_data());
+        }
+
+        let match_len = read_len(&mut cursor, (token & 0xF) as usize)? + MIN_MATCH;
+        if output.len() + match_len > len {
+            return Err(invalid_data());
+        }
+
+        // Copy byte by byte since the match may overlap with the bytes being written.
+        let match_start = output.len() - offset;
+        for index in match_start..match_start + match_len {
+            output.push(output[index]);
+        }
+    }
+
+    if cursor.position() != input.len() as u64 {
+        return Err(invalid_data());
+    }
+
+    Ok(output)
+}
+
+/// Writes a single sequence for [`compress`].
+fn write_sequence(output: &mut Vec<u8>, literals: &[u8], match_info: Option<(usize, usize)>) {
+    let literal_nibble = literals.len().min(LEN_EXTENDED);
+    let match_nibble = match_info
+        .map(|(_, match_len)| (match_len - MIN_MATCH).min(LEN_EXTENDED))
+        .unwrap_or_default();
+    output.push((literal_nibble << 4 | match_nibble) as u8);
+
+    if literal_nibble == LEN_EXTENDED {
+        write_extended_len(output, literals.len() - LEN_EXTENDED);
+    }
+    output.extend_from_slice(literals);
+
+    if let Some((offset, match_len)) = match_info {
+        output.extend_from_slice(&(offset as u16).to_le_bytes());
+        if match_nibble == LEN_EXTENDED {
+            write_extended_len(output, match_len - MIN_MATCH - LEN_EXTENDED);
+        }
+    }
+}
+
+fn write_extended_len(output: &mut Vec<u8>, mut len: usize) {
+    while len >= u8::MAX as usize {
+        output.push(u8::MAX);
+        len -= u8::MAX as usize;
+    }
+    output.push(len as u8);
+}
+
+/// Reads the length that started in a token nibble.
+fn read_len(cursor: &mut Cursor<&[u8]>, nibble: usize) -> bincode::Result<usize> {
+    let mut len = nibble;
+    if nibble == LEN_EXTENDED {
+        loop {
+            let byte = read_byte(cursor)?;
+            len = len.checked_add(byte as usize).ok_or_else(invalid_data)?;
+            if byte != u8::MAX {
+                break;
+            }
+        }
+    }
+
+    Ok(len)
+}
+
+fn read_byte(cursor: &mut Cursor<&[u8]>) -> bincode::Result<u8> {
+    let pos = cursor.position() as usize;
+    let byte = *cursor.get_ref().get(pos).ok_or_else(unexpected_eof)?;
+    cursor.set_position(pos as u64 + 1);
+
+    Ok(byte)
+}
+
+fn hash(sequence: &[u8]) -> usize {
+    let value = u32::from_le_bytes(sequence.try_into().unwrap());
+    (value.wrapping_mul(2654435761) >> (u32::BITS - HASH_LOG)) as usize
+}
+
+fn unexpected_eof() -> bincode::Error {
+    io::Error::from(io::ErrorKind::UnexpectedEof).into()
+}
+
+fn invalid_data() -> bincode::Error {
+    Box::new(bincode::ErrorKind::Custom(
+        "compressed data is malformed".into(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let repetitive: Vec<u8> = (0..1000).map(|index| (index % 7) as u8).collect();
+        let random: Vec<u8> = (0..1000u32)
+            .map(|index| (index.wrapping_mul(2654435761) >> 24) as u8)
+            .collect();
+        for input in [
+            &[][..],
+            &[1, 2, 3],
+            &[0; 4],
+            &[0; 300],
+            &repetitive,
+            &random,
+        ] {
+            let mut compressed = Vec::new();
+            compress(input, &mut compressed, &mut Vec::new()).unwrap();
+            assert_eq!(decompress(&compressed).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn ratio() {
+        let input: Vec<u8> = (0..1000).map(|index| (index % 7) as u8).collect();
+        let mut compressed = Vec::new();
+        compress(&input, &mut compressed, &mut Vec::new()).unwrap();
+        assert!(compressed.len() < input.len() / 10);
+    }
+
+    #[test]
+    fn message() {
+        let mut compressor = MessageCompressor::default();
+        let payload = [0; 100];
+
+        assert!(compressor.compress(&payload, None).unwrap().is_none());
+        assert!(
+            compressor.compress(&payload, Some(200)).unwrap().is_none(),
+            "payload below the threshold shouldn't be compressed"
+        );
+        assert!(
+            compressor
+                .compress(&[1, 2, 3, 4], Some(0))
+                .unwrap()
+                .is_none(),
+            "payload shouldn't be compressed if it doesn't reduce the size"
+        );
+
+        let compressed = compressor.compress(&payload, Some(50)).unwrap().unwrap();
+        assert_eq!(decompress(compressed).unwrap(), payload);
+    }
+
+    #[test]
+    fn malformed() {
+        let mut compressed = Vec::new();
+        compress(&[0; 100], &mut compressed, &mut Vec::new()).unwrap();
+
+        for len in 0..compressed.len() {
+            assert!(decompress(&compressed[..len]).is_err());
+        }
+
+        for input in [
+            &[10, 0xF0][..],
+            &[8, 0x00, 0, 0],
+            &[8, 0x10, 1, 5, 0],
+            &[1, 0x10],
+        ] {
+            assert!(decompress(input).is_err());
+        }
+    }
+}
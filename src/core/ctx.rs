@@ -40,6 +40,20 @@ impl<'a, 'w, 's> WriteCtx<'a, 'w, 's> {
             ignore_mapping: false,
         }
     }
+
+    /// Same as [`Self::new`], but entities won't be mapped.
+    pub(crate) fn new_unmapped(
+        commands: &'a mut Commands<'w, 's>,
+        entity_map: &'a mut ServerEntityMap,
+        message_tick: RepliconTick,
+    ) -> Self {
+        Self {
+            commands,
+            entity_map,
+            message_tick,
+            ignore_mapping: true,
+        }
+    }
 }
 
 impl EntityMapper for WriteCtx<'_, '_, '_> {
@@ -0,0 +1,259 @@
+use std::io::{self, Cursor, Write};
+
+use varint_rs::VarintWriter;
+
+use super::{replicon_tick::RepliconTick, varint};
+
+/// Max number of ticks between a message and the baseline it references.
+///
+/// Older baselines are not used by the server and discarded by the client.
+pub(crate) const MAX_BASELINE_AGE: u32 = 64;
+
+/// Minimum number of equal bytes that ends a literal run in a diff.
+///
+/// Shorter runs are cheaper to include into the literal than to start a new segment.
+const MIN_COPY_LEN: usize = 3;
+
+/// Component bytes read by [`read_bytes`].
+pub(crate) enum ComponentBytes<'a> {
+    /// Serialized component as-is.
+    Full(&'a [u8]),
+    /// Diff against the serialized component from the specified tick.
+    Delta {
+        baseline_tick: RepliconTick,
+        diff: &'a [u8],
+    },
+}
+
+/// Writes serialized component bytes, replacing them with a diff against `baseline` if it's smaller.
+///
+/// The length is written first as a varint with a bit flag that indicates if the data is a diff.
+/// For diffs the length is followed by the baseline tick.
+///
+/// `diff_buffer` is used to store the intermediate diff and reuse allocated memory.
+pub(crate) fn write_bytes(
+    cursor: &mut Cursor<Vec<u8>>,
+    bytes: &[u8],
+    baseline: Option<(RepliconTick, &[u8])>,
+    diff_buffer: &mut Vec<u8>,
+) -> bincode::Result<()> {
+    if let Some((baseline_tick, baseline)) = baseline {
+        diff_buffer.clear();
+        write_diff(baseline, bytes, diff_buffer)?;
+        if diff_buffer.len() < bytes.len() {
+            cursor.write_u64_varint((diff_buffer.len() as u64) << 1 | 1)?;
+            cursor.write_u32_varint(baseline_tick.get())?;
+            cursor.write_all(diff_buffer)?;
+            return Ok(());
+        }
+    }
+
+    cursor.write_u64_varint((bytes.len() as u64) << 1)?;
+    cursor.write_all(bytes)?;
+
+    Ok(())
+}
+
+/// Reads component bytes written by [`write_bytes`].
+pub(crate) fn read_bytes<'a>(cursor: &mut Cursor<&'a [u8]>) -> bincode::Result<ComponentBytes<'a>> {
+    let flagged_len = varint::read_u64(cursor)?;
+    let is_delta = (flagged_len & 1) > 0;
+    let baseline_tick = if is_delta {
+        Some(RepliconTick::new(varint::read_u32(cursor)?))
+    } else {
+        None
+    };
+
+    let bytes = take_slice(cursor, flagged_len >> 1)?;
+    match baseline_tick {
+        Some(baseline_tick) => Ok(ComponentBytes::Delta {
+            baseline_tick,
+            diff: bytes,
+        }),
+        None => Ok(ComponentBytes::Full(bytes)),
+    }
+}
+
+/// Writes the difference between `baseline` and `bytes`.
+///
+/// The diff starts with the length of `bytes` followed by segments.
+/// Each segment contains the number of bytes to copy from the baseline
+/// at the current position and the number of literal bytes with the bytes themselves.
+fn write_diff(baseline: &[u8], bytes: &[u8], diff: &mut Vec<u8>) -> bincode::Result<()> {
+    diff.write_u64_varint(bytes.len() as u64)?;
+
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let copy_len = equal_len(baseline, bytes, pos);
+        pos += copy_len;
+
+        let literal_start = pos;
+        while pos < bytes.len() {
+            let equal = equal_len(baseline, bytes, pos);
+            if equal >= MIN_COPY_LEN || pos + equal == bytes.len() {
+                break;
+            }
+            pos += equal.max(1);
+        }
+
+        diff.write_u64_varint(copy_len as u64)?;
+        diff.write_u64_varint((pos - literal_start) as u64)?;
+        diff.write_all(&bytes[literal_start..pos])?;
+    }
+
+    Ok(())
+}
+
+/// Reconstructs bytes from `baseline` and a diff written by [`write_diff`].
+///
+/// Returns an error if the diff doesn't match the baseline.
+pub(crate) fn apply_diff(baseline: &[u8], diff: &[u8], bytes: &mut Vec<u8>) -> bincode::Result<()> {
+    let mut cursor = Cursor::new(diff);
+    let len = varint::read_u64(&mut cursor)?;
+    if len > (baseline.len() + diff.len()) as u64 {
+        return Err(invalid_diff());
+    }
+    let len = len as usize;
+
+    bytes.clear();
+    bytes.reserve(len);
+    while bytes.len() < len {
+        let copy_len = varint::read_u64(&mut cursor)?;
+        let literal_len = varint::read_u64(&mut cursor)?;
+        if copy_len == 0 && literal_len == 0 {
+            return Err(invalid_diff());
+        }
+
+        let copy_start = bytes.len();
+        let copy = copy_start
+            .checked_add(copy_len as usize)
+            .and_then(|copy_end| baseline.get(copy_start..copy_end))
+            .ok_or_else(invalid_diff)?;
+        bytes.extend_from_slice(copy);
+
+        let literal = take_slice(&mut cursor, literal_len)?;
+        bytes.extend_from_slice(literal);
+    }
+
+    if bytes.len() != len {
+        return Err(invalid_diff());
+    }
+
+    Ok(())
+}
+
+/// Returns the number of equal bytes in both slices starting from `pos`.
+fn equal_len(baseline: &[u8], bytes: &[u8], pos: usize) -> usize {
+    baseline
+        .iter()
+        .skip(pos)
+        .zip(bytes.iter().skip(pos))
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+/// Returns a slice of the specified length from the current cursor position and advances the cursor.
+fn take_slice<'a>(cursor: &mut Cursor<&'a [u8]>, len: u64) -> bincode::Result<&'a [u8]> {
+    let start = cursor.position();
+    let end = start
+        .checked_add(len)
+        .filter(|&end| end <= cursor.get_ref().len() as u64)
+        .ok_or_else(|| bincode::Error::from(io::Error::from(io::ErrorKind::UnexpectedEof)))?;
+    cursor.set_position(end);
+
+    Ok(&cursor.get_ref()[start as usize..end as usize])
+}
+
+fn invalid_diff() -> bincode::Error {
+    Box::new(bincode::ErrorKind::Custom(
+        "diff doesn't match the baseline".into(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff() {
+        for (baseline, bytes) in [
+            (&[][..], &[][..]),
+            (&[1, 2, 3], &[1, 2, 3]),
+            (&[1, 2, 3], &[]),
+            (&[], &[1, 2, 3]),
+            (&[1, 2, 3, 4, 5, 6, 7, 8], &[1, 2, 0, 4, 5, 6, 7, 8]),
+            (&[1, 2, 3, 4, 5, 6, 7, 8], &[0, 2, 0, 4, 0, 6, 7, 0]),
+            (&[1, 2, 3, 4], &[1, 2, 3, 4, 5, 6]),
+            (&[1, 2, 3, 4, 5, 6], &[1, 2, 3]),
+        ] {
+            let mut diff = Vec::new();
+            write_diff(baseline, bytes, &mut diff).unwrap();
+
+            let mut result = Vec::new();
+            apply_diff(baseline, &diff, &mut result).unwrap();
+            assert_eq!(result, bytes);
+        }
+    }
+
+    #[test]
+    fn small_diff() {
+        let baseline = [0; 200];
+        let mut bytes = baseline;
+        bytes[100] = 1;
+
+        let mut diff = Vec::new();
+        write_diff(&baseline, &bytes, &mut diff).unwrap();
+        assert!(diff.len() < 10);
+    }
+
+    #[test]
+    fn framing() {
+        let baseline = [0; 200];
+        let mut bytes = baseline;
+        bytes[50] = 1;
+
+        let mut cursor = Cursor::new(Vec::new());
+        let mut diff_buffer = Vec::new();
+        write_bytes(&mut cursor, &bytes, None, &mut diff_buffer).unwrap();
+        write_bytes(
+            &mut cursor,
+            &bytes,
+            Some((RepliconTick::new(5), &baseline)),
+            &mut diff_buffer,
+        )
+        .unwrap();
+
+        let message = cursor.into_inner();
+        let mut cursor = Cursor::new(&*message);
+        let ComponentBytes::Full(full) = read_bytes(&mut cursor).unwrap() else {
+            panic!("first bytes should be written as-is");
+        };
+        assert_eq!(full, bytes);
+
+        let ComponentBytes::Delta {
+            baseline_tick,
+            diff,
+        } = read_bytes(&mut cursor).unwrap()
+        else {
+            panic!("second bytes should be written as a diff");
+        };
+        assert_eq!(baseline_tick, RepliconTick::new(5));
+        assert_eq!(cursor.position(), message.len() as u64);
+
+        let mut result = Vec::new();
+        apply_diff(&baseline, diff, &mut result).unwrap();
+        assert_eq!(result, bytes);
+    }
+
+    #[test]
+    fn mismatched_baseline() {
+        let baseline = [1, 2, 3, 4, 5, 6];
+        let bytes = [1, 2, 3, 4, 5, 0];
+
+        let mut diff = Vec::new();
+        write_diff(&baseline, &bytes, &mut diff).unwrap();
+
+        let mut result = Vec::new();
+        assert!(apply_diff(&[1, 2], &diff, &mut result).is_err());
+    }
+}
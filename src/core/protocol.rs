@@ -0,0 +1,241 @@
+use std::{
+    hash::{Hash, Hasher},
+    mem,
+};
+
+use bevy::{prelude::*, utils::HashMap};
+use bytes::Bytes;
+
+use super::{
+    channels::{ChannelKind, RepliconChannels},
+    replication_registry::ReplicationRegistry,
+    replication_resources::ReplicationResources,
+    IdPolicy,
+};
+use crate::{client::events::ClientEventRegistry, server::events::ServerEventRegistry};
+
+/// Hash of everything that the client and the server should register identically.
+///
+/// Replication rules, events and channels are identified by their registration order,
+/// so a mismatch between the client and the server corrupts the data.
+/// To detect it, the client sends its hash to the server after connecting and
+/// the server responds with its own.
+///
+/// Calculated on [`Startup`] from [`ReplicationRegistry`], [`ReplicationResources`], server and client events,
+/// [`RepliconChannels`], [`IdPolicy`] and the crate version. With [`IdPolicy::TypeHash`] events
+/// are excluded and rules are hashed in the order of their IDs. Since type names are used, apps built with different
+/// compiler versions may have different hashes.
+///
+/// See also [`ServerEvent::ProtocolMismatch`](crate::server::ServerEvent::ProtocolMismatch)
+/// and [`ProtocolStatus`](crate::client::ProtocolStatus).
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Deref)]
+pub struct ProtocolHash(u64);
+
+impl ProtocolHash {
+    /// Creates a hash from a raw value.
+    pub fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// Returns the raw value of the hash.
+    pub fn get(self) -> u64 {
+        self.0
+    }
+
+    /// Calculates the hash from the current registrations and inserts it as a resource.
+    pub(super) fn init(world: &mut World) {
+        let mut hasher = ProtocolHasher::default();
+        env!("CARGO_PKG_VERSION").hash(&mut hasher);
+        (*world.resource::<IdPolicy>() as u8).hash(&mut hasher);
+
+        let registry = world.resource::<ReplicationRegistry>();
+        registry.hash_rules(world.components(), &mut hasher);
+        world
+            .resource::<ReplicationResources>()
+            .hash_resources(&mut hasher);
+
+        if let Some(event_registry) = world.get_resource::<ServerEventRegistry>() {
+            event_registry.hash_events(&mut hasher);
+        }
+        if let Some(event_registry) = world.get_resource::<ClientEventRegistry>() {
+            event_registry.hash_events(&mut hasher);
+        }
+
+        let channels = world.resource::<RepliconChannels>();
+        for channels in [channels.server_channels(), channels.client_channels()] {
+            channels.len().hash(&mut hasher);
+            for channel in channels {
+                let kind: u8 = match channel.kind {
+                    ChannelKind::Unreliable => 0,
+                    ChannelKind::Unordered => 1,
+                    ChannelKind::Ordered => 2,
+                };
+                kind.hash(&mut hasher);
+            }
+        }
+        for names in [channels.server_names(), channels.client_names()] {
+            names.hash(&mut hasher);
+        }
+
+        let hash = Self(hasher.finish());
+        debug!("initialized {hash:?}");
+        world.insert_resource(hash);
+    }
+}
+
+/// Stable IDs for [`IdPolicy::TypeHash`](super::IdPolicy::TypeHash).
+///
+/// Maps hashes of type names into registration indices.
+#[derive(Default)]
+pub(crate) struct StableIds(HashMap<u32, StableIdEntry>);
+
+impl StableIds {
+    /// Calculates ID for a type name and associates it with the index.
+    ///
+    /// `occurrence` distinguishes multiple registrations of the same type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ID is already registered.
+    pub(crate) fn register(
+        &mut self,
+        type_name: &'static str,
+        occurrence: usize,
+        index: usize,
+    ) -> u32 {
+        let id = stable_id(type_name, occurrence);
+        if let Some(entry) = self.0.get(&id) {
+            if entry.type_name == type_name && entry.occurrence == occurrence {
+                panic!("`{type_name}` shouldn't be registered more than once");
+            }
+            panic!(
+                "stable ID {id:#x} of `{type_name}` (occurrence {occurrence}) collides with `{}` (occurrence {}), try renaming one of the types",
+                entry.type_name, entry.occurrence,
+            );
+        }
+
+        self.0.insert(
+            id,
+            StableIdEntry {
+                type_name,
+                occurrence,
+                index,
+            },
+        );
+
+        id
+    }
+
+    /// Returns the registration index associated with an ID.
+    pub(crate) fn index(&self, id: u32) -> Option<usize> {
+        self.0.get(&id).map(|entry| entry.index)
+    }
+
+    /// Reads the ID prefix from a message and returns the associated index and the rest of the message.
+    ///
+    /// Returns [`None`] if the message is too short or the ID is unknown.
+    pub(crate) fn split_message(&self, message: &Bytes) -> Option<(usize, Bytes)> {
+        let id = message.get(..mem::size_of::<u32>())?;
+        let index = self.index(u32::from_le_bytes(id.try_into().unwrap()))?;
+
+        Some((index, message.slice(mem::size_of::<u32>()..)))
+    }
+}
+
+struct StableIdEntry {
+    type_name: &'static str,
+    occurrence: usize,
+    index: usize,
+}
+
+/// Calculates a stable 32-bit ID from a type name and its occurrence.
+fn stable_id(type_name: &str, occurrence: usize) -> u32 {
+    let mut hasher = ProtocolHasher::default();
+    type_name.hash(&mut hasher);
+    occurrence.hash(&mut hasher);
+    let hash = hasher.finish();
+
+    (hash ^ (hash >> 32)) as u32
+}
+
+/// [FNV-1a](https://en.wikipedia.org/wiki/Fowler%E2%80%93Noll%E2%80%93Vo_hash_function) hasher.
+///
+/// Unlike [`DefaultHasher`](std::hash::DefaultHasher), produces the same results across Rust versions
+/// and platforms.
+pub(crate) struct ProtocolHasher(u64);
+
+impl Default for ProtocolHasher {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for ProtocolHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stable_hasher() {
+        let mut hasher = ProtocolHasher::default();
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63dc4c8601ec8c);
+
+        let mut hasher = ProtocolHasher::default();
+        hasher.write_usize(1);
+        let usize_hash = hasher.finish();
+
+        let mut hasher = ProtocolHasher::default();
+        hasher.write_u64(1);
+        assert_eq!(usize_hash, hasher.finish());
+    }
+
+    #[test]
+    fn stable_ids() {
+        let mut ids = StableIds::default();
+        let a = ids.register("A", 0, 0);
+        let b = ids.register("B", 0, 1);
+        let second_a = ids.register("A", 1, 2);
+        assert_ne!(a, b);
+        assert_ne!(a, second_a);
+        assert_eq!(ids.index(a), Some(0));
+        assert_eq!(ids.index(b), Some(1));
+        assert_eq!(ids.index(second_a), Some(2));
+        assert_eq!(ids.index(a.wrapping_add(1)), None);
+
+        let mut other_ids = StableIds::default();
+        assert_eq!(
+            other_ids.register("B", 0, 0),
+            b,
+            "IDs shouldn't depend on registration order"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn duplicate_stable_id() {
+        let mut ids = StableIds::default();
+        ids.register("A", 0, 0);
+        ids.register("A", 0, 1);
+    }
+}
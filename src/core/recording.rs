@@ -0,0 +1,184 @@
+use std::{
+    io::{Read, Write},
+    time::Duration,
+};
+
+use bincode::{DefaultOptions, Options};
+use bytes::Bytes;
+
+use super::protocol::ProtocolHash;
+
+/// Replication messages sent to a single client with their timestamps.
+///
+/// Recorded on server by [`ReplicationRecorder`](crate::server::recorder::ReplicationRecorder)
+/// and played on client by [`Replay`](crate::client::replay::Replay).
+#[derive(Clone, Debug)]
+pub struct Recording {
+    protocol_hash: ProtocolHash,
+    messages: Vec<RecordedMessage>,
+}
+
+impl Recording {
+    /// Creates an empty recording for the given protocol.
+    pub fn new(protocol_hash: ProtocolHash) -> Self {
+        Self {
+            protocol_hash,
+            messages: Default::default(),
+        }
+    }
+
+    /// Returns hash of the protocol used by the server during recording.
+    pub fn protocol_hash(&self) -> ProtocolHash {
+        self.protocol_hash
+    }
+
+    /// Returns all recorded messages in the order they were sent.
+    pub fn messages(&self) -> &[RecordedMessage] {
+        &self.messages
+    }
+
+    pub(crate) fn push(&mut self, message: RecordedMessage) {
+        self.messages.push(message);
+    }
+
+    /// Returns the timestamp of the last message.
+    pub fn duration(&self) -> Duration {
+        self.messages
+            .last()
+            .map(|message| message.timestamp)
+            .unwrap_or_default()
+    }
+
+    /// Returns index of the last keyframe recorded at or before `timestamp`.
+    pub fn keyframe_before(&self, timestamp: Duration) -> Option<usize> {
+        self.messages
+            .iter()
+            .rposition(|message| message.keyframe && message.timestamp <= timestamp)
+    }
+
+    /// Serializes the recording into `writer`.
+    pub fn write(&self, mut writer: impl Write) -> bincode::Result<()> {
+        let options = DefaultOptions::new();
+        options.serialize_into(&mut writer, &self.protocol_hash.get())?;
+        options.serialize_into(&mut writer, &self.messages.len())?;
+        for message in &self.messages {
+            options.serialize_into(
+                &mut writer,
+                &(
+                    message.timestamp,
+                    message.channel_id,
+                    message.keyframe,
+                    &*message.message,
+                ),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Deserializes a recording written by [`Self::write`] from `reader`.
+    pub fn read(mut reader: impl Read) -> bincode::Result<Self> {
+        let options = DefaultOptions::new();
+        let protocol_hash = ProtocolHash::new(options.deserialize_from(&mut reader)?);
+        let len: usize = options.deserialize_from(&mut reader)?;
+        let mut messages = Vec::new();
+        for _ in 0..len {
+            let (timestamp, channel_id, keyframe, message): (Duration, u8, bool, Vec<u8>) =
+                options.deserialize_from(&mut reader)?;
+            messages.push(RecordedMessage {
+                timestamp,
+                channel_id,
+                keyframe,
+                message: message.into(),
+            });
+        }
+
+        Ok(Self {
+            protocol_hash,
+            messages,
+        })
+    }
+}
+
+/// A single message from [`Recording`].
+#[derive(Clone, Debug)]
+pub struct RecordedMessage {
+    /// Time since the start of the recording.
+    pub timestamp: Duration,
+
+    /// Channel over which the message was sent.
+    pub channel_id: u8,
+
+    /// Indicates if the message is a full snapshot of the world.
+    ///
+    /// Keyframes are init messages for a client without any entities.
+    /// They are used only for seeking and skipped during playback.
+    pub keyframe: bool,
+
+    /// Message as sent by the server.
+    pub message: Bytes,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialization() {
+        let mut recording = Recording::new(ProtocolHash::new(42));
+        for (index, keyframe) in [false, true, false].into_iter().enumerate() {
+            recording.push(RecordedMessage {
+                timestamp: Duration::from_millis(index as u64 * 10),
+                channel_id: index as u8,
+                keyframe,
+                message: vec![index as u8; index].into(),
+            });
+        }
+
+        let mut bytes = Vec::new();
+        recording.write(&mut bytes).unwrap();
+        let deserialized = Recording::read(&*bytes).unwrap();
+
+        assert_eq!(deserialized.protocol_hash(), recording.protocol_hash());
+        assert_eq!(deserialized.duration(), Duration::from_millis(20));
+        for (message, expected) in deserialized.messages().iter().zip(recording.messages()) {
+            assert_eq!(message.timestamp, expected.timestamp);
+            assert_eq!(message.channel_id, expected.channel_id);
+            assert_eq!(message.keyframe, expected.keyframe);
+            assert_eq!(message.message, expected.message);
+        }
+    }
+
+    #[test]
+    fn keyframe_before() {
+        let mut recording = Recording::new(ProtocolHash::new(0));
+        for (index, keyframe) in [false, true, false, true, false].into_iter().enumerate() {
+            recording.push(RecordedMessage {
+                timestamp: Duration::from_millis(index as u64 * 10),
+                channel_id: 0,
+                keyframe,
+                message: Bytes::new(),
+            });
+        }
+
+        assert_eq!(recording.keyframe_before(Duration::ZERO), None);
+        assert_eq!(recording.keyframe_before(Duration::from_millis(9)), None);
+        assert_eq!(
+            recording.keyframe_before(Duration::from_millis(10)),
+            Some(1)
+        );
+        assert_eq!(
+            recording.keyframe_before(Duration::from_millis(29)),
+            Some(1)
+        );
+        assert_eq!(
+            recording.keyframe_before(Duration::from_millis(30)),
+            Some(3)
+        );
+        assert_eq!(recording.keyframe_before(Duration::MAX), Some(3));
+        assert_eq!(
+            Recording::new(ProtocolHash::new(0)).keyframe_before(Duration::MAX),
+            None
+        );
+    }
+}
@@ -3,10 +3,19 @@ pub mod component_fns;
 pub mod rule_fns;
 pub mod test_fns;
 
-use bevy::{ecs::component::ComponentId, prelude::*};
-use serde::{Deserialize, Serialize};
-
-use super::{command_markers::CommandMarkerIndex, ctx::DespawnCtx};
+use std::{
+    any,
+    hash::{Hash, Hasher},
+    io::{Cursor, Read, Write},
+};
+
+use bevy::{
+    ecs::component::{ComponentId, Components},
+    prelude::*,
+};
+use bincode::{DefaultOptions, Options};
+
+use super::{command_markers::CommandMarkerIndex, ctx::DespawnCtx, protocol::StableIds, IdPolicy};
 use command_fns::{RemoveFn, UntypedCommandFns, WriteFn};
 use component_fns::ComponentFns;
 use rule_fns::{RuleFns, UntypedRuleFns};
@@ -32,13 +41,37 @@ pub struct ReplicationRegistry {
     /// [`ReplicationRule`](super::replication_rules::ReplicationRule)
     rules: Vec<(UntypedRuleFns, usize)>,
 
+    /// Identification of [`Self::rules`] in messages.
+    id_policy: IdPolicy,
+
+    /// Stable IDs for each element in [`Self::rules`].
+    ///
+    /// Used only with [`IdPolicy::TypeHash`].
+    rule_ids: Vec<u32>,
+
+    /// Maps [`Self::rule_ids`] into indices of [`Self::rules`].
+    stable_ids: StableIds,
+
     /// Number of registered markers.
     ///
     /// Used to initialize new [`ComponentFns`] with the registered number of slots.
     marker_slots: usize,
+
+    /// Indicates if any rule functions use delta compression.
+    ///
+    /// See also [`RuleFns::with_delta_compression`].
+    delta_compression: bool,
 }
 
 impl ReplicationRegistry {
+    /// Creates an empty registry with the specified ID policy.
+    pub(super) fn with_id_policy(id_policy: IdPolicy) -> Self {
+        Self {
+            id_policy,
+            ..Default::default()
+        }
+    }
+
     /// Registers marker slot for component functions.
     ///
     /// Should be used after calling
@@ -105,7 +138,21 @@ impl ReplicationRegistry {
         rule_fns: RuleFns<C>,
     ) -> FnsInfo {
         let (index, component_id) = self.init_component_fns::<C>(world);
-        self.rules.push((rule_fns.into(), index));
+        if self.id_policy == IdPolicy::TypeHash {
+            let occurrence = self
+                .rules
+                .iter()
+                .filter(|&&(_, rule_index)| rule_index == index)
+                .count();
+            let id = self
+                .stable_ids
+                .register(any::type_name::<C>(), occurrence, self.rules.len());
+            self.rule_ids.push(id);
+        }
+
+        let rule_fns: UntypedRuleFns = rule_fns.into();
+        self.delta_compression |= rule_fns.delta_compression();
+        self.rules.push((rule_fns, index));
 
         FnsInfo {
             component_id,
@@ -113,6 +160,66 @@ impl ReplicationRegistry {
         }
     }
 
+    /// Hashes names of components for each registered rule functions and their wire format.
+    ///
+    /// With [`IdPolicy::TypeHash`] rules are hashed in the order of their IDs
+    /// to make the result independent of the registration order.
+    ///
+    /// See also [`ProtocolHash`](super::protocol::ProtocolHash).
+    pub(crate) fn hash_rules(&self, components: &Components, hasher: &mut impl Hasher) {
+        let mut order: Vec<_> = (0..self.rules.len()).collect();
+        if self.id_policy == IdPolicy::TypeHash {
+            order.sort_unstable_by_key(|&index| self.rule_ids[index]);
+        }
+
+        self.rules.len().hash(hasher);
+        for index in order {
+            let (rule_fns, component_index) = &self.rules[index];
+            let (_, component_id) = self.components[*component_index];
+            let info = components
+                .get_info(component_id)
+                .expect("registered components should be initialized");
+            info.name().hash(hasher);
+            rule_fns.delta_compression().hash(hasher);
+        }
+    }
+
+    /// Writes replication functions ID according to the [`IdPolicy`].
+    ///
+    /// Registration index is written as a varint for [`IdPolicy::RegistrationOrder`]
+    /// and stable ID is written as a fixed-size integer for [`IdPolicy::TypeHash`].
+    pub(crate) fn write_fns_id(&self, writer: impl Write, fns_id: FnsId) -> bincode::Result<()> {
+        match self.id_policy {
+            IdPolicy::RegistrationOrder => DefaultOptions::new().serialize_into(writer, &fns_id.0),
+            IdPolicy::TypeHash => {
+                let id = self.rule_ids[fns_id.0];
+                bincode::serialize_into(writer, &id)
+            }
+        }
+    }
+
+    /// Reads replication functions ID written by [`Self::write_fns_id`].
+    ///
+    /// Returns an error if there are no functions with such ID.
+    pub(crate) fn read_fns_id(&self, cursor: &mut Cursor<&[u8]>) -> bincode::Result<FnsId> {
+        let index = match self.id_policy {
+            IdPolicy::RegistrationOrder => {
+                let index: usize = DefaultOptions::new().deserialize_from(cursor.by_ref())?;
+                (index < self.rules.len()).then_some(index)
+            }
+            IdPolicy::TypeHash => {
+                let id: u32 = bincode::deserialize_from(cursor.by_ref())?;
+                self.stable_ids.index(id)
+            }
+        };
+
+        index.map(FnsId).ok_or_else(|| {
+            Box::new(bincode::ErrorKind::Custom(
+                "received unknown replication functions ID".into(),
+            ))
+        })
+    }
+
     /// Initializes [`ComponentFns`] for a component and returns its index and ID.
     ///
     /// If a [`ComponentFns`] has already been created for this component,
@@ -132,6 +239,26 @@ impl ReplicationRegistry {
         (index, component_id)
     }
 
+    /// Returns ID of the first registered rule functions for a component.
+    ///
+    /// Can be used to send a removal for a component without knowing its rule.
+    pub(crate) fn component_fns_id(&self, component_id: ComponentId) -> Option<FnsId> {
+        self.rules
+            .iter()
+            .position(|&(_, index)| self.components[index].1 == component_id)
+            .map(FnsId)
+    }
+
+    /// Returns `true` if any registered rule functions use delta compression.
+    pub(crate) fn has_delta_compression(&self) -> bool {
+        self.delta_compression
+    }
+
+    /// Returns functions and IDs of all replicated components.
+    pub(crate) fn components(&self) -> impl Iterator<Item = &(ComponentFns, ComponentId)> {
+        self.components.iter()
+    }
+
     /// Returns associates functions.
     ///
     /// See also [`Self::register_rule_fns`].
@@ -154,7 +281,11 @@ impl Default for ReplicationRegistry {
             despawn: despawn_recursive,
             components: Default::default(),
             rules: Default::default(),
+            id_policy: Default::default(),
+            rule_ids: Default::default(),
+            stable_ids: Default::default(),
             marker_slots: 0,
+            delta_compression: false,
         }
     }
 }
@@ -184,7 +315,7 @@ impl FnsInfo {
 /// ID of replicaton functions for a component.
 ///
 /// Can be obtained from [`ReplicationFns::register_rule_fns`].
-#[derive(Clone, Copy, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
 pub struct FnsId(usize);
 
 /// Signature of the entity despawn function.
@@ -198,6 +329,7 @@ pub fn despawn_recursive(_ctx: &DespawnCtx, entity: EntityWorldMut) {
 #[cfg(test)]
 mod tests {
     use bevy::ecs::entity::MapEntities;
+    use serde::{Deserialize, Serialize};
 
     use super::*;
 
@@ -236,6 +368,38 @@ mod tests {
         assert_eq!(registry.components.len(), 2);
     }
 
+    #[test]
+    fn stable_ids() {
+        let mut world = World::new();
+        let mut registry = ReplicationRegistry::with_id_policy(IdPolicy::TypeHash);
+        let fns_a = registry.register_rule_fns(&mut world, RuleFns::<ComponentA>::default());
+        let fns_b = registry.register_rule_fns(&mut world, RuleFns::<ComponentB>::default());
+
+        let mut other_world = World::new();
+        let mut other_registry = ReplicationRegistry::with_id_policy(IdPolicy::TypeHash);
+        let other_fns_b =
+            other_registry.register_rule_fns(&mut other_world, RuleFns::<ComponentB>::default());
+        let other_fns_a =
+            other_registry.register_rule_fns(&mut other_world, RuleFns::<ComponentA>::default());
+
+        for (fns_info, other_fns_info) in [(fns_a, other_fns_a), (fns_b, other_fns_b)] {
+            let mut message = Vec::new();
+            registry
+                .write_fns_id(&mut message, fns_info.fns_id())
+                .unwrap();
+            assert_eq!(message.len(), 4);
+
+            let fns_id = other_registry
+                .read_fns_id(&mut Cursor::new(&message))
+                .unwrap();
+            assert!(fns_id == other_fns_info.fns_id());
+        }
+
+        assert!(other_registry
+            .read_fns_id(&mut Cursor::new(&[0; 4]))
+            .is_err());
+    }
+
     #[derive(Component, Serialize, Deserialize)]
     struct ComponentA;
 
@@ -149,6 +149,20 @@ impl ComponentFns {
         }
     }
 
+    /// Calls the assigned consuming function to skip the component's data.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `rule_fns` was created for the same type as this instance.
+    pub(crate) unsafe fn consume(
+        &self,
+        ctx: &mut WriteCtx,
+        rule_fns: &UntypedRuleFns,
+        cursor: &mut Cursor<&[u8]>,
+    ) -> bincode::Result<()> {
+        (self.consume)(ctx, rule_fns, cursor)
+    }
+
     /// Same as [`Self::write`], but calls the assigned remove function.
     pub(crate) fn remove(
         &self,
@@ -21,9 +21,17 @@ pub(crate) struct UntypedRuleFns {
     deserialize: unsafe fn(),
     deserialize_in_place: unsafe fn(),
     consume: unsafe fn(),
+    delta_compression: bool,
 }
 
 impl UntypedRuleFns {
+    /// Returns `true` if changes should be sent as diffs against the last acknowledged value.
+    ///
+    /// See also [`RuleFns::with_delta_compression`].
+    pub(crate) fn delta_compression(&self) -> bool {
+        self.delta_compression
+    }
+
     /// Restores the original [`RuleFns`] from which this type was created.
     ///
     /// # Safety
@@ -43,6 +51,7 @@ impl UntypedRuleFns {
             deserialize: unsafe { mem::transmute(self.deserialize) },
             deserialize_in_place: unsafe { mem::transmute(self.deserialize_in_place) },
             consume: unsafe { mem::transmute(self.consume) },
+            delta_compression: self.delta_compression,
         }
     }
 }
@@ -57,12 +66,15 @@ impl<C: Component> From<RuleFns<C>> for UntypedRuleFns {
             deserialize: unsafe { mem::transmute(value.deserialize) },
             deserialize_in_place: unsafe { mem::transmute(value.deserialize_in_place) },
             consume: unsafe { mem::transmute(value.consume) },
+            delta_compression: value.delta_compression,
         }
     }
 }
 
 /// Serialization and deserialization functions for a component.
 ///
+/// Also used for resources, see [`AppResourceExt`](crate::core::replication_resources::AppResourceExt).
+///
 /// See also [`AppRuleExt`](crate::core::replication_rules::AppRuleExt)
 /// and [`ReplicationRule`](crate::core::replication_rules::ReplicationRule).
 pub struct RuleFns<C> {
@@ -70,9 +82,18 @@ pub struct RuleFns<C> {
     deserialize: DeserializeFn<C>,
     deserialize_in_place: DeserializeInPlaceFn<C>,
     consume: ConsumeFn<C>,
+    delta_compression: bool,
 }
 
-impl<C: Component> RuleFns<C> {
+impl<C> Clone for RuleFns<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C> Copy for RuleFns<C> {}
+
+impl<C> RuleFns<C> {
     /// Creates a new instance.
     ///
     /// See also [`Self::with_in_place`] and [`Self::with_consume`].
@@ -82,6 +103,7 @@ impl<C: Component> RuleFns<C> {
             deserialize,
             deserialize_in_place: in_place_as_deserialize::<C>,
             consume: consume_as_deserialize,
+            delta_compression: false,
         }
     }
 
@@ -112,8 +134,40 @@ impl<C: Component> RuleFns<C> {
         self
     }
 
+    /**
+    Enables delta compression for component changes.
+
+    The server will remember the serialized value of the component that each client acknowledged
+    and send only the difference between it and the new serialized value.
+    The client will reconstruct the serialized value and pass it to the deserialization
+    functions as usual, so [`Self::deserialize_in_place`] will patch the existing component.
+
+    Useful for large components where only a small part changes at a time.
+    Init messages and changes without an acknowledged value will contain the full value.
+    If the difference is not smaller than the full value, the full value will be sent instead.
+
+    # Examples
+
+    ```
+    use bevy::prelude::*;
+    use bevy_replicon::{core::replication_registry::rule_fns::RuleFns, prelude::*};
+    use serde::{Deserialize, Serialize};
+
+    # let mut app = App::new();
+    # app.add_plugins(RepliconPlugins);
+    app.replicate_with(RuleFns::<Inventory>::default().with_delta_compression());
+
+    #[derive(Component, Deserialize, Serialize)]
+    struct Inventory(Vec<u32>);
+    ```
+    */
+    pub fn with_delta_compression(mut self) -> Self {
+        self.delta_compression = true;
+        self
+    }
+
     /// Serializes a component into a cursor.
-    pub(super) fn serialize(
+    pub fn serialize(
         &self,
         ctx: &SerializeCtx,
         component: &C,
@@ -155,7 +209,7 @@ impl<C: Component> RuleFns<C> {
     }
 }
 
-impl<C: Component + Serialize + DeserializeOwned + MapEntities> RuleFns<C> {
+impl<C: Serialize + DeserializeOwned + MapEntities> RuleFns<C> {
     /// Like [`Self::default`], but uses a special deserialization function to map server
     /// entities inside the component into client entities.
     ///
@@ -167,7 +221,7 @@ impl<C: Component + Serialize + DeserializeOwned + MapEntities> RuleFns<C> {
     }
 }
 
-impl<C: Component + Serialize + DeserializeOwned> Default for RuleFns<C> {
+impl<C: Serialize + DeserializeOwned> Default for RuleFns<C> {
     /// Creates a new instance with default functions for a component.
     ///
     /// If your component contains any [`Entity`] inside, use [`Self::default_mapped`].
@@ -193,7 +247,7 @@ pub type ConsumeFn<C> =
     fn(DeserializeFn<C>, &mut WriteCtx, &mut Cursor<&[u8]>) -> bincode::Result<()>;
 
 /// Default component serialization function.
-pub fn default_serialize<C: Component + Serialize>(
+pub fn default_serialize<C: Serialize>(
     _ctx: &SerializeCtx,
     component: &C,
     cursor: &mut Cursor<Vec<u8>>,
@@ -202,7 +256,7 @@ pub fn default_serialize<C: Component + Serialize>(
 }
 
 /// Default component deserialization function.
-pub fn default_deserialize<C: Component + DeserializeOwned>(
+pub fn default_deserialize<C: DeserializeOwned>(
     _ctx: &mut WriteCtx,
     cursor: &mut Cursor<&[u8]>,
 ) -> bincode::Result<C> {
@@ -210,7 +264,7 @@ pub fn default_deserialize<C: Component + DeserializeOwned>(
 }
 
 /// Like [`default_deserialize`], but also maps entities before insertion.
-pub fn default_deserialize_mapped<C: Component + DeserializeOwned + MapEntities>(
+pub fn default_deserialize_mapped<C: DeserializeOwned + MapEntities>(
     ctx: &mut WriteCtx,
     cursor: &mut Cursor<&[u8]>,
 ) -> bincode::Result<C> {
@@ -222,7 +276,7 @@ pub fn default_deserialize_mapped<C: Component + DeserializeOwned + MapEntities>
 /// Default component in-place deserialization function.
 ///
 /// This implementation just assigns the value from the passed deserialization function.
-pub fn in_place_as_deserialize<C: Component>(
+pub fn in_place_as_deserialize<C>(
     deserialize: DeserializeFn<C>,
     ctx: &mut WriteCtx,
     component: &mut C,
@@ -235,7 +289,7 @@ pub fn in_place_as_deserialize<C: Component>(
 /// Default component consume function.
 ///
 /// This implementation just calls deserialization function and ignores its result.
-pub fn consume_as_deserialize<C: Component>(
+pub fn consume_as_deserialize<C>(
     deserialize: DeserializeFn<C>,
     ctx: &mut WriteCtx,
     cursor: &mut Cursor<&[u8]>,
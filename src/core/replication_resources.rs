@@ -0,0 +1,409 @@
+use std::{
+    any::{self, Any},
+    hash::{Hash, Hasher},
+    io::{Cursor, Read, Write},
+};
+
+use bevy::{
+    ecs::{component::ComponentTicks, entity::MapEntities},
+    prelude::*,
+    state::state::FreelyMutableState,
+};
+use bincode::{DefaultOptions, Options};
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{
+    ctx::{SerializeCtx, WriteCtx},
+    protocol::StableIds,
+    replication_registry::rule_fns::RuleFns,
+    IdPolicy,
+};
+
+/// Resource replication functions for [`App`].
+pub trait AppResourceExt {
+    /// Registers a resource for replication.
+    ///
+    /// Insertions, changes and removals of the resource on server will be sent
+    /// to all clients in init messages, so they will be applied in the same tick
+    /// as entity changes that happened together with them.
+    /// Clients that connect later receive the current value.
+    ///
+    /// Resource will be serialized and deserialized as-is using bincode.
+    /// To customize it, use [`Self::replicate_resource_with`].
+    ///
+    /// If your resource contains any [`Entity`] inside, use [`Self::replicate_resource_mapped`].
+    fn replicate_resource<R>(&mut self) -> &mut Self
+    where
+        R: Resource + Serialize + DeserializeOwned,
+    {
+        self.replicate_resource_with::<R>(RuleFns::default())
+    }
+
+    /// Same as [`Self::replicate_resource`], but additionally maps server entities
+    /// to client inside the resource after receiving.
+    ///
+    /// Always use it for resources that contain entities.
+    fn replicate_resource_mapped<R>(&mut self) -> &mut Self
+    where
+        R: Resource + Serialize + DeserializeOwned + MapEntities,
+    {
+        self.replicate_resource_with::<R>(RuleFns::default_mapped())
+    }
+
+    /**
+    Same as [`Self::replicate_resource`], but uses the specified functions for serialization and deserialization.
+
+    Only [`RuleFns::serialize`] and [`RuleFns::deserialize`] are used for resources.
+    Received values always replace the current value of the resource.
+
+    # Examples
+
+    ```
+    use std::io::Cursor;
+
+    use bevy::prelude::*;
+    use bevy_replicon::{
+        core::{
+            ctx::{SerializeCtx, WriteCtx},
+            replication_registry::rule_fns::RuleFns,
+        },
+        prelude::*,
+    };
+
+    # let mut app = App::new();
+    # app.add_plugins(RepliconPlugins);
+    app.replicate_resource_with(RuleFns::new(serialize_timer, deserialize_timer));
+
+    /// Sends only the remaining seconds as `u16`.
+    fn serialize_timer(
+        _ctx: &SerializeCtx,
+        timer: &RoundTimer,
+        cursor: &mut Cursor<Vec<u8>>,
+    ) -> bincode::Result<()> {
+        bincode::serialize_into(cursor, &(timer.0 as u16))
+    }
+
+    fn deserialize_timer(
+        _ctx: &mut WriteCtx,
+        cursor: &mut Cursor<&[u8]>,
+    ) -> bincode::Result<RoundTimer> {
+        let seconds: u16 = bincode::deserialize_from(cursor)?;
+        Ok(RoundTimer(seconds as f32))
+    }
+
+    #[derive(Resource)]
+    struct RoundTimer(f32);
+    ```
+    **/
+    fn replicate_resource_with<R: Resource>(&mut self, rule_fns: RuleFns<R>) -> &mut Self;
+
+    /**
+    Registers a [`States`] type for replication.
+
+    Transitions of [`State<S>`] on server will be sent to all clients in init messages.
+    Instead of writing [`State<S>`] directly, the client will set [`NextState<S>`], so the transition
+    will run in [`StateTransition`] on the same frame
+    when the init message is received, after entity changes from the same tick are applied.
+
+    The state should be initialized on client as usual.
+
+    # Examples
+
+    ```
+    # use bevy::{prelude::*, state::app::StatesPlugin};
+    # use bevy_replicon::prelude::*;
+    # use serde::{Deserialize, Serialize};
+    # let mut app = App::new();
+    # app.add_plugins((StatesPlugin, RepliconPlugins));
+    app.init_state::<GameState>()
+        .replicate_state::<GameState>();
+
+    #[derive(States, Default, Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+    enum GameState {
+        #[default]
+        Lobby,
+        InGame,
+    }
+    ```
+    **/
+    fn replicate_state<S>(&mut self) -> &mut Self
+    where
+        S: FreelyMutableState + Serialize + DeserializeOwned;
+}
+
+impl AppResourceExt for App {
+    fn replicate_resource_with<R: Resource>(&mut self, rule_fns: RuleFns<R>) -> &mut Self {
+        debug!("registering resource `{}`", any::type_name::<R>());
+
+        self.world_mut()
+            .resource_mut::<ReplicationResources>()
+            .register(rule_fns, write::<R>);
+
+        self
+    }
+
+    fn replicate_state<S>(&mut self) -> &mut Self
+    where
+        S: FreelyMutableState + Serialize + DeserializeOwned,
+    {
+        debug!("registering state `{}`", any::type_name::<S>());
+
+        let rule_fns = RuleFns::new(serialize_state::<S>, deserialize_state::<S>);
+        self.world_mut()
+            .resource_mut::<ReplicationResources>()
+            .register(rule_fns, write_state::<S>);
+
+        self
+    }
+}
+
+/// Registered resources for replication.
+///
+/// See also [`AppResourceExt`].
+#[derive(Resource)]
+pub struct ReplicationResources {
+    resources: Vec<ResourceFns>,
+
+    /// Identification of [`Self::resources`] in messages.
+    id_policy: IdPolicy,
+
+    /// Stable IDs for each element in [`Self::resources`].
+    ///
+    /// Used only with [`IdPolicy::TypeHash`].
+    resource_ids: Vec<u32>,
+
+    /// Maps [`Self::resource_ids`] into indices of [`Self::resources`].
+    stable_ids: StableIds,
+}
+
+impl ReplicationResources {
+    /// Creates an empty registry with the specified ID policy.
+    pub(super) fn with_id_policy(id_policy: IdPolicy) -> Self {
+        Self {
+            resources: Default::default(),
+            id_policy,
+            resource_ids: Default::default(),
+            stable_ids: Default::default(),
+        }
+    }
+
+    /// Registers functions for a resource.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is already registered.
+    fn register<R: Resource>(&mut self, rule_fns: RuleFns<R>, write: ResourceWriteFn) {
+        let type_name = any::type_name::<R>();
+        if self.id_policy == IdPolicy::TypeHash {
+            let id = self.stable_ids.register(type_name, 0, self.resources.len());
+            self.resource_ids.push(id);
+        } else if self.resources.iter().any(|fns| fns.type_name == type_name) {
+            panic!("`{type_name}` shouldn't be registered more than once");
+        }
+
+        self.resources.push(ResourceFns::new(rule_fns, write));
+    }
+
+    /// Returns the number of registered resources.
+    pub(crate) fn len(&self) -> usize {
+        self.resources.len()
+    }
+
+    /// Returns functions for a resource by its index.
+    pub(crate) fn get(&self, index: usize) -> &ResourceFns {
+        &self.resources[index]
+    }
+
+    /// Hashes names of registered resources.
+    ///
+    /// With [`IdPolicy::TypeHash`] resources are hashed in the order of their IDs
+    /// to make the result independent of the registration order.
+    ///
+    /// See also [`ProtocolHash`](super::protocol::ProtocolHash).
+    pub(crate) fn hash_resources(&self, hasher: &mut impl Hasher) {
+        let mut order: Vec<_> = (0..self.resources.len()).collect();
+        if self.id_policy == IdPolicy::TypeHash {
+            order.sort_unstable_by_key(|&index| self.resource_ids[index]);
+        }
+
+        self.resources.len().hash(hasher);
+        for index in order {
+            self.resources[index].type_name.hash(hasher);
+        }
+    }
+
+    /// Writes resource ID according to the [`IdPolicy`].
+    ///
+    /// Registration index is written as a varint for [`IdPolicy::RegistrationOrder`]
+    /// and stable ID is written as a fixed-size integer for [`IdPolicy::TypeHash`].
+    pub(crate) fn write_id(&self, writer: impl Write, index: usize) -> bincode::Result<()> {
+        match self.id_policy {
+            IdPolicy::RegistrationOrder => DefaultOptions::new().serialize_into(writer, &index),
+            IdPolicy::TypeHash => {
+                let id = self.resource_ids[index];
+                bincode::serialize_into(writer, &id)
+            }
+        }
+    }
+
+    /// Reads resource ID written by [`Self::write_id`] and returns the resource index.
+    ///
+    /// Returns an error if there is no resource with such ID.
+    pub(crate) fn read_id(&self, cursor: &mut Cursor<&[u8]>) -> bincode::Result<usize> {
+        let index = match self.id_policy {
+            IdPolicy::RegistrationOrder => {
+                let index: usize = DefaultOptions::new().deserialize_from(cursor.by_ref())?;
+                (index < self.resources.len()).then_some(index)
+            }
+            IdPolicy::TypeHash => {
+                let id: u32 = bincode::deserialize_from(cursor.by_ref())?;
+                self.stable_ids.index(id)
+            }
+        };
+
+        index.ok_or_else(|| {
+            Box::new(bincode::ErrorKind::Custom(
+                "received unknown resource ID".into(),
+            ))
+        })
+    }
+}
+
+impl Default for ReplicationResources {
+    fn default() -> Self {
+        Self::with_id_policy(Default::default())
+    }
+}
+
+/// Type-erased functions for a replicated resource.
+pub(crate) struct ResourceFns {
+    type_name: &'static str,
+
+    /// [`RuleFns`] for the resource type.
+    rule_fns: Box<dyn Any + Send + Sync>,
+
+    change_ticks: fn(&World) -> Option<ComponentTicks>,
+    serialize: fn(&dyn Any, &SerializeCtx, &World, &mut Cursor<Vec<u8>>) -> bincode::Result<()>,
+    write: ResourceWriteFn,
+    remove: fn(&mut Commands),
+}
+
+/// Signature of functions that deserialize a resource using type-erased [`RuleFns`] and write it.
+type ResourceWriteFn = fn(&dyn Any, &mut WriteCtx, &mut Cursor<&[u8]>) -> bincode::Result<()>;
+
+impl ResourceFns {
+    fn new<R: Resource>(rule_fns: RuleFns<R>, write: ResourceWriteFn) -> Self {
+        Self {
+            type_name: any::type_name::<R>(),
+            rule_fns: Box::new(rule_fns),
+            change_ticks: change_ticks::<R>,
+            serialize: serialize::<R>,
+            write,
+            remove: remove::<R>,
+        }
+    }
+
+    /// Returns type name of the resource.
+    pub(crate) fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns change ticks of the resource or [`None`] if it doesn't exist.
+    pub(crate) fn change_ticks(&self, world: &World) -> Option<ComponentTicks> {
+        (self.change_ticks)(world)
+    }
+
+    /// Serializes the resource from the world.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource doesn't exist.
+    pub(crate) fn serialize(
+        &self,
+        ctx: &SerializeCtx,
+        world: &World,
+        cursor: &mut Cursor<Vec<u8>>,
+    ) -> bincode::Result<()> {
+        (self.serialize)(&*self.rule_fns, ctx, world, cursor)
+    }
+
+    /// Deserializes the resource and inserts it using commands from `ctx`.
+    pub(crate) fn write(
+        &self,
+        ctx: &mut WriteCtx,
+        cursor: &mut Cursor<&[u8]>,
+    ) -> bincode::Result<()> {
+        (self.write)(&*self.rule_fns, ctx, cursor)
+    }
+
+    /// Removes the resource using commands.
+    pub(crate) fn remove(&self, commands: &mut Commands) {
+        (self.remove)(commands)
+    }
+}
+
+fn change_ticks<R: Resource>(world: &World) -> Option<ComponentTicks> {
+    world.get_resource_change_ticks::<R>()
+}
+
+fn serialize<R: Resource>(
+    rule_fns: &dyn Any,
+    ctx: &SerializeCtx,
+    world: &World,
+    cursor: &mut Cursor<Vec<u8>>,
+) -> bincode::Result<()> {
+    let rule_fns = typed::<R>(rule_fns);
+    rule_fns.serialize(ctx, world.resource::<R>(), cursor)
+}
+
+fn write<R: Resource>(
+    rule_fns: &dyn Any,
+    ctx: &mut WriteCtx,
+    cursor: &mut Cursor<&[u8]>,
+) -> bincode::Result<()> {
+    let rule_fns = typed::<R>(rule_fns);
+    let resource = rule_fns.deserialize(ctx, cursor)?;
+    ctx.commands.insert_resource(resource);
+
+    Ok(())
+}
+
+/// Sets [`NextState<S>`] instead of writing [`State<S>`] directly to trigger the transition.
+fn write_state<S: FreelyMutableState>(
+    rule_fns: &dyn Any,
+    ctx: &mut WriteCtx,
+    cursor: &mut Cursor<&[u8]>,
+) -> bincode::Result<()> {
+    let rule_fns = typed::<State<S>>(rule_fns);
+    let state = rule_fns.deserialize(ctx, cursor)?;
+    ctx.commands
+        .insert_resource(NextState::Pending(state.get().clone()));
+
+    Ok(())
+}
+
+fn serialize_state<S: States + Serialize>(
+    _ctx: &SerializeCtx,
+    state: &State<S>,
+    cursor: &mut Cursor<Vec<u8>>,
+) -> bincode::Result<()> {
+    DefaultOptions::new().serialize_into(cursor, state.get())
+}
+
+fn deserialize_state<S: States + DeserializeOwned>(
+    _ctx: &mut WriteCtx,
+    cursor: &mut Cursor<&[u8]>,
+) -> bincode::Result<State<S>> {
+    let state = DefaultOptions::new().deserialize_from(cursor)?;
+    Ok(State::new(state))
+}
+
+fn remove<R: Resource>(commands: &mut Commands) {
+    commands.remove_resource::<R>();
+}
+
+fn typed<R: Resource>(rule_fns: &dyn Any) -> &RuleFns<R> {
+    rule_fns
+        .downcast_ref()
+        .expect("resource functions should be called with the type they were created with")
+}
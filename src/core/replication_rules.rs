@@ -7,7 +7,10 @@ use bevy::{
 };
 use serde::{de::DeserializeOwned, Serialize};
 
-use super::replication_registry::{rule_fns::RuleFns, FnsInfo, ReplicationRegistry};
+use super::{
+    replication_registry::{rule_fns::RuleFns, FnsInfo, ReplicationRegistry},
+    replicon_tick::RepliconTick,
+};
 
 /// Replication functions for [`App`].
 pub trait AppRuleExt {
@@ -21,8 +24,8 @@ pub trait AppRuleExt {
     ///
     /// If your component contains any [`Entity`] inside, use [`Self::replicate_mapped`].
     ///
-    /// See also [`Self::replicate_with`] and the section on [`components`](../../index.html#components)
-    /// from the quick start guide.
+    /// See also [`Self::replicate_with`], [`Self::replicate_periodic`] and the section on
+    /// [`components`](../../index.html#components) from the quick start guide.
     fn replicate<C>(&mut self) -> &mut Self
     where
         C: Component + Serialize + DeserializeOwned,
@@ -30,6 +33,40 @@ pub trait AppRuleExt {
         self.replicate_with::<C>(RuleFns::default())
     }
 
+    /**
+    Same as [`Self::replicate`], but changes will be sent at most once per `period` ticks.
+
+    Useful for components that change often, but don't need to be updated on clients immediately.
+    See [`SendRate::Periodic`] for details.
+
+    # Examples
+
+    ```
+    # use bevy::prelude::*;
+    # use bevy_replicon::prelude::*;
+    # use serde::{Deserialize, Serialize};
+    # let mut app = App::new();
+    # app.add_plugins(RepliconPlugins);
+    app.replicate::<Health>()
+        .replicate_periodic::<Stats>(10);
+
+    #[derive(Component, Deserialize, Serialize)]
+    struct Health(u32);
+
+    #[derive(Component, Deserialize, Serialize)]
+    struct Stats {
+        kills: u32,
+        deaths: u32,
+    }
+    ```
+    **/
+    fn replicate_periodic<C>(&mut self, period: u32) -> &mut Self
+    where
+        C: Component + Serialize + DeserializeOwned,
+    {
+        self.replicate_with_rate::<C>(RuleFns::default(), SendRate::Periodic(period))
+    }
+
     /**
     Same as [`Self::replicate`], but additionally maps server entities to client inside the component after receiving.
 
@@ -114,6 +151,49 @@ pub trait AppRuleExt {
     ```
     */
     fn replicate_with<C>(&mut self, rule_fns: RuleFns<C>) -> &mut Self
+    where
+        C: Component,
+    {
+        self.replicate_with_rate(rule_fns, SendRate::EveryTick)
+    }
+
+    /// Same as [`Self::replicate_with`], but also configures how often changes will be sent.
+    ///
+    /// See also [`Self::replicate_periodic`].
+    fn replicate_with_rate<C>(&mut self, rule_fns: RuleFns<C>, send_rate: SendRate) -> &mut Self
+    where
+        C: Component;
+
+    /**
+    Same as [`Self::replicate`], but changes will be sent reliably.
+
+    See [`ReplicationRule::reliable`] for details.
+
+    # Examples
+
+    ```
+    # use bevy::prelude::*;
+    # use bevy_replicon::prelude::*;
+    # use serde::{Deserialize, Serialize};
+    # let mut app = App::new();
+    # app.add_plugins(RepliconPlugins);
+    app.replicate_reliable::<OwnedItems>();
+
+    #[derive(Component, Deserialize, Serialize)]
+    struct OwnedItems(Vec<u32>);
+    ```
+    **/
+    fn replicate_reliable<C>(&mut self) -> &mut Self
+    where
+        C: Component + Serialize + DeserializeOwned,
+    {
+        self.replicate_reliable_with::<C>(RuleFns::default())
+    }
+
+    /// Same as [`Self::replicate_with`], but changes will be sent reliably.
+    ///
+    /// See [`ReplicationRule::reliable`] for details.
+    fn replicate_reliable_with<C>(&mut self, rule_fns: RuleFns<C>) -> &mut Self
     where
         C: Component;
 
@@ -156,11 +236,19 @@ pub trait AppRuleExt {
     struct Player;
     ```
     **/
-    fn replicate_group<C: GroupReplication>(&mut self) -> &mut Self;
+    fn replicate_group<C: GroupReplication>(&mut self) -> &mut Self {
+        self.replicate_group_with_rate::<C>(SendRate::EveryTick)
+    }
+
+    /// Same as [`Self::replicate_group`], but also configures how often changes will be sent.
+    ///
+    /// Overrides the send rate from [`GroupReplication::register`].
+    /// See also [`Self::replicate_periodic`].
+    fn replicate_group_with_rate<C: GroupReplication>(&mut self, send_rate: SendRate) -> &mut Self;
 }
 
 impl AppRuleExt for App {
-    fn replicate_with<C>(&mut self, rule_fns: RuleFns<C>) -> &mut Self
+    fn replicate_with_rate<C>(&mut self, rule_fns: RuleFns<C>, send_rate: SendRate) -> &mut Self
     where
         C: Component,
     {
@@ -168,7 +256,7 @@ impl AppRuleExt for App {
             self.world_mut()
                 .resource_scope(|world, mut registry: Mut<ReplicationRegistry>| {
                     let fns_info = registry.register_rule_fns(world, rule_fns);
-                    ReplicationRule::new(vec![fns_info])
+                    ReplicationRule::new(vec![fns_info]).with_send_rate(send_rate)
                 });
 
         self.world_mut()
@@ -178,11 +266,29 @@ impl AppRuleExt for App {
         self
     }
 
-    fn replicate_group<C: GroupReplication>(&mut self) -> &mut Self {
+    fn replicate_reliable_with<C>(&mut self, rule_fns: RuleFns<C>) -> &mut Self
+    where
+        C: Component,
+    {
         let rule =
             self.world_mut()
                 .resource_scope(|world, mut registry: Mut<ReplicationRegistry>| {
-                    C::register(world, &mut registry)
+                    let fns_info = registry.register_rule_fns(world, rule_fns);
+                    ReplicationRule::new(vec![fns_info]).reliable()
+                });
+
+        self.world_mut()
+            .resource_mut::<ReplicationRules>()
+            .insert(rule);
+
+        self
+    }
+
+    fn replicate_group_with_rate<C: GroupReplication>(&mut self, send_rate: SendRate) -> &mut Self {
+        let rule =
+            self.world_mut()
+                .resource_scope(|world, mut registry: Mut<ReplicationRegistry>| {
+                    C::register(world, &mut registry).with_send_rate(send_rate)
                 });
 
         self.world_mut()
@@ -199,7 +305,7 @@ pub(crate) struct ReplicationRules(Vec<ReplicationRule>);
 
 impl ReplicationRules {
     /// Inserts a new rule, maintaining sorting by their priority in descending order.
-    fn insert(&mut self, rule: ReplicationRule) {
+    pub(crate) fn insert(&mut self, rule: ReplicationRule) {
         let index = self
             .binary_search_by_key(&Reverse(rule.priority), |rule| Reverse(rule.priority))
             .unwrap_or_else(|index| index);
@@ -218,17 +324,48 @@ pub struct ReplicationRule {
 
     /// Rule components and their serialization/deserialization/removal functions.
     pub components: Vec<FnsInfo>,
+
+    /// How often changes of the rule components will be sent.
+    ///
+    /// See also [`Self::with_send_rate`].
+    pub(crate) send_rate: SendRate,
+
+    /// Send changes of the rule components in init messages instead of update messages.
+    ///
+    /// See also [`Self::reliable`].
+    pub(crate) reliable: bool,
 }
 
 impl ReplicationRule {
     /// Creates a new rule with priority equal to the number of serializable components.
+    ///
+    /// Changes will be sent every tick, see also [`Self::with_send_rate`].
     pub fn new(components: Vec<FnsInfo>) -> Self {
         Self {
             priority: components.len(),
             components,
+            send_rate: Default::default(),
+            reliable: false,
         }
     }
 
+    /// Replaces the default [`SendRate::EveryTick`] with a custom rate.
+    pub fn with_send_rate(mut self, send_rate: SendRate) -> Self {
+        self.send_rate = send_rate;
+        self
+    }
+
+    /// Makes changes of the rule components reliable.
+    ///
+    /// By default changes are sent in update messages over an unreliable channel, and a lost change
+    /// is replaced by the next one. Reliable changes are written into init messages together
+    /// with all other changes of the entity from the same tick, so they are never lost
+    /// and are applied in the order of ticks. Use it for components whose intermediate values matter.
+    pub fn reliable(mut self) -> Self {
+        self.reliable = true;
+        self
+    }
+
     /// Determines whether an archetype contains all components required by the rule.
     pub(crate) fn matches(&self, archetype: &Archetype) -> bool {
         self.components
@@ -260,6 +397,37 @@ impl ReplicationRule {
     }
 }
 
+/// Configures how often changes of components from a [`ReplicationRule`] will be sent.
+///
+/// Only affects changes. Insertions are always sent immediately.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SendRate {
+    /// Send changes every tick.
+    #[default]
+    EveryTick,
+    /// Send changes at most once per the specified number of ticks.
+    ///
+    /// Ticks at which changes are sent are spread between entities to avoid sending
+    /// all changes at the same tick.
+    /// Changes made between sends are not lost: they will be sent at the next due tick.
+    /// Values 0 and 1 are equivalent to [`Self::EveryTick`].
+    Periodic(u32),
+}
+
+impl SendRate {
+    /// Returns `true` if changes for an entity should be sent at the specified tick.
+    // TODO: Use `is_multiple_of` after bumping MSRV to 1.87.
+    #[allow(clippy::manual_is_multiple_of)]
+    pub(crate) fn is_due(self, tick: RepliconTick, entity: Entity) -> bool {
+        match self {
+            SendRate::EveryTick => true,
+            SendRate::Periodic(period) => {
+                period <= 1 || tick.get().wrapping_add(entity.index()) % period == 0
+            }
+        }
+    }
+}
+
 /**
 Describes how a component group should be serialized, deserialized, written, and removed.
 
@@ -366,6 +534,21 @@ mod tests {
         assert_eq!(priorities, [2, 2, 1, 1, 1, 1]);
     }
 
+    #[test]
+    fn send_rate() {
+        let entity = Entity::from_raw(1);
+        for tick in 0..3 {
+            assert!(SendRate::EveryTick.is_due(RepliconTick::new(tick), entity));
+            assert!(SendRate::Periodic(0).is_due(RepliconTick::new(tick), entity));
+        }
+
+        let periodic = SendRate::Periodic(3);
+        assert!(!periodic.is_due(RepliconTick::new(0), entity));
+        assert!(!periodic.is_due(RepliconTick::new(1), entity));
+        assert!(periodic.is_due(RepliconTick::new(2), entity));
+        assert!(periodic.is_due(RepliconTick::new(5), entity));
+    }
+
     #[derive(Serialize, Deserialize, Component)]
     struct ComponentA;
 
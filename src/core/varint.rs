@@ -0,0 +1,70 @@
+use std::io::{self, Read};
+
+/// Reads an unsigned varint written by [`varint_rs::VarintWriter`].
+///
+/// Unlike [`varint_rs::VarintReader`], returns an error instead of overflowing on malformed input.
+pub(crate) fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut value = 0;
+    for shift in (0..u64::BITS).step_by(7) {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+        let [byte] = byte;
+
+        let bits = (byte & 0b0111_1111) as u64;
+        if bits.checked_shl(shift).map(|shifted| shifted >> shift) != Some(bits) {
+            break;
+        }
+        value |= bits << shift;
+
+        if byte & 0b1000_0000 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "varint is too large",
+    ))
+}
+
+/// Like [`read_u64`], but for [`u32`].
+pub(crate) fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    read_u64(reader)?
+        .try_into()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "varint is too large"))
+}
+
+/// Like [`read_u64`], but for [`usize`].
+pub(crate) fn read_usize(reader: &mut impl Read) -> io::Result<usize> {
+    read_u64(reader)?
+        .try_into()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "varint is too large"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use varint_rs::VarintWriter;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for value in [0, 1, 127, 128, u32::MAX as u64, u64::MAX] {
+            let mut buffer = Vec::new();
+            buffer.write_u64_varint(value).unwrap();
+            assert_eq!(read_u64(&mut Cursor::new(&buffer)).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn overflow() {
+        let buffer = [0xFF; 11];
+        assert!(read_u64(&mut Cursor::new(&buffer)).is_err());
+
+        let mut buffer = Vec::new();
+        buffer.write_u64_varint(u32::MAX as u64 + 1).unwrap();
+        assert!(read_u32(&mut Cursor::new(&buffer)).is_err());
+    }
+}
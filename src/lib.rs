@@ -96,14 +96,42 @@ condition for those cases.
 If you want your systems to run only on frames when the server sends updates to clients,
 use [`ServerSet::Send`].
 
+## Protocol verification
+
+Replication rules, events and channels must be registered identically on the client and the server.
+To detect a mismatch, the client sends its [`ProtocolHash`] after connecting and the server responds with its own.
+The client applies replication only after [`ProtocolStatus::Verified`]. On mismatch the server emits
+[`ServerEvent::ProtocolMismatch`] and requests a disconnect from the messaging backend via
+[`RepliconServer::drain_disconnects`].
+
+By default rules and events are identified by their registration order. To register them in any order,
+or to let builds with additional events interoperate, set [`RepliconCorePlugin::id_policy`]
+to [`IdPolicy::TypeHash`].
+
 ## Replication
 
 It's a process of sending changes from server to clients in order to
 keep the world in sync.
 
-To prevent cheating, we do not support replicating from the client. If you need to send
+To prevent cheating, clients can't replicate components by default. If you need to send
 information from clients to the server, use [events](#network-events).
 
+For input-like components you can opt-in into replication from clients that own them.
+Register a component via [`AppAuthorityExt::replicate_from_client`] instead of
+[`AppRuleExt::replicate`] and insert [`Authority<C>`] on server to grant a client authority over it.
+The client will send its changes to the server, where they can be validated with
+[`AppAuthorityExt::replicate_from_client_with`] before being replicated to other clients.
+
+To hide latency, components can also be predicted on client. Add [`PredictionPlugin`],
+register a component via [`AppPredictionExt::predict`] and insert [`Predicted`] on the client entity.
+Systems in [`PredictionSchedule`] will simulate it locally, and on mismatch with the received
+value the entity will be rolled back and resimulated.
+
+For entities that aren't predicted, add [`InterpolationPlugin`], register a component via
+[`AppInterpolationExt::interpolate`] and insert [`Interpolated`] on the client entity.
+Received values will be buffered and rendered with a delay behind the server, blended via
+the [`Interpolate`] trait.
+
 ### Marking for replication
 
 By default nothing is replicated. User needs to choose which entities
@@ -157,6 +185,30 @@ Useful for implementing rollback and interpolation.
 
 In order to serialize Bevy components you need to enable the `serialize` feature on Bevy.
 
+#### Resources
+
+Global state, like match score or round timer, can be replicated without wrapping it into an entity.
+Use [`AppResourceExt::replicate_resource()`] to enable replication for a resource:
+
+```
+# use bevy::prelude::*;
+# use bevy_replicon::prelude::*;
+# use serde::{Deserialize, Serialize};
+# let mut app = App::new();
+# app.add_plugins(RepliconPlugins);
+app.replicate_resource::<Score>();
+
+#[derive(Resource, Deserialize, Serialize)]
+struct Score(u32);
+```
+
+Insertions, changes and removals are sent to all clients in the same tick as entity changes.
+Similar to components, there are [`AppResourceExt::replicate_resource_mapped()`]
+and [`AppResourceExt::replicate_resource_with()`].
+
+For [`States`] use [`AppResourceExt::replicate_state()`]. Clients will transition into the received
+state on the same frame when entity changes from the server's transition tick are applied.
+
 ### Mapping to existing client entities
 
 If you want the server to replicate an entity into a client entity that was already spawned on a client, see [`ClientEntityMap`].
@@ -365,6 +417,12 @@ Just like with client events, if the event contains an entity, then
 For events that require special serialization and deserialization functions you can use
 [`ServerEventAppExt::add_server_event_with()`].
 
+### Requests and responses
+
+If the client needs an answer from the server, register a request/response pair with
+[`AppRpcExt::add_rpc()`] instead of two separate events. Requests are sent with [`Requests`]
+and resolve into [`RpcResult`] with the response or a timeout. See the method documentation for an example.
+
 ## Client visibility
 
 You can control which parts of the world are visible for each client by setting visibility policy
@@ -412,6 +470,21 @@ fn update_visibility(
 struct Player(ClientId);
 ```
 
+To hide only some components of a visible entity, use [`ClientComponentVisibility`]
+from [`ConnectedClient::component_visibility_mut`]. It works with any visibility policy.
+The client receives a removal when a component becomes hidden and an insertion when it becomes visible again.
+
+For group-based visibility, use [`VisibilityPolicy::Whitelist`] with rooms. Insert [`Rooms`] component
+into entities and add clients into rooms via [`ConnectedClients::join_room`]. An entity is visible
+for a client while they share at least one room. Replicon tracks room membership incrementally,
+so only entities from affected rooms are touched when a client joins or leaves a room or an entity's
+rooms change. Don't manually change visibility of entities with [`Rooms`].
+
+For distance-based visibility, add [`SpatialInterestPlugin`] on server instead of recomputing distances
+manually like in the example above. It maintains a grid of replicated entities with [`Transform`]
+and shows clients entities around their [`Viewer`] entities. Visibility is updated only for cells
+that changed, so the cost is proportional to movement. It also requires [`VisibilityPolicy::Whitelist`].
+
 For a higher level API consider using [`bevy_replicon_attributes`](https://docs.rs/bevy_replicon_attributes).
 
 ## Eventual consistency
@@ -430,14 +503,29 @@ Updates for despawned entities will be discarded automatically, but events or co
 Clients should never assume their world state is the same as the server's on any given tick value-wise.
 World state on the client is only "eventually consistent" with the server's.
 
+## Recording and replay
+
+To record messages sent to a client, add [`RecorderPlugin`] on server and call [`ReplicationRecorder::start`].
+Use [`RecordTarget::Observer`] to record the world from a virtual client instead of a connected one.
+The resulting [`Recording`] can be saved with [`Recording::write`].
+
+To play it, add [`ReplayPlugin`] on client without a messaging backend and insert [`Replay`] as a resource.
+It supports pausing, speed control and seeking to periodically recorded keyframes.
+
 ## Limits
 
-To reduce packet size there are the following limits per replication update:
+There are no hard limits on the number of entities or the size of component data per replication update
+since all lengths are written as varints.
 
-- Up to [`u16::MAX`] entities that have added components with up to [`u16::MAX`] bytes of component data.
-- Up to [`u16::MAX`] entities that have changed components with up to [`u16::MAX`] bytes of component data.
-- Up to [`u16::MAX`] entities that have removed components with up to [`u16::MAX`] bytes of component data.
-- Up to [`u16::MAX`] entities that were despawned.
+But keep in mind that init messages are not split by default and update messages are split only between entities.
+So messaging backends should be able to send messages bigger than
+[`RepliconServer::max_payload_size`](server::replicon_server::RepliconServer::max_payload_size),
+usually by fragmenting them.
+
+When a client connects to a large world, all entities are sent in a single init message by default.
+To spread them over multiple ticks, set [`ServerPlugin::initial_sync_budget`]. With it, new entities
+that don't fit into the reported max payload size are also deferred to the next ticks. Use the
+[`initial_sync_complete`] condition on client to know when the world is fully received.
 */
 
 pub mod client;
@@ -453,27 +541,49 @@ pub mod prelude {
 
     pub use super::{
         client::{
+            authority::{AppAuthorityExt, Authority, ClientAuthorityPlugin},
             diagnostics::{ClientDiagnosticsPlugin, ClientStats},
             events::{ClientEventAppExt, ClientEventsPlugin, FromClient},
+            interpolation::{
+                AppInterpolationExt, Interpolate, Interpolated, InterpolationPlugin,
+                InterpolationTime,
+            },
+            prediction::{
+                AppPredictionExt, Predicted, PredictionPlugin, PredictionSchedule, PredictionTick,
+            },
+            repair::{RepairDespawn, RepairPlugin},
+            replay::{Replay, ReplayPlugin},
             replicon_client::{RepliconClient, RepliconClientStatus},
-            ClientPlugin, ClientSet,
+            rpc::{AppRpcExt, Requests, RpcResult},
+            ClientPlugin, ClientSet, InitialSyncStatus, MessageKind, ProtocolStatus, ReceiveError,
+            ReceiveErrorPolicy,
         },
         core::{
             channels::{ChannelKind, RepliconChannel, RepliconChannels},
             command_markers::AppMarkerExt,
             common_conditions::*,
+            protocol::ProtocolHash,
+            recording::Recording,
+            replication_resources::AppResourceExt,
             replication_rules::AppRuleExt,
-            ClientId, Replicated, RepliconCorePlugin,
+            ClientId, IdPolicy, Replicated, RepliconCorePlugin,
         },
         parent_sync::{ParentSync, ParentSyncPlugin},
         server::{
             client_entity_map::{ClientEntityMap, ClientMapping},
             connected_clients::{
-                client_visibility::ClientVisibility, ConnectedClient, ConnectedClients,
+                client_component_visibility::ClientComponentVisibility,
+                client_priority::ClientPriority, client_visibility::ClientVisibility,
+                ConnectedClient, ConnectedClients,
             },
             events::{SendMode, ServerEventAppExt, ServerEventsPlugin, ToClients},
+            recorder::{RecordTarget, RecorderPlugin, ReplicationRecorder},
             replicon_server::RepliconServer,
-            ServerEvent, ServerPlugin, ServerSet, TickPolicy, VisibilityPolicy,
+            rooms::{RoomId, Rooms},
+            rpc::{ClientRequest, Reply},
+            spatial_interest::{GridAxes, SpatialGrid, SpatialInterestPlugin, Viewer},
+            CompressionPolicy, ProtocolViolation, ReplicationPriority, ServerEvent, ServerPlugin,
+            ServerSet, TickPolicy, ViolationKind, ViolationPolicy, VisibilityPolicy,
         },
         RepliconPlugins,
     };
@@ -490,11 +600,12 @@ pub struct RepliconPlugins;
 impl PluginGroup for RepliconPlugins {
     fn build(self) -> PluginGroupBuilder {
         PluginGroupBuilder::start::<Self>()
-            .add(RepliconCorePlugin)
+            .add(RepliconCorePlugin::default())
             .add(ParentSyncPlugin)
             .add(ClientPlugin)
             .add(ServerPlugin::default())
             .add(ClientEventsPlugin)
+            .add(ClientAuthorityPlugin)
             .add(ServerEventsPlugin)
     }
 }
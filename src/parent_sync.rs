@@ -119,7 +119,7 @@ mod tests {
     #[test]
     fn update() {
         let mut app = App::new();
-        app.add_plugins((RepliconCorePlugin, ParentSyncPlugin));
+        app.add_plugins((RepliconCorePlugin::default(), ParentSyncPlugin));
 
         let child_entity = app.world_mut().spawn_empty().id();
         app.world_mut().spawn_empty().add_child(child_entity);
@@ -140,7 +140,7 @@ mod tests {
     #[test]
     fn removal() {
         let mut app = App::new();
-        app.add_plugins((RepliconCorePlugin, ParentSyncPlugin));
+        app.add_plugins((RepliconCorePlugin::default(), ParentSyncPlugin));
 
         let parent_entity = app.world_mut().spawn_empty().id();
         let child_entity = app
@@ -166,7 +166,7 @@ mod tests {
     #[test]
     fn update_sync() {
         let mut app = App::new();
-        app.add_plugins((RepliconCorePlugin, ParentSyncPlugin));
+        app.add_plugins((RepliconCorePlugin::default(), ParentSyncPlugin));
 
         let parent_entity = app.world_mut().spawn_empty().id();
         let child_entity = app.world_mut().spawn(ParentSync(Some(parent_entity))).id();
@@ -182,7 +182,7 @@ mod tests {
     #[test]
     fn removal_sync() {
         let mut app = App::new();
-        app.add_plugins((RepliconCorePlugin, ParentSyncPlugin));
+        app.add_plugins((RepliconCorePlugin::default(), ParentSyncPlugin));
 
         let child_entity = app.world_mut().spawn_empty().id();
         app.world_mut().spawn_empty().add_child(child_entity);
@@ -206,7 +206,7 @@ mod tests {
         app.add_plugins((
             AssetPlugin::default(),
             ScenePlugin,
-            RepliconCorePlugin,
+            RepliconCorePlugin::default(),
             ParentSyncPlugin,
         ));
 
@@ -1,12 +1,18 @@
+pub(crate) mod authority;
 pub mod client_entity_map;
 pub mod connected_clients;
 pub(super) mod despawn_buffer;
+pub mod event_limit;
 pub mod events;
+pub mod recorder;
 pub(super) mod removal_buffer;
 pub(super) mod replicated_archetypes;
 pub(super) mod replication_messages;
 pub mod replicon_server;
+pub mod rooms;
+pub mod rpc;
 pub mod server_tick;
+pub mod spatial_interest;
 
 use std::{io::Cursor, mem, time::Duration};
 
@@ -22,12 +28,17 @@ use bevy::{
     ptr::Ptr,
     time::common_conditions::on_timer,
 };
+use bytes::Bytes;
 
 use crate::core::{
     channels::{ReplicationChannel, RepliconChannels},
     common_conditions::{server_just_stopped, server_running},
     ctx::SerializeCtx,
-    replication_registry::ReplicationRegistry,
+    protocol::ProtocolHash,
+    replication_registry::{
+        component_fns::ComponentFns, rule_fns::UntypedRuleFns, ReplicationRegistry,
+    },
+    replication_resources::ReplicationResources,
     replication_rules::ReplicationRules,
     replicon_tick::RepliconTick,
     ClientId,
@@ -54,6 +65,29 @@ pub struct ServerPlugin {
     ///
     /// In practice updates will live at least `update_timeout`, and at most `2*update_timeout`.
     pub update_timeout: Duration,
+
+    /// The time after which a client that hasn't sent its protocol hash will be disconnected.
+    ///
+    /// Until the hash is verified, the client doesn't receive replication and all its messages are ignored.
+    /// See also [`ProtocolHash`].
+    pub handshake_timeout: Duration,
+
+    /// Compression configuration for replication messages.
+    pub compression_policy: CompressionPolicy,
+
+    /// Max number of bytes of init messages to send to a newly connected client per tick
+    /// until it receives all visible entities.
+    ///
+    /// Without a budget, the completion of the initial sync is reported with the first init message
+    /// that contains data, so clients in a world without replicated entities will wait for the first one.
+    /// With a budget, the completion is reported as soon as all visible entities are sent,
+    /// which may require a separate message.
+    ///
+    /// Can be changed per client via [`ConnectedClient::set_initial_sync_budget`].
+    pub initial_sync_budget: Option<usize>,
+
+    /// Handling of clients that send malformed messages.
+    pub violation_policy: ViolationPolicy,
 }
 
 impl Default for ServerPlugin {
@@ -62,6 +96,10 @@ impl Default for ServerPlugin {
             tick_policy: TickPolicy::MaxTickRate(30),
             visibility_policy: Default::default(),
             update_timeout: Duration::from_secs(10),
+            handshake_timeout: Duration::from_secs(10),
+            compression_policy: Default::default(),
+            initial_sync_budget: None,
+            violation_policy: Default::default(),
         }
     }
 }
@@ -72,12 +110,19 @@ impl Default for ServerPlugin {
 impl Plugin for ServerPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((DespawnBufferPlugin, RemovalBufferPlugin))
+            .register_type::<ReplicationPriority>()
             .init_resource::<RepliconServer>()
             .init_resource::<ServerTick>()
             .init_resource::<ClientBuffers>()
             .init_resource::<ClientEntityMap>()
-            .insert_resource(ConnectedClients::new(self.visibility_policy))
+            .insert_resource(ConnectedClients::new(
+                self.visibility_policy,
+                self.initial_sync_budget,
+            ))
+            .insert_resource(self.compression_policy)
+            .insert_resource(self.violation_policy)
             .add_event::<ServerEvent>()
+            .add_event::<ProtocolViolation>()
             .configure_sets(
                 PreUpdate,
                 (
@@ -101,6 +146,17 @@ impl Plugin for ServerPlugin {
                 PreUpdate,
                 (
                     Self::handle_connections,
+                    Self::receive_protocol_hashes,
+                    Self::check_handshakes(self.handshake_timeout),
+                )
+                    .chain()
+                    .after(ServerSet::SendEvents)
+                    .before(ServerSet::Receive)
+                    .run_if(server_running),
+            )
+            .add_systems(
+                PreUpdate,
+                (
                     Self::receive_acks,
                     Self::cleanup_acks(self.update_timeout).run_if(on_timer(self.update_timeout)),
                 )
@@ -108,14 +164,23 @@ impl Plugin for ServerPlugin {
                     .in_set(ServerSet::Receive)
                     .run_if(server_running),
             )
+            .add_systems(
+                PreUpdate,
+                Self::handle_violations
+                    .after(ServerSet::Receive)
+                    .run_if(server_running),
+            )
             .add_systems(
                 PostUpdate,
                 (
                     Self::send_replication
-                        .map(Result::unwrap)
                         .in_set(ServerSet::Send)
                         .run_if(server_running)
                         .run_if(resource_changed::<ServerTick>),
+                    rooms::update_rooms
+                        .in_set(ServerSet::Send)
+                        .before(Self::send_replication)
+                        .run_if(server_running),
                     Self::reset.run_if(server_just_stopped),
                 ),
             );
@@ -161,6 +226,7 @@ impl ServerPlugin {
         mut connected_clients: ResMut<ConnectedClients>,
         mut server: ResMut<RepliconServer>,
         mut client_buffers: ResMut<ClientBuffers>,
+        time: Res<Time>,
     ) {
         for event in server_events.read() {
             match *event {
@@ -170,7 +236,78 @@ impl ServerPlugin {
                     server.remove_client(client_id);
                 }
                 ServerEvent::ClientConnected { client_id } => {
-                    connected_clients.add(&mut client_buffers, client_id);
+                    connected_clients.add(&mut client_buffers, client_id, time.elapsed());
+                }
+                ServerEvent::ProtocolMismatch { .. } => (),
+            }
+        }
+    }
+
+    fn receive_protocol_hashes(
+        mut server: ResMut<RepliconServer>,
+        mut connected_clients: ResMut<ConnectedClients>,
+        mut server_events: EventWriter<ServerEvent>,
+        mut violations: EventWriter<ProtocolViolation>,
+        protocol_hash: Res<ProtocolHash>,
+    ) {
+        let messages: Vec<_> = server.receive(ReplicationChannel::Protocol).collect();
+        for (client_id, message) in messages {
+            let Ok(bytes) = (*message).try_into() else {
+                debug!("disconnecting {client_id:?} due to invalid protocol hash");
+                server.disconnect(client_id);
+                violations.send(ProtocolViolation {
+                    client_id,
+                    kind: ViolationKind::InvalidProtocolHash,
+                });
+                continue;
+            };
+
+            // Always respond to let the client know about the mismatch before the disconnect.
+            server.send(
+                client_id,
+                ReplicationChannel::Protocol,
+                protocol_hash.get().to_le_bytes().to_vec(),
+            );
+
+            let client_hash = ProtocolHash::new(u64::from_le_bytes(bytes));
+            if client_hash == *protocol_hash {
+                debug!("{client_id:?} confirmed {client_hash:?}");
+                if let Some(client) = connected_clients.get_client_mut(client_id) {
+                    client.verify_protocol();
+                }
+            } else {
+                warn!(
+                    "disconnecting {client_id:?} because its {client_hash:?} doesn't match server's {:?}",
+                    *protocol_hash
+                );
+                server.disconnect(client_id);
+                server_events.send(ServerEvent::ProtocolMismatch {
+                    client_id,
+                    client_hash,
+                    server_hash: *protocol_hash,
+                });
+            }
+        }
+    }
+
+    /// Drops messages from clients that haven't confirmed the protocol and disconnects them after `handshake_timeout`.
+    fn check_handshakes(
+        handshake_timeout: Duration,
+    ) -> impl FnMut(Res<ConnectedClients>, ResMut<RepliconServer>, Res<Time>) {
+        move |connected_clients: Res<ConnectedClients>,
+              mut server: ResMut<RepliconServer>,
+              time: Res<Time>| {
+            for client in connected_clients
+                .iter()
+                .filter(|client| !client.is_protocol_verified())
+            {
+                server.remove_received(client.id());
+                if time.elapsed().saturating_sub(client.connected_at()) >= handshake_timeout {
+                    warn!(
+                        "disconnecting {:?} because it didn't send its protocol hash in time",
+                        client.id()
+                    );
+                    server.disconnect(client.id());
                 }
             }
         }
@@ -194,21 +331,55 @@ impl ServerPlugin {
         mut server: ResMut<RepliconServer>,
         mut connected_clients: ResMut<ConnectedClients>,
         mut client_buffers: ResMut<ClientBuffers>,
+        mut violations: EventWriter<ProtocolViolation>,
     ) {
         for (client_id, message) in server.receive(ReplicationChannel::Init) {
+            let Some(client) = connected_clients.get_client_mut(client_id) else {
+                debug!("ignoring acknowledgments from unknown {client_id:?}");
+                continue;
+            };
+
             let mut cursor = Cursor::new(&*message);
             let message_end = message.len() as u64;
             while cursor.position() < message_end {
                 match bincode::deserialize_from(&mut cursor) {
                     Ok(update_index) => {
-                        let client = connected_clients.client_mut(client_id);
                         client.acknowledge(
                             &mut client_buffers,
                             change_tick.this_run(),
                             update_index,
                         );
                     }
-                    Err(e) => debug!("unable to deserialize update index from {client_id:?}: {e}"),
+                    Err(e) => {
+                        violations.send(ProtocolViolation {
+                            client_id,
+                            kind: ViolationKind::InvalidAck(e),
+                        });
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Logs [`ProtocolViolation`] events and applies [`ViolationPolicy`].
+    fn handle_violations(
+        mut violations: EventReader<ProtocolViolation>,
+        mut server: ResMut<RepliconServer>,
+        policy: Res<ViolationPolicy>,
+    ) {
+        for violation in violations.read() {
+            match *policy {
+                ViolationPolicy::Emit => debug!(
+                    "received invalid message from {:?}: {:?}",
+                    violation.client_id, violation.kind
+                ),
+                ViolationPolicy::Disconnect => {
+                    warn!(
+                        "disconnecting {:?} due to invalid message: {:?}",
+                        violation.client_id, violation.kind
+                    );
+                    server.disconnect(violation.client_id);
                 }
             }
         }
@@ -218,7 +389,9 @@ impl ServerPlugin {
     pub(super) fn send_replication(
         mut entities_with_removals: Local<EntityHashSet>,
         mut messages: Local<ReplicationMessages>,
+        mut delta_buffers: Local<DeltaBuffers>,
         mut replicated_archetypes: Local<ReplicatedArchetypes>,
+        mut resources_present: Local<Vec<bool>>,
         change_tick: SystemChangeTick,
         mut set: ParamSet<(
             &World,
@@ -231,42 +404,64 @@ impl ServerPlugin {
         )>,
         registry: Res<ReplicationRegistry>,
         rules: Res<ReplicationRules>,
+        resources: Res<ReplicationResources>,
         server_tick: Res<ServerTick>,
+        compression_policy: Res<CompressionPolicy>,
         time: Res<Time>,
-    ) -> bincode::Result<()> {
+    ) {
         replicated_archetypes.update(set.p0(), &rules);
 
         let connected_clients = mem::take(&mut *set.p1()); // Take ownership to avoid borrowing issues.
-        messages.prepare(connected_clients);
-
-        collect_mappings(&mut messages, &mut set.p2())?;
-        collect_despawns(&mut messages, &mut set.p3())?;
-        collect_removals(&mut messages, &mut set.p4(), &mut entities_with_removals)?;
-        collect_changes(
-            &mut messages,
-            &replicated_archetypes,
-            &registry,
-            &entities_with_removals,
-            set.p0(),
-            &change_tick,
-            **server_tick,
-        )?;
-        entities_with_removals.clear();
-
         let mut client_buffers = mem::take(&mut *set.p5());
-        let connected_clients = messages.send(
-            &mut set.p6(),
-            &mut client_buffers,
-            **server_tick,
-            change_tick.this_run(),
-            time.elapsed(),
-        )?;
+        messages.prepare(connected_clients, &set.p6());
+
+        let mut collect_and_send = || -> bincode::Result<()> {
+            collect_mappings(&mut messages, &mut set.p2())?;
+            collect_despawns(&mut messages, &mut set.p3())?;
+            collect_removals(
+                &mut messages,
+                &registry,
+                &mut set.p4(),
+                &mut entities_with_removals,
+            )?;
+            collect_changes(
+                &mut messages,
+                &replicated_archetypes,
+                &registry,
+                &entities_with_removals,
+                &mut delta_buffers,
+                set.p0(),
+                &change_tick,
+                **server_tick,
+            )?;
+            entities_with_removals.clear();
+            collect_resources(
+                &mut messages,
+                &resources,
+                &mut resources_present,
+                set.p0(),
+                &change_tick,
+                **server_tick,
+            )?;
+
+            messages.send(
+                &mut set.p6(),
+                &mut client_buffers,
+                **server_tick,
+                change_tick.this_run(),
+                compression_policy.threshold(),
+                time.elapsed(),
+            )
+        };
+
+        if let Err(e) = collect_and_send() {
+            error!("unable to send replication for {:?}: {e}", **server_tick);
+            entities_with_removals.clear();
+        }
 
         // Return borrowed data back.
-        *set.p1() = connected_clients;
+        *set.p1() = messages.take_clients();
         *set.p5() = client_buffers;
-
-        Ok(())
     }
 
     fn reset(
@@ -309,6 +504,7 @@ fn collect_changes(
     replicated_archetypes: &ReplicatedArchetypes,
     registry: &ReplicationRegistry,
     entities_with_removals: &EntityHashSet,
+    delta_buffers: &mut DeltaBuffers,
     world: &World,
     change_tick: &SystemChangeTick,
     server_tick: RepliconTick,
@@ -339,6 +535,11 @@ fn collect_changes(
                 init_message.start_entity_data(entity.id());
                 update_message.start_entity_data(entity.id());
                 client.visibility_mut().cache_visibility(entity.id());
+                client.cache_sync_deferred(
+                    entity.id(),
+                    init_message.size(),
+                    init_message.max_size(),
+                );
             }
 
             // SAFETY: all replicated archetypes have marker component with table storage.
@@ -371,59 +572,185 @@ fn collect_changes(
 
                 let (component_fns, rule_fns) = registry.get(replicated_component.fns_id);
                 let ctx = SerializeCtx { server_tick };
-                let mut shared_bytes = None;
-                for (init_message, update_message, client) in messages.iter_mut_with_clients() {
-                    let visibility = client.visibility().cached_visibility();
-                    if visibility == Visibility::Hidden {
-                        continue;
-                    }
+                let mut write_clients = || -> bincode::Result<()> {
+                    let mut shared_bytes = None;
+                    let mut delta_bytes = None;
+                    for (init_message, update_message, client) in messages.iter_mut_with_clients() {
+                        let visibility = client.visibility().cached_visibility();
+                        if visibility == Visibility::Hidden || client.sync_deferred() {
+                            continue;
+                        }
 
-                    if let Some(tick) = client
-                        .get_change_tick(entity.id())
-                        .filter(|_| !marker_added)
-                        .filter(|_| visibility != Visibility::Gained)
-                        .filter(|_| !ticks.is_added(change_tick.last_run(), change_tick.this_run()))
-                    {
-                        if ticks.is_changed(tick, change_tick.this_run()) {
-                            update_message.write_component(
-                                &mut shared_bytes,
+                        let component_visibility = client.component_visibility();
+                        if !component_visibility
+                            .is_visible(entity.id(), replicated_component.component_id)
+                        {
+                            continue;
+                        }
+                        let component_gained = component_visibility
+                            .is_gained(entity.id(), replicated_component.component_id);
+
+                        if let Some(tick) = client
+                            .get_change_tick(entity.id())
+                            .filter(|_| !marker_added)
+                            .filter(|_| visibility != Visibility::Gained)
+                            .filter(|_| !component_gained)
+                            .filter(|_| {
+                                !ticks.is_added(change_tick.last_run(), change_tick.this_run())
+                            })
+                        {
+                            if !ticks.is_changed(tick, change_tick.this_run()) {
+                                continue;
+                            }
+
+                            if !replicated_component
+                                .send_rate
+                                .is_due(server_tick, entity.id())
+                            {
+                                update_message
+                                    .skip_change(ticks.last_changed_tick(), change_tick.this_run());
+                                continue;
+                            }
+
+                            if !replicated_component.reliable {
+                                if rule_fns.delta_compression() {
+                                    let bytes = serialize_delta_bytes(
+                                        &mut delta_bytes,
+                                        &mut delta_buffers.cursor,
+                                        rule_fns,
+                                        component_fns,
+                                        &ctx,
+                                        component,
+                                    )?;
+                                    let baseline = client.baselines().get(
+                                        entity.id(),
+                                        replicated_component.fns_id,
+                                        server_tick,
+                                    );
+                                    update_message.write_delta_component(
+                                        registry,
+                                        replicated_component.fns_id,
+                                        &bytes,
+                                        baseline,
+                                        &mut delta_buffers.diff,
+                                    )?;
+                                    client.baselines_mut().insert_sent(
+                                        entity.id(),
+                                        replicated_component.fns_id,
+                                        server_tick,
+                                        bytes,
+                                    );
+                                } else {
+                                    update_message.write_component(
+                                        &mut shared_bytes,
+                                        registry,
+                                        &ctx,
+                                        replicated_component.fns_id,
+                                        component,
+                                    )?;
+                                }
+                                continue;
+                            }
+                        }
+
+                        // Insertions and reliable changes are written into init message.
+                        if rule_fns.delta_compression() {
+                            let bytes = serialize_delta_bytes(
+                                &mut delta_bytes,
+                                &mut delta_buffers.cursor,
                                 rule_fns,
                                 component_fns,
                                 &ctx,
+                                component,
+                            )?;
+                            init_message.write_delta_component(
+                                registry,
+                                replicated_component.fns_id,
+                                &bytes,
+                                None,
+                                &mut delta_buffers.diff,
+                            )?;
+                            client.baselines_mut().insert_sent(
+                                entity.id(),
+                                replicated_component.fns_id,
+                                server_tick,
+                                bytes,
+                            );
+                        } else {
+                            init_message.write_component(
+                                &mut shared_bytes,
+                                registry,
+                                &ctx,
                                 replicated_component.fns_id,
                                 component,
                             )?;
                         }
-                    } else {
-                        init_message.write_component(
-                            &mut shared_bytes,
-                            rule_fns,
-                            component_fns,
-                            &ctx,
-                            replicated_component.fns_id,
-                            component,
-                        )?;
                     }
+                    Ok(())
+                };
+                if let Err(e) = write_clients() {
+                    let name = world
+                        .components()
+                        .get_name(replicated_component.component_id)
+                        .unwrap_or_default();
+                    error!(
+                        "skipping `{name}` for {:?} due to serialization error: {e}",
+                        entity.id()
+                    );
                 }
             }
 
+            let mut base_priority = None;
             for (init_message, update_message, client) in messages.iter_mut_with_clients() {
                 let visibility = client.visibility().cached_visibility();
-                if visibility == Visibility::Hidden {
+                if visibility == Visibility::Hidden || client.sync_deferred() {
                     continue;
                 }
 
-                let new_entity = marker_added || visibility == Visibility::Gained;
+                let new_entity = marker_added
+                    || visibility == Visibility::Gained
+                    || client.get_change_tick(entity.id()).is_none();
                 if new_entity
                     || init_message.entity_data_size() != 0
                     || entities_with_removals.contains(&entity.id())
+                    || client.component_visibility().has_lost(entity.id())
                 {
                     // If there is any insertion, removal, or we must initialize, include all updates into init message.
                     // and bump the last acknowledged tick to keep entity updates atomic.
                     init_message.take_entity_data(update_message)?;
-                    client.set_change_tick(entity.id(), change_tick.this_run());
+                    if new_entity
+                        && client.defer_oversized(init_message.size(), init_message.max_size())
+                    {
+                        trace!(
+                            "deferring {:?} for {:?} due to max payload size",
+                            entity.id(),
+                            client.id()
+                        );
+                        init_message.discard_entity_data();
+                        continue;
+                    }
+                    let tick = update_message
+                        .skipped_tick()
+                        .unwrap_or(change_tick.this_run());
+                    client.set_change_tick(entity.id(), tick);
+                    if registry.has_delta_compression() {
+                        // Init messages are reliable, so the client will receive all values
+                        // and treat all previously sent updates for this entity as outdated.
+                        client.baselines_mut().acknowledge(entity.id(), server_tick);
+                    }
                 } else {
-                    update_message.end_entity_data()?;
+                    let priority = if client.bandwidth_budget().is_some() {
+                        *base_priority.get_or_insert_with(|| {
+                            world
+                                .get::<ReplicationPriority>(entity.id())
+                                .copied()
+                                .unwrap_or_default()
+                                .0
+                        })
+                    } else {
+                        Default::default()
+                    };
+                    update_message.end_entity_data(priority)?;
                 }
 
                 init_message.end_entity_data(new_entity)?;
@@ -438,6 +765,40 @@ fn collect_changes(
     Ok(())
 }
 
+/// Serializes a component with delta compression or returns previously serialized bytes.
+///
+/// Serialized only once per component since the same bytes are stored as a baseline for each client.
+fn serialize_delta_bytes(
+    delta_bytes: &mut Option<Bytes>,
+    cursor: &mut Cursor<Vec<u8>>,
+    rule_fns: &UntypedRuleFns,
+    component_fns: &ComponentFns,
+    ctx: &SerializeCtx,
+    component: Ptr,
+) -> bincode::Result<Bytes> {
+    if let Some(bytes) = delta_bytes {
+        return Ok(bytes.clone());
+    }
+
+    cursor.set_position(0);
+    // SAFETY: `component_fns`, `ptr` and `rule_fns` were created for the same component type.
+    unsafe { component_fns.serialize(ctx, rule_fns, component, cursor)? };
+    let bytes = Bytes::copy_from_slice(&cursor.get_ref()[..cursor.position() as usize]);
+    *delta_bytes = Some(bytes.clone());
+
+    Ok(bytes)
+}
+
+/// Reusable buffers for components with delta compression.
+#[derive(Default)]
+pub(crate) struct DeltaBuffers {
+    /// Buffer to serialize components into.
+    cursor: Cursor<Vec<u8>>,
+
+    /// Buffer for diffs against baselines.
+    diff: Vec<u8>,
+}
+
 /// Extracts component in form of [`Ptr`] and its ticks from table or sparse set based on its storage type.
 ///
 /// # Safety
@@ -468,6 +829,73 @@ unsafe fn get_component_unchecked<'w>(
     }
 }
 
+/// Collects removals and changes of replicated resources from this tick into init messages.
+///
+/// Clients that haven't received resources yet get all existing resources.
+/// `resources_present` stores whether each resource existed on the previous run to detect removals.
+fn collect_resources(
+    messages: &mut ReplicationMessages,
+    resources: &ReplicationResources,
+    resources_present: &mut Vec<bool>,
+    world: &World,
+    change_tick: &SystemChangeTick,
+    server_tick: RepliconTick,
+) -> bincode::Result<()> {
+    resources_present.resize(resources.len(), false);
+
+    for (message, _) in messages.iter_mut() {
+        message.start_array();
+    }
+
+    for (index, &present) in resources_present.iter().enumerate() {
+        if present && resources.get(index).change_ticks(world).is_none() {
+            for (message, _, client) in messages.iter_mut_with_clients() {
+                if client.resources_synced() {
+                    message.write_resource_id(resources, index)?;
+                }
+            }
+        }
+    }
+
+    for (message, _) in messages.iter_mut() {
+        message.end_array()?;
+        message.start_array();
+    }
+
+    let ctx = SerializeCtx { server_tick };
+    for (index, present) in resources_present.iter_mut().enumerate() {
+        let Some(ticks) = resources.get(index).change_ticks(world) else {
+            *present = false;
+            continue;
+        };
+
+        let changed = !*present || ticks.is_changed(change_tick.last_run(), change_tick.this_run());
+        *present = true;
+
+        let mut shared_bytes = None;
+        for (message, _, client) in messages.iter_mut_with_clients() {
+            if changed || !client.resources_synced() {
+                if let Err(e) =
+                    message.write_resource(&mut shared_bytes, resources, &ctx, world, index)
+                {
+                    error!(
+                        "skipping `{}` due to serialization error: {e}",
+                        resources.get(index).type_name()
+                    );
+                    break;
+                }
+            }
+        }
+    }
+
+    for (message, _, client) in messages.iter_mut_with_clients() {
+        client.set_resources_synced();
+        message.end_array()?;
+    }
+
+    Ok(())
+}
+
 /// Collect entity despawns from this tick into init messages.
 fn collect_despawns(
     messages: &mut ReplicationMessages,
@@ -483,6 +911,9 @@ fn collect_despawns(
             client.remove_despawned(entity);
             message.write_entity(&mut shared_bytes, entity)?;
         }
+        for client in messages.iter_unverified_clients() {
+            client.remove_despawned(entity);
+        }
     }
 
     for (message, _, client) in messages.iter_mut_with_clients() {
@@ -499,6 +930,7 @@ fn collect_despawns(
 /// Collects component removals from this tick into init messages.
 fn collect_removals(
     messages: &mut ReplicationMessages,
+    registry: &ReplicationRegistry,
     removal_buffer: &mut RemovalBuffer,
     entities_with_removals: &mut EntityHashSet,
 ) -> bincode::Result<()> {
@@ -507,10 +939,15 @@ fn collect_removals(
     }
 
     for (entity, remove_ids) in removal_buffer.iter() {
-        for (message, _) in messages.iter_mut() {
+        for (message, _, client) in messages.iter_mut_with_clients() {
+            if client.get_change_tick(entity).is_none() {
+                // The client doesn't have this entity yet.
+                continue;
+            }
+
             message.start_entity_data(entity);
             for fns_info in remove_ids {
-                message.write_fns_id(fns_info.fns_id())?;
+                message.write_fns_id(registry, fns_info.fns_id())?;
             }
             entities_with_removals.insert(entity);
             message.end_entity_data(false)?;
@@ -518,6 +955,24 @@ fn collect_removals(
     }
     removal_buffer.clear();
 
+    // Components that became hidden are removed only for specific clients.
+    for (message, _, client) in messages.iter_mut_with_clients() {
+        for (entity, component_ids) in client.component_visibility().iter_lost() {
+            if client.get_change_tick(entity).is_none() {
+                // The client doesn't have this entity.
+                continue;
+            }
+
+            message.start_entity_data(entity);
+            for &component_id in component_ids {
+                if let Some(fns_id) = registry.component_fns_id(component_id) {
+                    message.write_fns_id(registry, fns_id)?;
+                }
+            }
+            message.end_entity_data(false)?;
+        }
+    }
+
     for (message, _) in messages.iter_mut() {
         message.end_array()?;
     }
@@ -598,11 +1053,110 @@ pub enum VisibilityPolicy {
     Whitelist,
 }
 
+/// Controls compression of replication messages.
+///
+/// Compression is applied only to the payload of init and update messages.
+/// Each message has a bit in its header that indicates whether its payload is compressed,
+/// so clients decode messages without any configuration.
+/// Init messages start with an extra flags byte that stores this bit.
+/// For update messages the bit is stored in the update index, so they don't have any size overhead.
+/// Uncompressed messages don't require any additional processing.
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub enum CompressionPolicy {
+    /// Messages are sent as is.
+    #[default]
+    Disabled,
+    /// Payloads with at least the specified number of bytes are compressed with a built-in LZ4-style algorithm.
+    ///
+    /// If compression doesn't reduce the size, the payload will be sent as is.
+    ///
+    /// Compression takes CPU time, so small messages are better sent as is.
+    /// Useful for big init messages, like the initial world sent to new clients.
+    Threshold(usize),
+}
+
+impl CompressionPolicy {
+    /// Returns the min payload size to compress or [`None`] if compression is disabled.
+    fn threshold(self) -> Option<usize> {
+        match self {
+            CompressionPolicy::Disabled => None,
+            CompressionPolicy::Threshold(threshold) => Some(threshold),
+        }
+    }
+}
+
+/// Replication priority of an entity for clients with a bandwidth budget.
+///
+/// Entities without this component have priority 1.0.
+/// See [`ConnectedClient::set_bandwidth_budget`] for details.
+#[derive(Component, Clone, Copy, Debug, Deref, DerefMut, Reflect)]
+#[reflect(Component)]
+pub struct ReplicationPriority(pub f32);
+
+impl Default for ReplicationPriority {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
 /// Connection and disconnection events on the server.
 ///
-/// The messaging backend is responsible for emitting these in [`ServerSet::SendEvents`].
+/// The messaging backend is responsible for emitting connection and disconnection events in [`ServerSet::SendEvents`].
 #[derive(Event, Debug, Clone)]
 pub enum ServerEvent {
-    ClientConnected { client_id: ClientId },
-    ClientDisconnected { client_id: ClientId, reason: String },
+    ClientConnected {
+        client_id: ClientId,
+    },
+    ClientDisconnected {
+        client_id: ClientId,
+        reason: String,
+    },
+    /// The client has a different [`ProtocolHash`].
+    ///
+    /// Emitted by Replicon after receiving the client's hash.
+    /// The disconnect for the client is requested via [`RepliconServer::disconnect`].
+    ProtocolMismatch {
+        client_id: ClientId,
+        client_hash: ProtocolHash,
+        server_hash: ProtocolHash,
+    },
+}
+
+/// An event that indicates that a client sent a message that can't be processed.
+///
+/// Emitted by Replicon on server instead of panicking, the message is ignored.
+/// See also [`ViolationPolicy`].
+#[derive(Event, Debug)]
+pub struct ProtocolViolation {
+    pub client_id: ClientId,
+    pub kind: ViolationKind,
+}
+
+/// Type of [`ProtocolViolation`].
+#[derive(Debug)]
+pub enum ViolationKind {
+    /// The protocol hash has an invalid size.
+    ///
+    /// The disconnect for the client is always requested.
+    InvalidProtocolHash,
+    /// An acknowledgment of an update message can't be deserialized.
+    InvalidAck(bincode::Error),
+    /// A client event can't be deserialized.
+    InvalidEvent {
+        /// Type name of the event.
+        event_name: &'static str,
+        error: bincode::Error,
+    },
+    /// Component changes from a client with [`Authority`](crate::client::authority::Authority) can't be read.
+    InvalidAuthorityChange(bincode::Error),
+}
+
+/// Controls handling of [`ProtocolViolation`].
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViolationPolicy {
+    /// Only emit the event.
+    #[default]
+    Emit,
+    /// Emit the event and request the disconnect for the client via [`RepliconServer::disconnect`].
+    Disconnect,
 }
@@ -0,0 +1,213 @@
+use std::{any, io::Cursor};
+
+use bevy::{prelude::*, utils::HashMap};
+use bytes::Bytes;
+
+use super::{
+    replicon_server::RepliconServer, server_tick::ServerTick, ProtocolViolation, ViolationKind,
+};
+use crate::{
+    client::{
+        authority::{read_change, write_change, Authority, AuthorityFns, ValidateCtx, Validation},
+        server_entity_map::ServerEntityMap,
+    },
+    core::{
+        channels::ReplicationChannel,
+        ctx::{SerializeCtx, WriteCtx},
+        replication_registry::{FnsId, ReplicationRegistry},
+        ClientId,
+    },
+};
+
+/// Received messages grouped by replication functions.
+///
+/// Contains entries only for functions registered via
+/// [`AppAuthorityExt::replicate_from_client_with`](crate::client::authority::AppAuthorityExt::replicate_from_client_with).
+/// Reused between frames to avoid allocations.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub(crate) struct ReceivedChanges(HashMap<FnsId, Vec<(ClientId, Bytes)>>);
+
+/// Drains received messages and groups them by replication functions.
+///
+/// Messages for functions that aren't registered for authority are dropped.
+pub(crate) fn receive(
+    mut server: ResMut<RepliconServer>,
+    mut received: ResMut<ReceivedChanges>,
+    mut violations: EventWriter<ProtocolViolation>,
+    registry: Res<ReplicationRegistry>,
+) {
+    for (client_id, message) in server.receive(ReplicationChannel::Authority) {
+        let mut cursor = Cursor::new(&*message);
+        let result = registry.read_fns_id(&mut cursor).and_then(|fns_id| {
+            received.get_mut(&fns_id).ok_or_else(|| {
+                bincode::ErrorKind::Custom(
+                    "received replication functions ID that isn't registered for authority".into(),
+                )
+                .into()
+            })
+        });
+
+        match result {
+            Ok(messages) => {
+                let message = message.slice(cursor.position() as usize..);
+                messages.push((client_id, message));
+            }
+            Err(e) => {
+                violations.send(ProtocolViolation {
+                    client_id,
+                    kind: ViolationKind::InvalidAuthorityChange(e),
+                });
+            }
+        }
+    }
+}
+
+/// Validates and applies changes received from clients with authority.
+///
+/// Accepted values are not sent back to the owner. If a value was clamped or rejected,
+/// the resulting value is sent to the owner over [`ReplicationChannel::Authority`].
+pub(crate) fn apply_changes<C: Component>(
+    mut commands: Commands,
+    mut received: ResMut<ReceivedChanges>,
+    mut server: ResMut<RepliconServer>,
+    mut violations: EventWriter<ProtocolViolation>,
+    registry: Res<ReplicationRegistry>,
+    fns: Res<AuthorityFns<C>>,
+    server_tick: Res<ServerTick>,
+    mut components: Query<(&mut C, &Authority<C>)>,
+) {
+    let Some(messages) = received.get_mut(&fns.fns_id) else {
+        return;
+    };
+
+    // Entities are not mapped, so the map stays empty.
+    let mut entity_map = ServerEntityMap::default();
+    let serialize_ctx = SerializeCtx {
+        server_tick: **server_tick,
+    };
+    let mut corrections = HashMap::<ClientId, Cursor<Vec<u8>>>::default();
+    for (client_id, message) in messages.drain(..) {
+        let mut cursor = Cursor::new(&*message);
+        while cursor.position() < message.len() as u64 {
+            let (entity, bytes) = match read_change(&mut cursor) {
+                Ok(change) => change,
+                Err(e) => {
+                    violations.send(ProtocolViolation {
+                        client_id,
+                        kind: ViolationKind::InvalidAuthorityChange(e),
+                    });
+                    break;
+                }
+            };
+
+            let Ok((mut component, authority)) = components.get_mut(entity) else {
+                debug!("ignoring change for unknown {entity:?} from {client_id:?}");
+                continue;
+            };
+            if authority.client_id() != client_id {
+                debug!("ignoring change for {entity:?} from {client_id:?} without authority");
+                continue;
+            }
+
+            let mut ctx = WriteCtx::new_unmapped(&mut commands, &mut entity_map, **server_tick);
+            let new = match fns.rule_fns.deserialize(&mut ctx, &mut Cursor::new(bytes)) {
+                Ok(new) => new,
+                Err(e) => {
+                    violations.send(ProtocolViolation {
+                        client_id,
+                        kind: ViolationKind::InvalidAuthorityChange(e),
+                    });
+                    continue;
+                }
+            };
+
+            let validate_ctx = ValidateCtx { client_id, entity };
+            let new = match (fns.validate)(&validate_ctx, &component, &new) {
+                Validation::Accept => Some(new),
+                Validation::Clamp(clamped) => {
+                    debug!(
+                        "clamping `{}` for {entity:?} from {client_id:?}",
+                        any::type_name::<C>()
+                    );
+                    let message = corrections.entry(client_id).or_default();
+                    if let Err(e) =
+                        write_correction(message, &registry, &fns, &serialize_ctx, entity, &clamped)
+                    {
+                        error!("unable to write correction for {entity:?}: {e}");
+                    }
+                    Some(clamped)
+                }
+                Validation::Reject => {
+                    debug!(
+                        "rejecting `{}` for {entity:?} from {client_id:?}",
+                        any::type_name::<C>()
+                    );
+                    let message = corrections.entry(client_id).or_default();
+                    if let Err(e) = write_correction(
+                        message,
+                        &registry,
+                        &fns,
+                        &serialize_ctx,
+                        entity,
+                        &component,
+                    ) {
+                        error!("unable to write correction for {entity:?}: {e}");
+                    }
+                    None
+                }
+            };
+
+            let Some(new) = new else {
+                continue;
+            };
+
+            // Skip values that don't change anything to avoid needless replication.
+            let mut current_bytes = Cursor::new(Vec::new());
+            let mut new_bytes = Cursor::new(Vec::new());
+            let unchanged = fns
+                .rule_fns
+                .serialize(&serialize_ctx, &component, &mut current_bytes)
+                .and_then(|_| fns.rule_fns.serialize(&serialize_ctx, &new, &mut new_bytes))
+                .is_ok_and(|_| current_bytes.get_ref() == new_bytes.get_ref());
+            if !unchanged {
+                trace!(
+                    "applying `{}` for {entity:?} from {client_id:?}",
+                    any::type_name::<C>()
+                );
+                *component = new;
+            }
+        }
+    }
+
+    for (client_id, message) in corrections {
+        trace!(
+            "sending corrections of `{}` to {client_id:?}",
+            any::type_name::<C>()
+        );
+        server.send(
+            client_id,
+            ReplicationChannel::Authority,
+            message.into_inner(),
+        );
+    }
+}
+
+/// Writes a value that the owner should apply instead of the sent one.
+///
+/// Writes replication functions ID first if the message is empty.
+fn write_correction<C: Component>(
+    message: &mut Cursor<Vec<u8>>,
+    registry: &ReplicationRegistry,
+    fns: &AuthorityFns<C>,
+    ctx: &SerializeCtx,
+    entity: Entity,
+    component: &C,
+) -> bincode::Result<()> {
+    let mut bytes = Cursor::new(Vec::new());
+    fns.rule_fns.serialize(ctx, component, &mut bytes)?;
+
+    if message.get_ref().is_empty() {
+        registry.write_fns_id(&mut *message, fns.fns_id)?;
+    }
+    write_change(message, entity, bytes.get_ref())
+}
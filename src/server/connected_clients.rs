@@ -1,3 +1,7 @@
+pub(crate) mod client_baselines;
+pub mod client_component_visibility;
+mod client_initial_sync;
+pub mod client_priority;
 pub mod client_visibility;
 
 use std::mem;
@@ -8,24 +12,33 @@ use bevy::{
     utils::{Duration, HashMap},
 };
 
+use super::rooms::{RoomId, RoomIndex};
 use crate::{
-    core::{replicon_tick::RepliconTick, ClientId},
+    core::{compression::COMPRESSED_UPDATE_BIT, replicon_tick::RepliconTick, ClientId},
     server::VisibilityPolicy,
 };
-use client_visibility::ClientVisibility;
+use client_baselines::ClientBaselines;
+use client_component_visibility::ClientComponentVisibility;
+use client_initial_sync::ClientInitialSync;
+use client_priority::ClientPriority;
+use client_visibility::{ClientVisibility, Visibility};
 
 /// Stores information about connected clients.
 #[derive(Resource, Default)]
 pub struct ConnectedClients {
     clients: Vec<ConnectedClient>,
     policy: VisibilityPolicy,
+    initial_sync_budget: Option<usize>,
+    room_index: RoomIndex,
 }
 
 impl ConnectedClients {
-    pub(super) fn new(policy: VisibilityPolicy) -> Self {
+    pub(super) fn new(policy: VisibilityPolicy, initial_sync_budget: Option<usize>) -> Self {
         Self {
             clients: Default::default(),
             policy,
+            initial_sync_budget,
+            room_index: Default::default(),
         }
     }
 
@@ -103,17 +116,99 @@ impl ConnectedClients {
         self.clients.is_empty()
     }
 
-    /// Initializes a new [`ConnectedClient`] for this client.
+    /// Adds a client to a room.
+    ///
+    /// All entities with [`Rooms`](super::rooms::Rooms) containing this room become visible for the client.
+    /// Does nothing if the client is already in the room.
+    ///
+    /// This operation is *O*(*n*) over connected clients plus *O*(*m*) over entities in the room.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the passed client ID is not connected.
+    pub fn join_room(&mut self, client_id: ClientId, room_id: RoomId) {
+        let client = self
+            .clients
+            .iter_mut()
+            .find(|client| client.id == client_id)
+            .unwrap_or_else(|| panic!("{client_id:?} should be connected"));
+        if client.rooms.contains(&room_id) {
+            return;
+        }
+
+        client.rooms.push(room_id);
+        for entity in self.room_index.entities(room_id) {
+            client.add_room_entity(entity);
+        }
+    }
+
+    /// Removes a client from a room.
+    ///
+    /// Entities from this room that don't share any other room with the client become hidden for it.
+    /// Does nothing if the client is not in the room.
+    ///
+    /// This operation is *O*(*n*) over connected clients plus *O*(*m*) over entities in the room.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the passed client ID is not connected.
+    pub fn leave_room(&mut self, client_id: ClientId, room_id: RoomId) {
+        let client = self
+            .clients
+            .iter_mut()
+            .find(|client| client.id == client_id)
+            .unwrap_or_else(|| panic!("{client_id:?} should be connected"));
+        let Some(index) = client.rooms.iter().position(|&id| id == room_id) else {
+            return;
+        };
+
+        client.rooms.swap_remove(index);
+        for entity in self.room_index.entities(room_id) {
+            client.remove_room_entity(entity);
+        }
+    }
+
+    /// Updates rooms of an entity and its visibility for clients in the affected rooms.
+    pub(super) fn update_entity_rooms(&mut self, entity: Entity, rooms: &[RoomId]) {
+        let clients = &mut self.clients;
+        self.room_index
+            .update_entity(entity, rooms, |room_id, added| {
+                for client in clients
+                    .iter_mut()
+                    .filter(|client| client.rooms.contains(&room_id))
+                {
+                    if added {
+                        client.add_room_entity(entity);
+                    } else {
+                        client.remove_room_entity(entity);
+                    }
+                }
+            });
+    }
+
+    /// Removes a despawned entity from all rooms.
+    ///
+    /// Clients will clean up their data in [`ConnectedClient::remove_despawned`].
+    pub(super) fn remove_despawned_from_rooms(&mut self, entity: Entity) {
+        self.room_index.remove_despawned(entity);
+    }
+
+    /// Initializes a new [`ConnectedClient`] for this client connected at `timestamp`.
     ///
     /// Reuses the memory from the buffers if available.
-    pub(super) fn add(&mut self, client_buffers: &mut ClientBuffers, client_id: ClientId) {
+    pub(super) fn add(
+        &mut self,
+        client_buffers: &mut ClientBuffers,
+        client_id: ClientId,
+        timestamp: Duration,
+    ) {
         debug!("adding connected `{client_id:?}`");
 
         let client = if let Some(mut client) = client_buffers.clients.pop() {
-            client.reset(client_id);
+            client.reset(client_id, self.initial_sync_budget, timestamp);
             client
         } else {
-            ConnectedClient::new(client_id, self.policy)
+            ConnectedClient::new(client_id, self.policy, self.initial_sync_budget, timestamp)
         };
 
         self.clients.push(client);
@@ -156,6 +251,33 @@ pub struct ConnectedClient {
     /// Entity visibility settings.
     visibility: ClientVisibility,
 
+    /// Component visibility settings.
+    component_visibility: ClientComponentVisibility,
+
+    /// Serialized component values for delta compression.
+    baselines: ClientBaselines,
+
+    /// Entity priority settings.
+    priority: ClientPriority,
+
+    /// Rooms the client is in.
+    rooms: Vec<RoomId>,
+
+    /// Number of shared rooms for each entity.
+    ///
+    /// An entity is visible while the client shares at least one room with it.
+    room_entities: EntityHashMap<u32>,
+
+    /// Max number of bytes to send per tick.
+    ///
+    /// See also [`Self::set_bandwidth_budget`].
+    bandwidth_budget: Option<usize>,
+
+    /// Progress of sending the initial world state.
+    ///
+    /// See also [`Self::set_initial_sync_budget`].
+    initial_sync: ClientInitialSync,
+
     /// The last tick in which a replicated entity had an insertion, removal, or gained/lost a component from the
     /// perspective of the client.
     ///
@@ -163,6 +285,11 @@ pub struct ConnectedClient {
     /// message to arrive.
     init_tick: RepliconTick,
 
+    /// Indicates if all replicated resources were sent to the client.
+    ///
+    /// After that only their changes and removals are sent.
+    resources_synced: bool,
+
     /// Update message indexes mapped to their info.
     updates: HashMap<u16, UpdateInfo>,
 
@@ -170,17 +297,42 @@ pub struct ConnectedClient {
     ///
     /// See also [`Self::register_update`].
     next_update_index: u16,
+
+    /// Time when the client connected.
+    ///
+    /// Used to disconnect clients that don't send their protocol hash in time.
+    connected_at: Duration,
+
+    /// Indicates if the client sent a protocol hash that matches the server's.
+    ///
+    /// See also [`Self::is_protocol_verified`].
+    protocol_verified: bool,
 }
 
 impl ConnectedClient {
-    fn new(id: ClientId, policy: VisibilityPolicy) -> Self {
+    fn new(
+        id: ClientId,
+        policy: VisibilityPolicy,
+        initial_sync_budget: Option<usize>,
+        connected_at: Duration,
+    ) -> Self {
         Self {
             id,
             change_ticks: Default::default(),
             visibility: ClientVisibility::new(policy),
+            component_visibility: Default::default(),
+            baselines: Default::default(),
+            priority: Default::default(),
+            rooms: Default::default(),
+            room_entities: Default::default(),
+            bandwidth_budget: None,
+            initial_sync: ClientInitialSync::new(initial_sync_budget),
             init_tick: Default::default(),
+            resources_synced: false,
             updates: Default::default(),
             next_update_index: Default::default(),
+            connected_at,
+            protocol_verified: false,
         }
     }
 
@@ -189,6 +341,24 @@ impl ConnectedClient {
         self.id
     }
 
+    /// Returns `true` if the client sent a protocol hash that matches the server's.
+    ///
+    /// Until then the client doesn't receive replication and all its messages are ignored.
+    /// See also [`ProtocolHash`](crate::core::protocol::ProtocolHash).
+    pub fn is_protocol_verified(&self) -> bool {
+        self.protocol_verified
+    }
+
+    /// Marks the client's protocol hash as matching the server's.
+    pub(super) fn verify_protocol(&mut self) {
+        self.protocol_verified = true;
+    }
+
+    /// Returns the time when the client connected.
+    pub(super) fn connected_at(&self) -> Duration {
+        self.connected_at
+    }
+
     /// Returns a reference to the client's visibility settings.
     pub fn visibility(&self) -> &ClientVisibility {
         &self.visibility
@@ -199,6 +369,158 @@ impl ConnectedClient {
         &mut self.visibility
     }
 
+    /// Returns a reference to the client's component visibility settings.
+    pub fn component_visibility(&self) -> &ClientComponentVisibility {
+        &self.component_visibility
+    }
+
+    /// Returns a mutable reference to the client's component visibility settings.
+    pub fn component_visibility_mut(&mut self) -> &mut ClientComponentVisibility {
+        &mut self.component_visibility
+    }
+
+    /// Returns a reference to the client's entity priority settings.
+    pub fn priority(&self) -> &ClientPriority {
+        &self.priority
+    }
+
+    /// Returns a mutable reference to the client's entity priority settings.
+    pub fn priority_mut(&mut self) -> &mut ClientPriority {
+        &mut self.priority
+    }
+
+    /// Returns rooms the client is in.
+    ///
+    /// See also [`ConnectedClients::join_room`] and [`ConnectedClients::leave_room`].
+    pub fn rooms(&self) -> &[RoomId] {
+        &self.rooms
+    }
+
+    /// Increments the number of shared rooms for an entity and shows it if it's the first one.
+    fn add_room_entity(&mut self, entity: Entity) {
+        let count = self.room_entities.entry(entity).or_default();
+        *count += 1;
+        if *count == 1 {
+            self.visibility.set_visibility(entity, true);
+        }
+    }
+
+    /// Decrements the number of shared rooms for an entity and hides it if it was the last one.
+    fn remove_room_entity(&mut self, entity: Entity) {
+        let Some(count) = self.room_entities.get_mut(&entity) else {
+            return;
+        };
+        *count -= 1;
+        if *count == 0 {
+            self.room_entities.remove(&entity);
+            self.visibility.set_visibility(entity, false);
+        }
+    }
+
+    /// Sets max number of bytes of replication data to send to the client per tick.
+    ///
+    /// If the init and update messages exceed the budget, entities in the update message will be sent in
+    /// the order of their accumulated priority and the rest will be deferred to the next ticks.
+    /// The priority of an entity is its [`ReplicationPriority`](super::ReplicationPriority)
+    /// multiplied by the client's priority for it from [`Self::priority_mut`].
+    /// Each tick the entity is deferred, the priority is added to its accumulated value,
+    /// so low priority entities will eventually be sent too. Deferred changes are never lost.
+    ///
+    /// Init messages are always sent, but their size is subtracted from the budget.
+    /// At least one entity is sent if any budget remains, so entities larger than the budget
+    /// will be sent when they have the highest priority.
+    ///
+    /// By default it's [`None`], which means there is no limit.
+    pub fn set_bandwidth_budget(&mut self, budget: Option<usize>) {
+        self.bandwidth_budget = budget;
+    }
+
+    /// Returns max number of bytes of replication data to send to the client per tick.
+    ///
+    /// See also [`Self::set_bandwidth_budget`].
+    pub fn bandwidth_budget(&self) -> Option<usize> {
+        self.bandwidth_budget
+    }
+
+    /// Sets max number of bytes of init messages to send to the client per tick until it receives
+    /// all visible entities.
+    ///
+    /// While the initial sync is in progress, entities the client doesn't have yet are deferred
+    /// to the next ticks once the init message exceeds the budget. It spreads a large world over
+    /// multiple ticks instead of sending it in a single message. Changes for already sent entities,
+    /// despawns and resources are never deferred.
+    /// At least one new entity is sent per tick, so entities larger than the budget will be sent too.
+    ///
+    /// When all visible entities are sent, the client is notified and
+    /// [`initial_sync_complete`](crate::core::common_conditions::initial_sync_complete)
+    /// starts returning `true`. After that the budget no longer applies.
+    ///
+    /// Initialized from [`ServerPlugin::initial_sync_budget`](super::ServerPlugin::initial_sync_budget).
+    pub fn set_initial_sync_budget(&mut self, budget: Option<usize>) {
+        self.initial_sync.set_budget(budget);
+    }
+
+    /// Returns max number of bytes of init messages to send to the client per tick until it receives
+    /// all visible entities.
+    ///
+    /// See also [`Self::set_initial_sync_budget`].
+    pub fn initial_sync_budget(&self) -> Option<usize> {
+        self.initial_sync.budget()
+    }
+
+    /// Returns `true` if all visible entities were sent to the client.
+    ///
+    /// See also [`Self::set_initial_sync_budget`].
+    pub fn initial_sync_complete(&self) -> bool {
+        self.initial_sync.is_complete()
+    }
+
+    /// Decides if the entity should be deferred due to the initial sync budget or
+    /// the max init message size and caches the result.
+    ///
+    /// Should be called after caching the visibility for the entity.
+    pub(super) fn cache_sync_deferred(
+        &mut self,
+        entity: Entity,
+        message_size: usize,
+        max_size: Option<usize>,
+    ) {
+        let new_entity = self.visibility.cached_visibility() != Visibility::Hidden
+            && !self.change_ticks.contains_key(&entity);
+        self.initial_sync
+            .cache_deferred(new_entity, message_size, max_size);
+    }
+
+    /// Defers the entity from the last call of [`Self::cache_sync_deferred`] if it was new
+    /// and the init message exceeded `max_size` after writing it.
+    ///
+    /// Returns `true` if the entity was deferred and should be removed from the message.
+    pub(super) fn defer_oversized(&mut self, message_size: usize, max_size: Option<usize>) -> bool {
+        self.initial_sync.defer_oversized(message_size, max_size)
+    }
+
+    /// Returns `true` if the entity from the last call of [`Self::cache_sync_deferred`] is deferred.
+    pub(super) fn sync_deferred(&self) -> bool {
+        self.initial_sync.cached_deferred()
+    }
+
+    /// Finishes the initial sync tracking for the current tick.
+    ///
+    /// Returns `true` if the initial sync completed on this tick.
+    pub(super) fn finish_initial_sync_tick(&mut self, has_data: bool) -> bool {
+        self.initial_sync.finish_tick(has_data)
+    }
+
+    /// Returns a reference to the client's baselines for delta compression.
+    pub(crate) fn baselines(&self) -> &ClientBaselines {
+        &self.baselines
+    }
+
+    /// Returns a mutable reference to the client's baselines for delta compression.
+    pub(crate) fn baselines_mut(&mut self) -> &mut ClientBaselines {
+        &mut self.baselines
+    }
+
     /// Sets the client's init tick.
     pub(super) fn set_init_tick(&mut self, tick: RepliconTick) {
         self.init_tick = tick;
@@ -210,10 +532,20 @@ impl ConnectedClient {
         self.init_tick
     }
 
+    /// Returns `true` if all replicated resources were sent to the client.
+    pub(super) fn resources_synced(&self) -> bool {
+        self.resources_synced
+    }
+
+    /// Marks all replicated resources as sent to the client.
+    pub(super) fn set_resources_synced(&mut self) {
+        self.resources_synced = true;
+    }
+
     /// Clears all entities for unacknowledged updates, returning them as an iterator.
     ///
     /// Keeps the allocated memory for reuse.
-    fn drain_entities(&mut self) -> impl Iterator<Item = Vec<Entity>> + '_ {
+    fn drain_entities(&mut self) -> impl Iterator<Item = Vec<(Entity, Tick)>> + '_ {
         self.updates
             .drain()
             .map(|(_, update_info)| update_info.entities)
@@ -222,15 +554,28 @@ impl ConnectedClient {
     /// Resets all data.
     ///
     /// Keeps the allocated memory for reuse.
-    fn reset(&mut self, id: ClientId) {
+    fn reset(&mut self, id: ClientId, initial_sync_budget: Option<usize>, connected_at: Duration) {
         self.id = id;
         self.visibility.clear();
+        self.component_visibility.clear();
+        self.baselines.clear();
+        self.priority.clear();
+        self.rooms.clear();
+        self.room_entities.clear();
+        self.bandwidth_budget = None;
+        self.initial_sync.clear(initial_sync_budget);
         self.change_ticks.clear();
+        self.resources_synced = false;
         self.updates.clear();
         self.next_update_index = 0;
+        self.connected_at = connected_at;
+        self.protocol_verified = false;
     }
 
-    /// Registers update at specified `tick` and `timestamp` and returns its index with entities to fill.
+    /// Registers update at specified `tick`, `server_tick` and `timestamp` and returns its index with entities to fill.
+    ///
+    /// Each entity is stored with the tick that will be set as its change tick after acknowledgment.
+    /// Usually it's equal to `tick`, but could be lower if some changes were skipped.
     ///
     /// Used later to acknowledge updated entities.
     #[must_use]
@@ -238,15 +583,18 @@ impl ConnectedClient {
         &mut self,
         client_buffers: &mut ClientBuffers,
         tick: Tick,
+        server_tick: RepliconTick,
         timestamp: Duration,
-    ) -> (u16, &mut Vec<Entity>) {
+    ) -> (u16, &mut Vec<(Entity, Tick)>) {
         let update_index = self.next_update_index;
-        self.next_update_index = self.next_update_index.overflowing_add(1).0;
+        // Wrap before the bit that marks compressed messages.
+        self.next_update_index = (self.next_update_index + 1) & !COMPRESSED_UPDATE_BIT;
 
         let mut entities = client_buffers.entities.pop().unwrap_or_default();
         entities.clear();
         let update_info = UpdateInfo {
             tick,
+            server_tick,
             timestamp,
             entities,
         };
@@ -268,13 +616,14 @@ impl ConnectedClient {
     }
 
     /// Gets the change tick for an entity that is replicated to this client.
-    pub fn get_change_tick(&mut self, entity: Entity) -> Option<Tick> {
+    pub fn get_change_tick(&self, entity: Entity) -> Option<Tick> {
         self.change_ticks.get(&entity).copied()
     }
 
     /// Marks update with the specified index as acknowledged.
     ///
-    /// Change limits for all entities from this update will be set to the update's tick if it's higher.
+    /// Change limits for all entities from this update will be set to their ticks from the update if they're higher.
+    /// Component values sent in this update become baselines for delta compression.
     ///
     /// Keeps allocated memory in the buffers for reuse.
     pub(super) fn acknowledge(
@@ -291,16 +640,18 @@ impl ConnectedClient {
             return;
         };
 
-        for entity in &update_info.entities {
-            let Some(last_tick) = self.change_ticks.get_mut(entity) else {
+        for &(entity, entity_tick) in &update_info.entities {
+            self.baselines.acknowledge(entity, update_info.server_tick);
+
+            let Some(last_tick) = self.change_ticks.get_mut(&entity) else {
                 // We ignore missing entities, since they were probably despawned.
                 continue;
             };
 
             // Received tick could be outdated because we bump it
             // if we detect any insertion on the entity in `collect_changes`.
-            if !last_tick.is_newer_than(update_info.tick, tick) {
-                *last_tick = update_info.tick;
+            if !last_tick.is_newer_than(entity_tick, tick) {
+                *last_tick = entity_tick;
             }
         }
         client_buffers.entities.push(update_info.entities);
@@ -312,10 +663,23 @@ impl ConnectedClient {
         );
     }
 
+    /// Marks all sent updates as acknowledged.
+    ///
+    /// Used for virtual clients that can't send acknowledgments.
+    pub(super) fn acknowledge_all(&mut self, client_buffers: &mut ClientBuffers, tick: Tick) {
+        while let Some(&update_index) = self.updates.keys().next() {
+            self.acknowledge(client_buffers, tick, update_index);
+        }
+    }
+
     /// Removes a despawned entity tracked by this client.
     pub fn remove_despawned(&mut self, entity: Entity) {
         self.change_ticks.remove(&entity);
+        self.baselines.remove(entity);
+        self.priority.remove_despawned(entity);
         self.visibility.remove_despawned(entity);
+        self.component_visibility.remove_despawned(entity);
+        self.room_entities.remove(&entity);
         // We don't clean up `self.updates` for efficiency reasons.
         // `Self::acknowledge()` will properly ignore despawned entities.
     }
@@ -326,6 +690,8 @@ impl ConnectedClient {
     pub(super) fn drain_lost_visibility(&mut self) -> impl Iterator<Item = Entity> + '_ {
         self.visibility.drain_lost_visibility().inspect(|entity| {
             self.change_ticks.remove(entity);
+            self.baselines.remove(*entity);
+            self.priority.reset_accumulated(*entity);
         })
     }
 
@@ -361,11 +727,12 @@ pub(crate) struct ClientBuffers {
     /// [`Vec`]'s from acknowledged update indexes from [`ConnectedClient`].
     ///
     /// Stored to reuse allocated capacity.
-    entities: Vec<Vec<Entity>>,
+    entities: Vec<Vec<(Entity, Tick)>>,
 }
 
 struct UpdateInfo {
     tick: Tick,
+    server_tick: RepliconTick,
     timestamp: Duration,
-    entities: Vec<Entity>,
+    entities: Vec<(Entity, Tick)>,
 }
@@ -0,0 +1,213 @@
+use bevy::{ecs::entity::EntityHashMap, prelude::*};
+use bytes::Bytes;
+
+use crate::core::{
+    delta_compression::MAX_BASELINE_AGE, replication_registry::FnsId, replicon_tick::RepliconTick,
+};
+
+/// Serialized component values used as baselines for delta compression for a client.
+///
+/// See also [`RuleFns::with_delta_compression`](crate::core::replication_registry::rule_fns::RuleFns::with_delta_compression).
+#[derive(Default)]
+pub(crate) struct ClientBaselines(EntityHashMap<Vec<ComponentBaseline>>);
+
+impl ClientBaselines {
+    /// Returns the last acknowledged value for a component if it's not too old to be used at `server_tick`.
+    pub(crate) fn get(
+        &self,
+        entity: Entity,
+        fns_id: FnsId,
+        server_tick: RepliconTick,
+    ) -> Option<(RepliconTick, &[u8])> {
+        let baselines = self.0.get(&entity)?;
+        let baseline = baselines
+            .iter()
+            .find(|baseline| baseline.fns_id == fns_id)?;
+        let (tick, bytes) = baseline.acked.as_ref()?;
+        if server_tick - *tick >= MAX_BASELINE_AGE {
+            return None;
+        }
+
+        Some((*tick, bytes))
+    }
+
+    /// Remembers a component value sent at `tick`.
+    ///
+    /// The value will become a baseline after acknowledgment of the tick for the entity.
+    pub(crate) fn insert_sent(
+        &mut self,
+        entity: Entity,
+        fns_id: FnsId,
+        tick: RepliconTick,
+        bytes: Bytes,
+    ) {
+        let baselines = self.0.entry(entity).or_default();
+        let baseline = match baselines
+            .iter_mut()
+            .position(|baseline| baseline.fns_id == fns_id)
+        {
+            Some(index) => &mut baselines[index],
+            None => {
+                baselines.push(ComponentBaseline {
+                    fns_id,
+                    acked: None,
+                    sent: Vec::new(),
+                });
+                baselines.last_mut().unwrap()
+            }
+        };
+
+        // Values from lost updates will never be acknowledged.
+        baseline
+            .sent
+            .retain(|&(sent_tick, _)| tick - sent_tick < MAX_BASELINE_AGE);
+        baseline.sent.push((tick, bytes));
+    }
+
+    /// Marks all values sent for the entity at `tick` as acknowledged.
+    ///
+    /// Values will replace the current baselines if they are newer.
+    /// Values sent before `tick` are discarded since the client will treat them as outdated.
+    pub(crate) fn acknowledge(&mut self, entity: Entity, tick: RepliconTick) {
+        let Some(baselines) = self.0.get_mut(&entity) else {
+            return;
+        };
+
+        for baseline in baselines {
+            if let Some(index) = baseline
+                .sent
+                .iter()
+                .position(|&(sent_tick, _)| sent_tick == tick)
+            {
+                let (sent_tick, bytes) = baseline.sent.remove(index);
+                let newer = match baseline.acked {
+                    Some((acked_tick, _)) => sent_tick > acked_tick,
+                    None => true,
+                };
+                if newer {
+                    baseline.acked = Some((sent_tick, bytes));
+                }
+            }
+
+            baseline.sent.retain(|&(sent_tick, _)| sent_tick > tick);
+        }
+    }
+
+    /// Removes all values for an entity.
+    ///
+    /// Should be called when the entity is despawned or the client loses visibility of it,
+    /// because the client discards its values too.
+    pub(crate) fn remove(&mut self, entity: Entity) {
+        self.0.remove(&entity);
+    }
+
+    /// Removes all values.
+    pub(crate) fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// Serialized values for a component on an entity.
+struct ComponentBaseline {
+    fns_id: FnsId,
+
+    /// Last value acknowledged by the client and its tick.
+    acked: Option<(RepliconTick, Bytes)>,
+
+    /// Values sent to the client and their ticks.
+    sent: Vec<(RepliconTick, Bytes)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::core::replication_registry::{rule_fns::RuleFns, ReplicationRegistry};
+
+    #[test]
+    fn acknowledge() {
+        const ENTITY: Entity = Entity::PLACEHOLDER;
+        let fns_id = test_fns_id();
+        let mut baselines = ClientBaselines::default();
+        baselines.insert_sent(
+            ENTITY,
+            fns_id,
+            RepliconTick::new(1),
+            Bytes::from_static(&[1]),
+        );
+        baselines.insert_sent(
+            ENTITY,
+            fns_id,
+            RepliconTick::new(2),
+            Bytes::from_static(&[2]),
+        );
+        baselines.insert_sent(
+            ENTITY,
+            fns_id,
+            RepliconTick::new(3),
+            Bytes::from_static(&[3]),
+        );
+        assert!(baselines
+            .get(ENTITY, fns_id, RepliconTick::new(3))
+            .is_none());
+
+        baselines.acknowledge(ENTITY, RepliconTick::new(2));
+        let (tick, bytes) = baselines.get(ENTITY, fns_id, RepliconTick::new(3)).unwrap();
+        assert_eq!(tick, RepliconTick::new(2));
+        assert_eq!(bytes, [2]);
+
+        baselines.acknowledge(ENTITY, RepliconTick::new(1));
+        let (tick, _) = baselines.get(ENTITY, fns_id, RepliconTick::new(3)).unwrap();
+        assert_eq!(
+            tick,
+            RepliconTick::new(2),
+            "older values should be discarded"
+        );
+
+        baselines.acknowledge(ENTITY, RepliconTick::new(3));
+        let (tick, _) = baselines.get(ENTITY, fns_id, RepliconTick::new(3)).unwrap();
+        assert_eq!(tick, RepliconTick::new(3));
+    }
+
+    #[test]
+    fn outdated() {
+        const ENTITY: Entity = Entity::PLACEHOLDER;
+        let fns_id = test_fns_id();
+        let mut baselines = ClientBaselines::default();
+        baselines.insert_sent(ENTITY, fns_id, RepliconTick::new(1), Bytes::new());
+        baselines.acknowledge(ENTITY, RepliconTick::new(1));
+
+        assert!(baselines
+            .get(ENTITY, fns_id, RepliconTick::new(2))
+            .is_some());
+        assert!(baselines
+            .get(ENTITY, fns_id, RepliconTick::new(1 + MAX_BASELINE_AGE))
+            .is_none());
+    }
+
+    #[test]
+    fn removal() {
+        const ENTITY: Entity = Entity::PLACEHOLDER;
+        let fns_id = test_fns_id();
+        let mut baselines = ClientBaselines::default();
+        baselines.insert_sent(ENTITY, fns_id, RepliconTick::new(1), Bytes::new());
+        baselines.remove(ENTITY);
+        baselines.acknowledge(ENTITY, RepliconTick::new(1));
+
+        assert!(baselines
+            .get(ENTITY, fns_id, RepliconTick::new(1))
+            .is_none());
+    }
+
+    fn test_fns_id() -> FnsId {
+        let mut world = World::new();
+        let mut registry = ReplicationRegistry::default();
+        registry
+            .register_rule_fns(&mut world, RuleFns::<TestComponent>::default())
+            .fns_id()
+    }
+
+    #[derive(Component, Deserialize, Serialize)]
+    struct TestComponent;
+}
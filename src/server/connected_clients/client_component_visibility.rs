@@ -0,0 +1,191 @@
+use bevy::{
+    ecs::{component::ComponentId, entity::EntityHashMap},
+    prelude::*,
+};
+
+/// Component visibility settings for a client.
+///
+/// Allows hiding individual components of an entity that is visible for the client.
+/// All components are visible by default.
+///
+/// When a component becomes hidden, the client receives its removal. When it becomes visible
+/// again, the client receives it as a newly inserted component.
+///
+/// Components are identified by [`ComponentId`], which can be obtained via
+/// [`World::component_id`] or [`Components::component_id`](bevy::ecs::component::Components::component_id).
+#[derive(Default)]
+pub struct ClientComponentVisibility {
+    /// Hidden components for each entity.
+    hidden: EntityHashMap<Vec<ComponentId>>,
+
+    /// Components that became visible during this tick.
+    gained: EntityHashMap<Vec<ComponentId>>,
+
+    /// Components that became hidden during this tick.
+    lost: EntityHashMap<Vec<ComponentId>>,
+}
+
+impl ClientComponentVisibility {
+    /// Sets visibility of a component on a specific entity.
+    pub fn set_visibility(&mut self, entity: Entity, component_id: ComponentId, visible: bool) {
+        if visible {
+            let Some(hidden) = self.hidden.get_mut(&entity) else {
+                return;
+            };
+            let Some(index) = hidden.iter().position(|&id| id == component_id) else {
+                return;
+            };
+            hidden.swap_remove(index);
+            if hidden.is_empty() {
+                self.hidden.remove(&entity);
+            }
+
+            // If the component was hidden in this tick, then undo it.
+            if !remove_entry(&mut self.lost, entity, component_id) {
+                self.gained.entry(entity).or_default().push(component_id);
+            }
+        } else {
+            let hidden = self.hidden.entry(entity).or_default();
+            if hidden.contains(&component_id) {
+                return;
+            }
+            hidden.push(component_id);
+
+            // If the component was shown in this tick, then undo it.
+            if !remove_entry(&mut self.gained, entity, component_id) {
+                self.lost.entry(entity).or_default().push(component_id);
+            }
+        }
+    }
+
+    /// Checks if a component on a specific entity is visible.
+    pub fn is_visible(&self, entity: Entity, component_id: ComponentId) -> bool {
+        !self
+            .hidden
+            .get(&entity)
+            .is_some_and(|hidden| hidden.contains(&component_id))
+    }
+
+    /// Returns `true` if the component became visible during this tick.
+    pub(crate) fn is_gained(&self, entity: Entity, component_id: ComponentId) -> bool {
+        self.gained
+            .get(&entity)
+            .is_some_and(|gained| gained.contains(&component_id))
+    }
+
+    /// Returns `true` if any component of the entity became hidden during this tick.
+    pub(crate) fn has_lost(&self, entity: Entity) -> bool {
+        self.lost.contains_key(&entity)
+    }
+
+    /// Returns an iterator over entities and their components that became hidden during this tick.
+    pub(crate) fn iter_lost(&self) -> impl Iterator<Item = (Entity, &[ComponentId])> {
+        self.lost
+            .iter()
+            .map(|(&entity, components)| (entity, &components[..]))
+    }
+
+    /// Clears information about gained and lost components.
+    ///
+    /// Should be called after each tick.
+    pub(crate) fn update(&mut self) {
+        self.gained.clear();
+        self.lost.clear();
+    }
+
+    /// Removes all data related to an entity.
+    pub(super) fn remove_despawned(&mut self, entity: Entity) {
+        self.hidden.remove(&entity);
+        self.gained.remove(&entity);
+        self.lost.remove(&entity);
+    }
+
+    /// Clears all data.
+    pub(super) fn clear(&mut self) {
+        self.hidden.clear();
+        self.gained.clear();
+        self.lost.clear();
+    }
+}
+
+/// Removes a component from the entity's list and returns `true` if it was present.
+fn remove_entry(
+    map: &mut EntityHashMap<Vec<ComponentId>>,
+    entity: Entity,
+    component_id: ComponentId,
+) -> bool {
+    let Some(components) = map.get_mut(&entity) else {
+        return false;
+    };
+    let Some(index) = components.iter().position(|&id| id == component_id) else {
+        return false;
+    };
+
+    components.swap_remove(index);
+    if components.is_empty() {
+        map.remove(&entity);
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hiding() {
+        let mut visibility = ClientComponentVisibility::default();
+        let component_id = ComponentId::new(0);
+        assert!(visibility.is_visible(Entity::PLACEHOLDER, component_id));
+
+        visibility.set_visibility(Entity::PLACEHOLDER, component_id, false);
+        assert!(!visibility.is_visible(Entity::PLACEHOLDER, component_id));
+        assert!(visibility.has_lost(Entity::PLACEHOLDER));
+
+        visibility.update();
+        assert!(!visibility.is_visible(Entity::PLACEHOLDER, component_id));
+        assert!(!visibility.has_lost(Entity::PLACEHOLDER));
+
+        visibility.set_visibility(Entity::PLACEHOLDER, component_id, true);
+        assert!(visibility.is_visible(Entity::PLACEHOLDER, component_id));
+        assert!(visibility.is_gained(Entity::PLACEHOLDER, component_id));
+
+        visibility.update();
+        assert!(!visibility.is_gained(Entity::PLACEHOLDER, component_id));
+    }
+
+    #[test]
+    fn undo() {
+        let mut visibility = ClientComponentVisibility::default();
+        let component_id = ComponentId::new(0);
+
+        visibility.set_visibility(Entity::PLACEHOLDER, component_id, false);
+        visibility.set_visibility(Entity::PLACEHOLDER, component_id, true);
+        assert!(visibility.is_visible(Entity::PLACEHOLDER, component_id));
+        assert!(!visibility.has_lost(Entity::PLACEHOLDER));
+        assert!(!visibility.is_gained(Entity::PLACEHOLDER, component_id));
+
+        visibility.set_visibility(Entity::PLACEHOLDER, component_id, false);
+        visibility.update();
+        visibility.set_visibility(Entity::PLACEHOLDER, component_id, true);
+        visibility.set_visibility(Entity::PLACEHOLDER, component_id, false);
+        assert!(!visibility.is_visible(Entity::PLACEHOLDER, component_id));
+        assert!(!visibility.has_lost(Entity::PLACEHOLDER));
+        assert!(!visibility.is_gained(Entity::PLACEHOLDER, component_id));
+    }
+
+    #[test]
+    fn duplicate() {
+        let mut visibility = ClientComponentVisibility::default();
+        let component_id = ComponentId::new(0);
+
+        visibility.set_visibility(Entity::PLACEHOLDER, component_id, true);
+        assert!(visibility.gained.is_empty());
+
+        visibility.set_visibility(Entity::PLACEHOLDER, component_id, false);
+        visibility.set_visibility(Entity::PLACEHOLDER, component_id, false);
+        assert_eq!(visibility.iter_lost().count(), 1);
+        assert_eq!(visibility.hidden[&Entity::PLACEHOLDER].len(), 1);
+    }
+}
@@ -0,0 +1,226 @@
+/// Tracks sending of the initial world state to a client.
+///
+/// While the sync is in progress, entities unknown to the client are deferred to the next ticks
+/// once the init message for the current tick exceeds the budget.
+/// If the budget is set, such entities are also deferred at any time once the init message exceeds
+/// the max payload size reported by the messaging backend.
+#[derive(Default)]
+pub(super) struct ClientInitialSync {
+    /// Max number of bytes of init messages to send per tick until the sync completes.
+    budget: Option<usize>,
+
+    /// Indicates if all visible entities were sent to the client.
+    complete: bool,
+
+    /// Indicates if a new entity was sent to the client on the current tick.
+    progressed: bool,
+
+    /// Indicates if any entity was deferred on the current tick.
+    deferred: bool,
+
+    /// Result of the last call of [`Self::cache_deferred`].
+    cached_deferred: bool,
+
+    /// Indicates if the entity from the last call of [`Self::cache_deferred`] could be deferred.
+    ///
+    /// Used by [`Self::defer_oversized`].
+    cached_deferrable: bool,
+}
+
+impl ClientInitialSync {
+    pub(super) fn new(budget: Option<usize>) -> Self {
+        Self {
+            budget,
+            ..Default::default()
+        }
+    }
+
+    pub(super) fn set_budget(&mut self, budget: Option<usize>) {
+        self.budget = budget;
+    }
+
+    pub(super) fn budget(&self) -> Option<usize> {
+        self.budget
+    }
+
+    pub(super) fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Decides if a new entity should be deferred based on the current size of the init message.
+    ///
+    /// At least one new entity is sent per tick even if the message already exceeds the budget or `max_size`.
+    /// Without a budget, init messages are never split.
+    /// The result can be obtained later via [`Self::cached_deferred`].
+    pub(super) fn cache_deferred(
+        &mut self,
+        new_entity: bool,
+        message_size: usize,
+        max_size: Option<usize>,
+    ) {
+        self.cached_deferrable = new_entity && self.progressed && self.budget.is_some();
+        self.cached_deferred = self.cached_deferrable
+            && (max_size.is_some_and(|max_size| message_size >= max_size)
+                || self
+                    .budget
+                    .is_some_and(|budget| !self.complete && message_size >= budget));
+
+        if self.cached_deferred {
+            self.deferred = true;
+        } else if new_entity {
+            self.progressed = true;
+        }
+    }
+
+    pub(super) fn cached_deferred(&self) -> bool {
+        self.cached_deferred
+    }
+
+    /// Defers the already written entity from the last call of [`Self::cache_deferred`]
+    /// if the init message exceeded `max_size` because of it.
+    ///
+    /// Returns `true` if the entity was deferred and its data should be discarded.
+    pub(super) fn defer_oversized(&mut self, message_size: usize, max_size: Option<usize>) -> bool {
+        let Some(max_size) = max_size else {
+            return false;
+        };
+        if !self.cached_deferrable || message_size <= max_size {
+            return false;
+        }
+
+        self.cached_deferred = true;
+        self.deferred = true;
+        true
+    }
+
+    /// Clears per-tick state and returns `true` if the sync completed on this tick.
+    ///
+    /// Without a budget, the sync completes only on a tick with data to send
+    /// to avoid sending a separate message to each connected client.
+    pub(super) fn finish_tick(&mut self, has_data: bool) -> bool {
+        let just_completed =
+            !self.complete && !self.deferred && (has_data || self.budget.is_some());
+        self.complete |= just_completed;
+        self.progressed = false;
+        self.deferred = false;
+        self.cached_deferred = false;
+        self.cached_deferrable = false;
+
+        just_completed
+    }
+
+    pub(super) fn clear(&mut self, budget: Option<usize>) {
+        *self = Self::new(budget);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_SIZE: Option<usize> = Some(1200);
+
+    #[test]
+    fn deferral() {
+        let mut initial_sync = ClientInitialSync::new(Some(10));
+
+        initial_sync.cache_deferred(true, 20, MAX_SIZE);
+        assert!(
+            !initial_sync.cached_deferred(),
+            "first entity should be sent regardless of the budget"
+        );
+
+        initial_sync.cache_deferred(true, 5, MAX_SIZE);
+        assert!(!initial_sync.cached_deferred());
+
+        initial_sync.cache_deferred(false, 20, MAX_SIZE);
+        assert!(
+            !initial_sync.cached_deferred(),
+            "known entities shouldn't be deferred"
+        );
+
+        initial_sync.cache_deferred(true, 20, MAX_SIZE);
+        assert!(initial_sync.cached_deferred());
+
+        assert!(!initial_sync.finish_tick(true));
+        assert!(!initial_sync.is_complete());
+
+        initial_sync.cache_deferred(true, 0, MAX_SIZE);
+        assert!(initial_sync.finish_tick(true));
+        assert!(initial_sync.is_complete());
+
+        initial_sync.cache_deferred(true, 20, MAX_SIZE);
+        initial_sync.cache_deferred(true, 20, MAX_SIZE);
+        assert!(
+            !initial_sync.cached_deferred(),
+            "nothing should be deferred after completion"
+        );
+        assert!(!initial_sync.finish_tick(true));
+    }
+
+    #[test]
+    fn max_size() {
+        let mut initial_sync = ClientInitialSync::new(Some(usize::MAX));
+
+        initial_sync.cache_deferred(true, 20, Some(10));
+        assert!(
+            !initial_sync.cached_deferred(),
+            "first entity should be sent regardless of the max size"
+        );
+        assert!(!initial_sync.defer_oversized(30, Some(10)));
+
+        initial_sync.cache_deferred(true, 5, Some(10));
+        assert!(!initial_sync.cached_deferred());
+        assert!(initial_sync.defer_oversized(15, Some(10)));
+        assert!(initial_sync.cached_deferred());
+
+        initial_sync.cache_deferred(false, 20, Some(10));
+        assert!(
+            !initial_sync.defer_oversized(30, Some(10)),
+            "known entities shouldn't be deferred"
+        );
+
+        assert!(!initial_sync.finish_tick(true));
+
+        initial_sync.cache_deferred(true, 0, Some(10));
+        assert!(initial_sync.finish_tick(true));
+
+        initial_sync.cache_deferred(true, 0, Some(10));
+        initial_sync.cache_deferred(true, 20, Some(10));
+        assert!(
+            initial_sync.cached_deferred(),
+            "entities should be deferred by the max size even after completion"
+        );
+    }
+
+    #[test]
+    fn without_budget() {
+        let mut initial_sync = ClientInitialSync::new(None);
+
+        initial_sync.cache_deferred(true, 20, Some(10));
+        initial_sync.cache_deferred(true, 20, Some(10));
+        assert!(
+            !initial_sync.cached_deferred(),
+            "init messages shouldn't be split without a budget"
+        );
+        assert!(!initial_sync.defer_oversized(30, Some(10)));
+        assert!(initial_sync.finish_tick(true));
+    }
+
+    #[test]
+    fn without_data() {
+        let mut initial_sync = ClientInitialSync::new(Some(10));
+        assert!(
+            initial_sync.finish_tick(false),
+            "sync with budget should complete even without data"
+        );
+
+        let mut initial_sync = ClientInitialSync::new(None);
+        assert!(
+            !initial_sync.finish_tick(false),
+            "sync without budget should complete only with data"
+        );
+        assert!(!initial_sync.is_complete());
+        assert!(initial_sync.finish_tick(true));
+    }
+}
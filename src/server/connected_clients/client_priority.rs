@@ -0,0 +1,93 @@
+use bevy::{ecs::entity::EntityHashMap, prelude::*};
+
+/// Entity replication priority settings for a client.
+///
+/// Used only if the client has a bandwidth budget.
+/// See [`ConnectedClient::set_bandwidth_budget`](super::ConnectedClient::set_bandwidth_budget) for details.
+#[derive(Default)]
+pub struct ClientPriority {
+    /// Priority multipliers for entities.
+    multipliers: EntityHashMap<f32>,
+
+    /// Priorities accumulated by entities that were deferred due to the budget.
+    accumulated: EntityHashMap<f32>,
+}
+
+impl ClientPriority {
+    /// Sets priority multiplier for an entity for this client.
+    ///
+    /// The final priority of an entity is its [`ReplicationPriority`](crate::server::ReplicationPriority)
+    /// multiplied by this value. By default it's 1.0.
+    pub fn set_priority(&mut self, entity: Entity, multiplier: f32) {
+        if multiplier == 1.0 {
+            self.multipliers.remove(&entity);
+        } else {
+            self.multipliers.insert(entity, multiplier);
+        }
+    }
+
+    /// Returns priority multiplier for an entity for this client.
+    ///
+    /// See also [`Self::set_priority`].
+    pub fn priority(&self, entity: Entity) -> f32 {
+        self.multipliers.get(&entity).copied().unwrap_or(1.0)
+    }
+
+    /// Returns priority accumulated by an entity while it was deferred.
+    pub fn accumulated(&self, entity: Entity) -> f32 {
+        self.accumulated.get(&entity).copied().unwrap_or_default()
+    }
+
+    /// Adds the entity priority based on `base_priority` to the accumulated priority and returns the result.
+    pub(crate) fn accumulate(&mut self, entity: Entity, base_priority: f32) -> f32 {
+        let priority = base_priority * self.priority(entity);
+        let accumulated = self.accumulated.entry(entity).or_default();
+        *accumulated += priority;
+        *accumulated
+    }
+
+    /// Resets the accumulated priority for an entity.
+    ///
+    /// Should be called when the entity is sent.
+    pub(crate) fn reset_accumulated(&mut self, entity: Entity) {
+        self.accumulated.remove(&entity);
+    }
+
+    /// Removes all data related to an entity.
+    pub(super) fn remove_despawned(&mut self, entity: Entity) {
+        self.multipliers.remove(&entity);
+        self.accumulated.remove(&entity);
+    }
+
+    /// Clears all data.
+    pub(super) fn clear(&mut self) {
+        self.multipliers.clear();
+        self.accumulated.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accumulation() {
+        let mut priority = ClientPriority::default();
+        assert_eq!(priority.accumulate(Entity::PLACEHOLDER, 1.0), 1.0);
+        assert_eq!(priority.accumulate(Entity::PLACEHOLDER, 1.0), 2.0);
+
+        priority.reset_accumulated(Entity::PLACEHOLDER);
+        assert_eq!(priority.accumulated(Entity::PLACEHOLDER), 0.0);
+    }
+
+    #[test]
+    fn multiplier() {
+        let mut priority = ClientPriority::default();
+        priority.set_priority(Entity::PLACEHOLDER, 2.0);
+        assert_eq!(priority.accumulate(Entity::PLACEHOLDER, 1.5), 3.0);
+
+        priority.remove_despawned(Entity::PLACEHOLDER);
+        assert_eq!(priority.priority(Entity::PLACEHOLDER), 1.0);
+        assert_eq!(priority.accumulated(Entity::PLACEHOLDER), 0.0);
+    }
+}
@@ -0,0 +1,251 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashMap};
+use bytes::Bytes;
+
+use crate::core::{replicon_tick::RepliconTick, ClientId};
+
+/// Quotas for a client event applied to each client individually.
+///
+/// Messages over any quota are dropped before deserialization.
+/// See also [`ClientEventAppExt::limit_client_event`](crate::client::events::ClientEventAppExt::limit_client_event).
+#[derive(Clone, Copy, Debug)]
+pub struct EventLimit {
+    /// Maximum number of events a client can send per second.
+    ///
+    /// Allows short bursts up to the same number of events.
+    pub events_per_second: Option<u32>,
+
+    /// Maximum number of bytes a client can send per server tick.
+    ///
+    /// The quota is restored when [`ServerTick`](super::server_tick::ServerTick) changes,
+    /// so with [`TickPolicy::MaxTickRate`](super::TickPolicy::MaxTickRate) it covers all frames of a tick.
+    pub bytes_per_tick: Option<usize>,
+
+    /// What to do with a client that exceeded a quota.
+    pub action: LimitAction,
+}
+
+impl Default for EventLimit {
+    fn default() -> Self {
+        Self {
+            events_per_second: None,
+            bytes_per_tick: None,
+            action: LimitAction::DropAndLog,
+        }
+    }
+}
+
+/// Action for clients that exceed [`EventLimit`].
+///
+/// Exceeding messages are always dropped, actions are performed at most once per frame for each client.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LimitAction {
+    /// Silently drop messages.
+    Drop,
+    /// Drop messages and log a warning.
+    DropAndLog,
+    /// Drop messages and emit [`EventLimitExceeded`].
+    ///
+    /// Useful to kick misbehaving clients.
+    DropAndEmit,
+}
+
+/// An event that indicates that a client exceeded [`EventLimit`] with [`LimitAction::DropAndEmit`].
+///
+/// Emitted only on server.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct EventLimitExceeded {
+    /// Client that exceeded the limit.
+    pub client_id: ClientId,
+
+    /// Type name of the limited event.
+    pub event_name: &'static str,
+
+    /// Number of messages dropped this frame.
+    pub dropped: usize,
+}
+
+/// Tracks quotas of [`EventLimit`] for each client.
+pub(crate) struct EventLimiter {
+    limit: EventLimit,
+    clients: HashMap<ClientId, ClientQuota>,
+
+    /// Tick for which byte quotas were last restored.
+    tick: Option<RepliconTick>,
+}
+
+impl EventLimiter {
+    pub(crate) fn new(limit: EventLimit) -> Self {
+        Self {
+            limit,
+            clients: Default::default(),
+            tick: None,
+        }
+    }
+
+    /// Removes messages over quotas and performs the configured action for each client that exceeded them.
+    ///
+    /// Should be called once per frame.
+    pub(crate) fn apply(
+        &mut self,
+        event_name: &'static str,
+        messages: &mut Vec<(ClientId, Bytes)>,
+        elapsed: Duration,
+        tick: RepliconTick,
+        exceeded: &mut Vec<EventLimitExceeded>,
+    ) {
+        for (client_id, dropped) in self.drop_exceeding(messages, elapsed, tick) {
+            match self.limit.action {
+                LimitAction::Drop => (),
+                LimitAction::DropAndLog => warn!(
+                    "dropping {dropped} events `{event_name}` from {client_id:?} due to exceeded limit"
+                ),
+                LimitAction::DropAndEmit => exceeded.push(EventLimitExceeded {
+                    client_id,
+                    event_name,
+                    dropped,
+                }),
+            }
+        }
+    }
+
+    /// Removes messages over quotas and returns the number of dropped messages for each client.
+    fn drop_exceeding(
+        &mut self,
+        messages: &mut Vec<(ClientId, Bytes)>,
+        elapsed: Duration,
+        tick: RepliconTick,
+    ) -> HashMap<ClientId, usize> {
+        if self.tick != Some(tick) {
+            self.tick = Some(tick);
+            for quota in self.clients.values_mut() {
+                quota.tick_bytes = 0;
+            }
+        }
+
+        let mut dropped = HashMap::<ClientId, usize>::default();
+        messages.retain(|(client_id, message)| {
+            let quota = self
+                .clients
+                .entry(*client_id)
+                .or_insert_with(|| ClientQuota::new(self.limit, elapsed));
+            if quota.consume(self.limit, message.len(), elapsed) {
+                true
+            } else {
+                *dropped.entry(*client_id).or_default() += 1;
+                false
+            }
+        });
+
+        dropped
+    }
+
+    /// Removes quotas for clients that aren't connected.
+    pub(crate) fn retain_clients(&mut self, f: impl Fn(ClientId) -> bool) {
+        self.clients.retain(|&client_id, _| f(client_id));
+    }
+}
+
+/// Remaining quotas of a single client.
+struct ClientQuota {
+    /// Events available for sending, refilled over time.
+    tokens: f32,
+    last_refill: Duration,
+    tick_bytes: usize,
+}
+
+impl ClientQuota {
+    fn new(limit: EventLimit, elapsed: Duration) -> Self {
+        Self {
+            tokens: limit.events_per_second.unwrap_or_default() as f32,
+            last_refill: elapsed,
+            tick_bytes: 0,
+        }
+    }
+
+    /// Returns `true` and consumes quotas if a message of `size` fits.
+    fn consume(&mut self, limit: EventLimit, size: usize, elapsed: Duration) -> bool {
+        if let Some(max_bytes) = limit.bytes_per_tick {
+            if self.tick_bytes + size > max_bytes {
+                return false;
+            }
+        }
+
+        if let Some(rate) = limit.events_per_second {
+            let rate = rate as f32;
+            let delta = elapsed.saturating_sub(self.last_refill).as_secs_f32();
+            self.tokens = (self.tokens + rate * delta).min(rate);
+            self.last_refill = elapsed;
+            if self.tokens < 1.0 {
+                return false;
+            }
+            self.tokens -= 1.0;
+        }
+
+        self.tick_bytes += size;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_per_second() {
+        let mut limiter = EventLimiter::new(EventLimit {
+            events_per_second: Some(2),
+            ..Default::default()
+        });
+
+        let client_id = ClientId::new(1);
+        let mut messages = vec![(client_id, Bytes::new()); 3];
+        let dropped = limiter.drop_exceeding(&mut messages, Duration::ZERO, RepliconTick::new(0));
+        assert_eq!(messages.len(), 2);
+        assert_eq!(dropped[&client_id], 1);
+
+        let mut messages = vec![(client_id, Bytes::new()); 2];
+        let dropped = limiter.drop_exceeding(
+            &mut messages,
+            Duration::from_millis(500),
+            RepliconTick::new(1),
+        );
+        assert_eq!(messages.len(), 1, "only one event should be refilled");
+        assert_eq!(dropped[&client_id], 1);
+    }
+
+    #[test]
+    fn bytes_per_tick() {
+        let mut limiter = EventLimiter::new(EventLimit {
+            bytes_per_tick: Some(4),
+            ..Default::default()
+        });
+
+        let client1 = ClientId::new(1);
+        let client2 = ClientId::new(2);
+        let message = Bytes::from_static(&[0; 3]);
+        let mut messages = vec![
+            (client1, message.clone()),
+            (client1, message.clone()),
+            (client2, message.clone()),
+        ];
+        let dropped = limiter.drop_exceeding(&mut messages, Duration::ZERO, RepliconTick::new(0));
+        assert_eq!(messages.len(), 2, "quotas should be separate for clients");
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[&client1], 1);
+
+        let mut messages = vec![(client1, message.clone())];
+        let dropped = limiter.drop_exceeding(&mut messages, Duration::ZERO, RepliconTick::new(0));
+        assert!(
+            messages.is_empty(),
+            "bytes should be kept within the same tick"
+        );
+        assert_eq!(dropped[&client1], 1);
+
+        let mut messages = vec![(client1, message)];
+        let dropped = limiter.drop_exceeding(&mut messages, Duration::ZERO, RepliconTick::new(1));
+        assert_eq!(messages.len(), 1, "bytes should be reset on tick change");
+        assert!(dropped.is_empty());
+    }
+}
@@ -1,9 +1,14 @@
 mod event_data;
 
-use std::io::Cursor;
+use std::{
+    any,
+    hash::{Hash, Hasher},
+    io::Cursor,
+};
 
 use bevy::{ecs::entity::MapEntities, prelude::*};
 use bincode::{DefaultOptions, Options};
+use bytes::Bytes;
 use ordered_multimap::ListOrderedMultimap;
 use serde::{de::DeserializeOwned, Serialize};
 
@@ -19,6 +24,7 @@ use crate::{
         channels::{RepliconChannel, RepliconChannels},
         common_conditions::*,
         ctx::{ClientReceiveCtx, ServerSendCtx},
+        protocol::StableIds,
         replicon_tick::RepliconTick,
         ClientId,
     },
@@ -130,19 +136,30 @@ impl ServerEventAppExt for App {
             .add_event::<ToClients<E>>()
             .init_resource::<ServerEventQueue<E>>();
 
-        let channel_id = self
-            .world_mut()
-            .resource_mut::<RepliconChannels>()
-            .create_server_channel(channel.into());
-
         self.world_mut()
             .resource_scope(|world, mut event_registry: Mut<ServerEventRegistry>| {
-                event_registry.0.push(ServerEventData::new(
+                let mut channels = world.resource_mut::<RepliconChannels>();
+                let channel = channel.into();
+                let (channel_id, stable_id) = if event_registry.shared_channels.is_empty() {
+                    (channels.create_server_channel(channel), None)
+                } else {
+                    let channel_id = channels.shared_server_channel(channel.kind);
+                    let index = event_registry.events.len();
+                    let stable_id =
+                        event_registry
+                            .stable_ids
+                            .register(any::type_name::<E>(), 0, index);
+                    (channel_id, Some(stable_id))
+                };
+
+                event_registry.events.push(ServerEventData::new(
                     world.components(),
                     channel_id,
+                    stable_id,
                     serialize,
                     deserialize,
                 ));
+                event_registry.received.push(Default::default());
             });
 
         self
@@ -164,7 +181,8 @@ impl Plugin for ServerEventsPlugin {
                     Self::receive
                         .after(ClientPlugin::receive_replication)
                         .in_set(ClientSet::Receive)
-                        .run_if(client_connected),
+                        .run_if(client_connected)
+                        .run_if(protocol_verified),
                 ),
             )
             .add_systems(
@@ -190,7 +208,7 @@ impl ServerEventsPlugin {
                             registry: &registry.read(),
                         };
 
-                        for event_data in &event_registry.0 {
+                        for event_data in &event_registry.events {
                             let server_events = world
                                 .get_resource_by_id(event_data.server_events_id())
                                 .expect("server events shouldn't be removed");
@@ -215,15 +233,23 @@ impl ServerEventsPlugin {
         world.resource_scope(|world, mut client: Mut<RepliconClient>| {
             world.resource_scope(|world, registry: Mut<AppTypeRegistry>| {
                 world.resource_scope(|world, entity_map: Mut<ServerEntityMap>| {
-                    world.resource_scope(|world, event_registry: Mut<ServerEventRegistry>| {
+                    world.resource_scope(|world, mut event_registry: Mut<ServerEventRegistry>| {
                         let init_tick = **world.resource::<ServerInitTick>();
                         let mut ctx = ClientReceiveCtx {
                             registry: &registry.read(),
                             entity_map: &entity_map,
                         };
 
+                        let event_registry = &mut *event_registry;
+                        event_registry.read_messages(&mut client);
+
+                        let mut errors = Vec::new();
                         let world_cell = world.as_unsafe_world_cell();
-                        for event_data in &event_registry.0 {
+                        for (event_data, messages) in event_registry
+                            .events
+                            .iter()
+                            .zip(&mut event_registry.received)
+                        {
                             // SAFETY: both resources mutably borrowed uniquely.
                             let (events, queue) = unsafe {
                                 let events = world_cell
@@ -241,11 +267,13 @@ impl ServerEventsPlugin {
                                     &mut ctx,
                                     events.into_inner(),
                                     queue.into_inner(),
-                                    &mut client,
+                                    messages,
                                     init_tick,
+                                    &mut errors,
                                 )
                             };
                         }
+                        world.send_event_batch(errors);
                     });
                 });
             });
@@ -255,7 +283,7 @@ impl ServerEventsPlugin {
     fn resend_locally(world: &mut World) {
         world.resource_scope(|world, event_registry: Mut<ServerEventRegistry>| {
             let world_cell = world.as_unsafe_world_cell();
-            for event_data in &event_registry.0 {
+            for event_data in &event_registry.events {
                 // SAFETY: both resources mutably borrowed uniquely.
                 let (server_events, events) = unsafe {
                     let server_events = world_cell
@@ -277,7 +305,7 @@ impl ServerEventsPlugin {
 
     fn reset(world: &mut World) {
         world.resource_scope(|world, event_registry: Mut<ServerEventRegistry>| {
-            for event_data in &event_registry.0 {
+            for event_data in &event_registry.events {
                 let queue = world
                     .get_resource_mut_by_id(event_data.queue_id())
                     .expect("event queue shouldn't be removed");
@@ -290,8 +318,73 @@ impl ServerEventsPlugin {
 }
 
 /// Registered server events.
-#[derive(Resource, Default)]
-struct ServerEventRegistry(Vec<ServerEventData>);
+#[derive(Resource)]
+pub(crate) struct ServerEventRegistry {
+    events: Vec<ServerEventData>,
+
+    /// Channels shared by all events.
+    ///
+    /// Empty unless [`IdPolicy::TypeHash`](crate::core::IdPolicy::TypeHash) is used.
+    shared_channels: Vec<u8>,
+
+    /// Stable IDs for [`Self::events`] if [`Self::shared_channels`] are used.
+    stable_ids: StableIds,
+
+    /// Received messages for each element in [`Self::events`].
+    ///
+    /// Reused between frames to avoid allocations.
+    received: Vec<Vec<Bytes>>,
+}
+
+impl ServerEventRegistry {
+    /// Drains received messages and groups them by events.
+    fn read_messages(&mut self, client: &mut RepliconClient) {
+        if self.shared_channels.is_empty() {
+            for (event_data, messages) in self.events.iter().zip(&mut self.received) {
+                messages.extend(client.receive(event_data.channel_id()));
+            }
+        } else {
+            for &channel_id in &self.shared_channels {
+                for message in client.receive(channel_id) {
+                    match self.stable_ids.split_message(&message) {
+                        Some((index, message)) => self.received[index].push(message),
+                        None => debug!("ignoring server event with unknown ID"),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Hashes names of registered events and their channels.
+    ///
+    /// Events registered with stable IDs are ignored because
+    /// unknown events are skipped during receiving.
+    ///
+    /// See also [`ProtocolHash`](crate::core::protocol::ProtocolHash).
+    pub(crate) fn hash_events(&self, hasher: &mut impl Hasher) {
+        if !self.shared_channels.is_empty() {
+            return;
+        }
+
+        self.events.len().hash(hasher);
+        for event_data in &self.events {
+            event_data.type_name().hash(hasher);
+            event_data.channel_id().hash(hasher);
+        }
+    }
+}
+
+impl FromWorld for ServerEventRegistry {
+    fn from_world(world: &mut World) -> Self {
+        let channels = world.resource::<RepliconChannels>();
+        Self {
+            events: Default::default(),
+            shared_channels: channels.shared_server_channels().to_vec(),
+            stable_ids: Default::default(),
+            received: Default::default(),
+        }
+    }
+}
 
 /// Signature of server event serialization functions.
 pub type SerializeFn<E> = fn(&mut ServerSendCtx, &E, &mut Cursor<Vec<u8>>) -> bincode::Result<()>;
@@ -328,18 +421,31 @@ pub fn default_deserialize_mapped<E: Event + DeserializeOwned + MapEntities>(
 }
 
 /// An event that will be send to client(s).
-#[derive(Clone, Copy, Debug, Event)]
+#[derive(Clone, Debug, Event)]
 pub struct ToClients<T> {
     pub mode: SendMode,
     pub event: T,
 }
 
 /// Type of server message sending.
-#[derive(Clone, Copy, Debug)]
+///
+/// For modes with multiple recipients the event is serialized only once and the bytes are shared.
+#[derive(Clone, Debug)]
 pub enum SendMode {
     Broadcast,
     BroadcastExcept(ClientId),
     Direct(ClientId),
+    /// Send to all listed clients.
+    ///
+    /// Disconnected clients are ignored.
+    Multicast(Vec<ClientId>),
+    /// Send to all clients except the listed ones.
+    BroadcastExceptMany(Vec<ClientId>),
+    /// Send to all clients for which the entity is visible.
+    ///
+    /// Visibility is checked with [`ClientVisibility::is_visible`](crate::server::connected_clients::client_visibility::ClientVisibility::is_visible).
+    /// The listen server always receives the event since all entities exist on it.
+    Visible(Entity),
 }
 
 /// Stores all received events from server that arrived earlier then replication message with their tick.
@@ -1,6 +1,6 @@
 use std::{
     any::{self, TypeId},
-    io::Cursor,
+    io::{Cursor, Write},
     mem,
 };
 
@@ -14,7 +14,7 @@ use bytes::Bytes;
 
 use super::{DeserializeFn, SendMode, SerializeFn, ServerEventQueue, ToClients};
 use crate::{
-    client::replicon_client::RepliconClient,
+    client::{MessageKind, ReceiveError},
     core::{
         ctx::{ClientReceiveCtx, ServerSendCtx},
         replicon_tick::RepliconTick,
@@ -45,6 +45,9 @@ pub(super) struct ServerEventData {
     /// Used channel.
     channel_id: u8,
 
+    /// ID written before each message for [`IdPolicy::TypeHash`](crate::core::IdPolicy::TypeHash).
+    stable_id: Option<u32>,
+
     send: SendFn,
     receive: ReceiveFn,
     resend_locally: ResendLocallyFn,
@@ -57,6 +60,7 @@ impl ServerEventData {
     pub(super) fn new<E: Event>(
         components: &Components,
         channel_id: u8,
+        stable_id: Option<u32>,
         serialize: SerializeFn<E>,
         deserialize: DeserializeFn<E>,
     ) -> Self {
@@ -91,6 +95,7 @@ impl ServerEventData {
             server_events_id,
             queue_id,
             channel_id,
+            stable_id,
             send: send::<E>,
             receive: receive::<E>,
             resend_locally: resend_locally::<E>,
@@ -100,6 +105,14 @@ impl ServerEventData {
         }
     }
 
+    pub(super) fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub(super) fn channel_id(&self) -> u8 {
+        self.channel_id
+    }
+
     pub(super) fn events_id(&self) -> ComponentId {
         self.events_id
     }
@@ -128,7 +141,9 @@ impl ServerEventData {
         (self.send)(self, ctx, server_events, server, connected_clients);
     }
 
-    /// Receives an event from the server.
+    /// Receives events from the server by draining `messages`.
+    ///
+    /// Messages that can't be deserialized are written into `errors`.
     ///
     /// # Safety
     ///
@@ -139,10 +154,11 @@ impl ServerEventData {
         ctx: &mut ClientReceiveCtx,
         events: PtrMut,
         queue: PtrMut,
-        client: &mut RepliconClient,
+        messages: &mut Vec<Bytes>,
         init_tick: RepliconTick,
+        errors: &mut Vec<ReceiveError>,
     ) {
-        (self.receive)(self, ctx, events, queue, client, init_tick);
+        (self.receive)(self, ctx, events, queue, messages, init_tick, errors);
     }
 
     /// Drains events [`ToClients<E>`] and re-emits them as `E` if the server is in the list of the event recipients.
@@ -198,6 +214,15 @@ impl ServerEventData {
         (deserialize)(ctx, cursor)
     }
 
+    /// Writes the stable ID if the event was registered with it.
+    fn write_stable_id(&self, writer: impl Write) -> bincode::Result<()> {
+        if let Some(stable_id) = self.stable_id {
+            bincode::serialize_into(writer, &stable_id)?;
+        }
+
+        Ok(())
+    }
+
     fn check_type<C: Event>(&self) {
         debug_assert_eq!(
             self.type_id,
@@ -219,8 +244,9 @@ type ReceiveFn = unsafe fn(
     &mut ClientReceiveCtx,
     PtrMut,
     PtrMut,
-    &mut RepliconClient,
+    &mut Vec<Bytes>,
     RepliconTick,
+    &mut Vec<ReceiveError>,
 );
 
 /// Signature of server event resending functions.
@@ -263,8 +289,9 @@ unsafe fn receive<E: Event>(
     ctx: &mut ClientReceiveCtx,
     events: PtrMut,
     queue: PtrMut,
-    client: &mut RepliconClient,
+    messages: &mut Vec<Bytes>,
     init_tick: RepliconTick,
+    errors: &mut Vec<ReceiveError>,
 ) {
     let events: &mut Events<E> = events.deref_mut();
     let queue: &mut ServerEventQueue<E> = queue.deref_mut();
@@ -277,10 +304,20 @@ unsafe fn receive<E: Event>(
         events.send(event);
     }
 
-    for message in client.receive(event_data.channel_id) {
+    for message in messages.drain(..) {
         let mut cursor = Cursor::new(&*message);
-        let (tick, event) = deserialize_with(ctx, event_data, &mut cursor)
-            .expect("server should send valid events");
+        let (tick, event) = match deserialize_with(ctx, event_data, &mut cursor) {
+            Ok(result) => result,
+            Err(error) => {
+                errors.push(ReceiveError {
+                    message: MessageKind::Event {
+                        event_name: any::type_name::<E>(),
+                    },
+                    error,
+                });
+                continue;
+            }
+        };
 
         if tick <= init_tick {
             trace!("applying event `{}` with `{tick:?}`", any::type_name::<E>());
@@ -315,6 +352,19 @@ unsafe fn resend_locally<E: Event>(server_events: PtrMut, events: PtrMut) {
                     events.send(event);
                 }
             }
+            SendMode::Multicast(client_ids) => {
+                if client_ids.contains(&ClientId::SERVER) {
+                    events.send(event);
+                }
+            }
+            SendMode::BroadcastExceptMany(client_ids) => {
+                if !client_ids.contains(&ClientId::SERVER) {
+                    events.send(event);
+                }
+            }
+            SendMode::Visible(_) => {
+                events.send(event);
+            }
         }
     }
 }
@@ -348,42 +398,118 @@ unsafe fn send_with<E: Event>(
     server: &mut RepliconServer,
     connected_clients: &ConnectedClients,
 ) -> bincode::Result<()> {
-    match *mode {
+    match mode {
         SendMode::Broadcast => {
-            let mut previous_message = None;
-            for client in connected_clients.iter() {
-                let message = serialize_with(event_data, ctx, event, client, previous_message)?;
-                server.send(client.id(), event_data.channel_id, message.bytes.clone());
-                previous_message = Some(message);
-            }
+            send_to_clients(event_data, ctx, event, server, connected_clients.iter())?;
         }
-        SendMode::BroadcastExcept(client_id) => {
-            let mut previous_message = None;
-            for client in connected_clients.iter() {
-                if client.id() == client_id {
-                    continue;
-                }
-                let message = serialize_with(event_data, ctx, event, client, previous_message)?;
-                server.send(client.id(), event_data.channel_id, message.bytes.clone());
-                previous_message = Some(message);
-            }
+        &SendMode::BroadcastExcept(client_id) => {
+            send_to_clients(
+                event_data,
+                ctx,
+                event,
+                server,
+                connected_clients
+                    .iter()
+                    .filter(|client| client.id() != client_id),
+            )?;
         }
-        SendMode::Direct(client_id) => {
+        &SendMode::Direct(client_id) => {
             if client_id != ClientId::SERVER {
                 if let Some(client) = connected_clients.get_client(client_id) {
                     let message = serialize_with(event_data, ctx, event, client, None)?;
-                    server.send(client.id(), event_data.channel_id, message.bytes);
+                    send_message(event_data, server, client.id(), message.bytes);
                 }
             }
         }
+        SendMode::Multicast(client_ids) => {
+            send_to_clients(
+                event_data,
+                ctx,
+                event,
+                server,
+                connected_clients
+                    .iter()
+                    .filter(|client| client_ids.contains(&client.id())),
+            )?;
+        }
+        SendMode::BroadcastExceptMany(client_ids) => {
+            send_to_clients(
+                event_data,
+                ctx,
+                event,
+                server,
+                connected_clients
+                    .iter()
+                    .filter(|client| !client_ids.contains(&client.id())),
+            )?;
+        }
+        &SendMode::Visible(entity) => {
+            send_to_clients(
+                event_data,
+                ctx,
+                event,
+                server,
+                connected_clients
+                    .iter()
+                    .filter(|client| client.visibility().is_visible(entity)),
+            )?;
+        }
     }
 
     Ok(())
 }
 
+/// Sends event `E` to each client from the iterator.
+///
+/// The event is serialized only for the first client, its bytes are reused for the rest.
+///
+/// # Safety
+///
+/// The caller must ensure that `event_data` was created for `E`.
+unsafe fn send_to_clients<'a, E: Event>(
+    event_data: &ServerEventData,
+    ctx: &mut ServerSendCtx,
+    event: &E,
+    server: &mut RepliconServer,
+    clients: impl Iterator<Item = &'a ConnectedClient>,
+) -> bincode::Result<()> {
+    let mut previous_message = None;
+    for client in clients {
+        let message = serialize_with(event_data, ctx, event, client, previous_message)?;
+        send_message(event_data, server, client.id(), message.bytes.clone());
+        previous_message = Some(message);
+    }
+
+    Ok(())
+}
+
+/// Sends a serialized event if it fits into the max payload size of the client's channel.
+///
+/// Oversized events are dropped since the messaging backend can't deliver them.
+/// If the backend didn't report the size, the event is always sent.
+fn send_message(
+    event_data: &ServerEventData,
+    server: &mut RepliconServer,
+    client_id: ClientId,
+    message: Bytes,
+) {
+    if let Some(max_size) = server.max_payload_size(client_id, event_data.channel_id) {
+        if message.len() > max_size {
+            error!(
+                "dropping event `{}` of {} bytes for {client_id:?} because it exceeds max payload size of {max_size} bytes",
+                event_data.type_name,
+                message.len()
+            );
+            return;
+        }
+    }
+
+    server.send(client_id, event_data.channel_id, message);
+}
+
 /// Helper for serializing a server event.
 ///
-/// Will prepend the client's change tick to the injected message.
+/// Will prepend the stable ID (if any) and the client's change tick to the injected message.
 /// Optimized to avoid reallocations when consecutive clients have the same change tick.
 ///
 /// # Safety
@@ -401,25 +527,27 @@ unsafe fn serialize_with<E: Event>(
             return Ok(previous_message);
         }
 
-        let tick_size = DefaultOptions::new().serialized_size(&client.init_tick())? as usize;
-        let mut bytes = Vec::with_capacity(tick_size + previous_message.event_bytes().len());
+        let mut bytes = Vec::with_capacity(previous_message.bytes.len());
+        event_data.write_stable_id(&mut bytes)?;
         DefaultOptions::new().serialize_into(&mut bytes, &client.init_tick())?;
+        let header_size = bytes.len();
         bytes.extend_from_slice(previous_message.event_bytes());
         let message = SerializedMessage {
             tick: client.init_tick(),
-            tick_size,
+            header_size,
             bytes: bytes.into(),
         };
 
         Ok(message)
     } else {
         let mut cursor = Cursor::new(Vec::new());
+        event_data.write_stable_id(&mut cursor)?;
         DefaultOptions::new().serialize_into(&mut cursor, &client.init_tick())?;
-        let tick_size = cursor.get_ref().len();
+        let header_size = cursor.get_ref().len();
         event_data.serialize(ctx, event, &mut cursor)?;
         let message = SerializedMessage {
             tick: client.init_tick(),
-            tick_size,
+            header_size,
             bytes: cursor.into_inner().into(),
         };
 
@@ -446,12 +574,12 @@ unsafe fn deserialize_with<E: Event>(
 /// Cached message for use in [`serialize_with`].
 struct SerializedMessage {
     tick: RepliconTick,
-    tick_size: usize,
+    header_size: usize,
     bytes: Bytes,
 }
 
 impl SerializedMessage {
     fn event_bytes(&self) -> &[u8] {
-        &self.bytes[self.tick_size..]
+        &self.bytes[self.header_size..]
     }
 }
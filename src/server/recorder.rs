@@ -0,0 +1,281 @@
+use std::{mem, time::Duration};
+
+use bevy::{ecs::system::SystemChangeTick, prelude::*};
+
+use super::{
+    connected_clients::{ClientBuffers, ConnectedClients},
+    replicon_server::RepliconServer,
+    ServerSet,
+};
+use crate::core::{
+    channels::ReplicationChannel,
+    common_conditions::server_running,
+    protocol::ProtocolHash,
+    recording::{RecordedMessage, Recording},
+    replication_registry::ReplicationRegistry,
+    ClientId,
+};
+
+/// Records the replication stream sent to a single client.
+///
+/// Recording is controlled via [`ReplicationRecorder`].
+/// The result can be played on client with [`ReplayPlugin`](crate::client::replay::ReplayPlugin).
+///
+/// Recording is refused if any replication rule uses
+/// [delta compression](crate::core::replication_registry::rule_fns::RuleFns::with_delta_compression)
+/// because diffs reference baselines that aren't available after seeking.
+pub struct RecorderPlugin {
+    /// How often to record a full snapshot of the world for seeking.
+    ///
+    /// Each keyframe is an init message for a virtual client with [`ReplicationRecorder::KEYFRAME_ID`]
+    /// that connects and disconnects within a single tick.
+    /// If [`None`], keyframes aren't recorded and seeking always replays from the beginning.
+    pub keyframe_interval: Option<Duration>,
+}
+
+impl Default for RecorderPlugin {
+    fn default() -> Self {
+        Self {
+            keyframe_interval: Some(Duration::from_secs(10)),
+        }
+    }
+}
+
+impl Plugin for RecorderPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ReplicationRecorder {
+            keyframe_interval: self.keyframe_interval,
+            target: None,
+            recording: Recording::new(ProtocolHash::new(0)),
+            start_time: None,
+            last_keyframe: None,
+        })
+        .add_systems(
+            PostUpdate,
+            Self::record
+                .after(ServerSet::Send)
+                .before(ServerSet::SendPackets)
+                .run_if(server_running),
+        );
+    }
+}
+
+impl RecorderPlugin {
+    fn record(
+        change_tick: SystemChangeTick,
+        mut recorder: ResMut<ReplicationRecorder>,
+        mut server: ResMut<RepliconServer>,
+        mut connected_clients: ResMut<ConnectedClients>,
+        mut client_buffers: ResMut<ClientBuffers>,
+        protocol_hash: Res<ProtocolHash>,
+        registry: Res<ReplicationRegistry>,
+        time: Res<Time>,
+    ) {
+        if recorder.target.is_some()
+            && recorder.start_time.is_none()
+            && registry.has_delta_compression()
+        {
+            error!(
+                "refusing to record because some replication rules use delta compression, \
+                which can't be restored after seeking"
+            );
+            recorder.target = None;
+        }
+
+        let observer = recorder.target == Some(RecordTarget::Observer);
+        sync_virtual_client(
+            &mut server,
+            &mut connected_clients,
+            &mut client_buffers,
+            ReplicationRecorder::OBSERVER_ID,
+            observer,
+        );
+
+        let Some(target) = recorder.target else {
+            sync_virtual_client(
+                &mut server,
+                &mut connected_clients,
+                &mut client_buffers,
+                ReplicationRecorder::KEYFRAME_ID,
+                false,
+            );
+            return;
+        };
+
+        let recorder = &mut *recorder;
+        let start_time = *recorder.start_time.get_or_insert_with(|| {
+            recorder.recording = Recording::new(*protocol_hash);
+            time.elapsed()
+        });
+        let timestamp = time.elapsed() - start_time;
+
+        let target_id = target.client_id();
+        let mut keyframe = None;
+        server.retain_sent(|(client_id, channel_id, message)| {
+            if *client_id == target_id {
+                recorder.recording.push(RecordedMessage {
+                    timestamp,
+                    channel_id: *channel_id,
+                    keyframe: false,
+                    message: message.clone(),
+                });
+                !observer
+            } else if *client_id == ReplicationRecorder::KEYFRAME_ID {
+                if *channel_id == ReplicationChannel::Init as u8 {
+                    keyframe = Some(message.clone());
+                }
+                false
+            } else {
+                true
+            }
+        });
+
+        if observer {
+            // Virtual observer can't send acknowledgments, so confirm all updates right away.
+            connected_clients
+                .client_mut(ReplicationRecorder::OBSERVER_ID)
+                .acknowledge_all(&mut client_buffers, change_tick.this_run());
+        }
+
+        if let Some(message) = keyframe {
+            trace!("recording keyframe at {timestamp:?}");
+            recorder.recording.push(RecordedMessage {
+                timestamp,
+                channel_id: ReplicationChannel::Init as u8,
+                keyframe: true,
+                message,
+            });
+            recorder.last_keyframe = Some(timestamp);
+            sync_virtual_client(
+                &mut server,
+                &mut connected_clients,
+                &mut client_buffers,
+                ReplicationRecorder::KEYFRAME_ID,
+                false,
+            );
+        } else if let Some(interval) = recorder.keyframe_interval {
+            let keyframe_due = match recorder.last_keyframe {
+                Some(last_keyframe) => timestamp >= last_keyframe + interval,
+                None => true,
+            };
+            if keyframe_due {
+                sync_virtual_client(
+                    &mut server,
+                    &mut connected_clients,
+                    &mut client_buffers,
+                    ReplicationRecorder::KEYFRAME_ID,
+                    true,
+                );
+            }
+        }
+    }
+}
+
+/// Adds or removes a virtual client from connected clients.
+///
+/// Virtual clients don't emit [`ServerEvent`](super::ServerEvent)s.
+fn sync_virtual_client(
+    server: &mut RepliconServer,
+    connected_clients: &mut ConnectedClients,
+    client_buffers: &mut ClientBuffers,
+    client_id: ClientId,
+    connected: bool,
+) {
+    match (connected_clients.get_client(client_id).is_some(), connected) {
+        (false, true) => {
+            connected_clients.add(client_buffers, client_id, Duration::ZERO);
+            let client = connected_clients.client_mut(client_id);
+            // Virtual clients don't perform the handshake.
+            client.verify_protocol();
+            // Keyframes and observer recordings should contain the whole visible world in a single message.
+            // Without a budget, init messages aren't split even if the max payload size is reported.
+            client.set_initial_sync_budget(None);
+        }
+        (true, false) => {
+            connected_clients.remove(client_buffers, client_id);
+            server.remove_client(client_id);
+        }
+        _ => (),
+    }
+}
+
+/// Controls recording of replication messages.
+///
+/// Available only with [`RecorderPlugin`].
+#[derive(Resource)]
+pub struct ReplicationRecorder {
+    keyframe_interval: Option<Duration>,
+    target: Option<RecordTarget>,
+    recording: Recording,
+    start_time: Option<Duration>,
+    last_keyframe: Option<Duration>,
+}
+
+impl ReplicationRecorder {
+    /// ID of the virtual client used for [`RecordTarget::Observer`].
+    pub const OBSERVER_ID: ClientId = ClientId::new(u64::MAX);
+
+    /// ID of the virtual client used to record keyframes.
+    pub const KEYFRAME_ID: ClientId = ClientId::new(u64::MAX - 1);
+
+    /// Starts a new recording.
+    ///
+    /// Messages sent to the target will be recorded starting from the next [`ServerSet::Send`].
+    /// Discards the previous recording if it wasn't stopped.
+    ///
+    /// If any replication rule uses delta compression, the recording will be stopped
+    /// with an error instead. See [`RecorderPlugin`] for details.
+    pub fn start(&mut self, target: RecordTarget) {
+        debug!("starting recording for {target:?}");
+        self.target = Some(target);
+        self.start_time = None;
+        self.last_keyframe = None;
+    }
+
+    /// Stops the recording and returns it.
+    ///
+    /// Virtual clients will be removed on the next [`ServerSet::Send`].
+    pub fn stop(&mut self) -> Recording {
+        debug!("stopping recording");
+        self.target = None;
+        self.start_time = None;
+        let protocol_hash = self.recording.protocol_hash();
+        mem::replace(&mut self.recording, Recording::new(protocol_hash))
+    }
+
+    /// Returns `true` if recording is in progress.
+    pub fn is_recording(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// Returns the current recording.
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+}
+
+/// Client whose messages are recorded by [`ReplicationRecorder`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecordTarget {
+    /// Record messages sent to a connected client.
+    ///
+    /// Messages are still delivered to the client.
+    Client(ClientId),
+
+    /// Record messages sent to a virtual client with [`ReplicationRecorder::OBSERVER_ID`].
+    ///
+    /// The observer is added to [`ConnectedClients`] without emitting [`ServerEvent`](super::ServerEvent)s
+    /// and sees everything allowed by [`VisibilityPolicy`](super::VisibilityPolicy).
+    /// With [`VisibilityPolicy::Whitelist`](super::VisibilityPolicy::Whitelist) entities need to be made
+    /// visible for it manually.
+    Observer,
+}
+
+impl RecordTarget {
+    fn client_id(self) -> ClientId {
+        match self {
+            RecordTarget::Client(client_id) => client_id,
+            RecordTarget::Observer => ReplicationRecorder::OBSERVER_ID,
+        }
+    }
+}
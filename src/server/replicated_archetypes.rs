@@ -10,7 +10,11 @@ use bevy::{
     utils::tracing::enabled,
 };
 
-use crate::core::{replication_registry::FnsId, replication_rules::ReplicationRules, Replicated};
+use crate::core::{
+    replication_registry::FnsId,
+    replication_rules::{ReplicationRules, SendRate},
+    Replicated,
+};
 
 /// Cached information about all replicated archetypes.
 #[derive(Deref)]
@@ -84,6 +88,8 @@ impl ReplicatedArchetypes {
                         component_id: fns_info.component_id(),
                         storage_type,
                         fns_id: fns_info.fns_id(),
+                        send_rate: rule.send_rate,
+                        reliable: rule.reliable,
                     });
                 }
             }
@@ -125,6 +131,8 @@ pub(super) struct ReplicatedComponent {
     pub(super) component_id: ComponentId,
     pub(super) storage_type: StorageType,
     pub(super) fns_id: FnsId,
+    pub(super) send_rate: SendRate,
+    pub(super) reliable: bool,
 }
 
 #[cfg(test)]
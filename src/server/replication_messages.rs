@@ -6,22 +6,33 @@ use std::{
 
 use bevy::{ecs::component::Tick, prelude::*, ptr::Ptr};
 use bincode::{DefaultOptions, Options};
-use bytes::Bytes;
 use varint_rs::VarintWriter;
 
 use super::{
     client_entity_map::ClientMapping,
-    connected_clients::{ClientBuffers, ConnectedClients},
+    connected_clients::{client_priority::ClientPriority, ClientBuffers, ConnectedClients},
     replicon_server::RepliconServer,
     ConnectedClient,
 };
 use crate::core::{
     channels::ReplicationChannel,
+    compression::{MessageCompressor, COMPRESSED_FLAG, COMPRESSED_UPDATE_BIT, INITIAL_SYNC_FLAG},
     ctx::SerializeCtx,
-    replication_registry::{component_fns::ComponentFns, rule_fns::UntypedRuleFns, FnsId},
+    delta_compression,
+    replication_registry::{FnsId, ReplicationRegistry},
+    replication_resources::ReplicationResources,
     replicon_tick::RepliconTick,
 };
 
+/// Size of the init message header with flags and server tick.
+const INIT_HEADER_SIZE: usize = mem::size_of::<u8>() + mem::size_of::<RepliconTick>();
+
+/// Max size of a length written by [`insert_len`].
+const MAX_LEN_SIZE: usize = mem::size_of::<u64>() + 1;
+
+/// Size used to split update messages for clients without a reported max payload size.
+const DEFAULT_UPDATE_SPLIT_SIZE: usize = 1200;
+
 /// Accumulates replication messages and sends them to clients.
 ///
 /// Messages are serialized and deserialized manually because using an intermediate structure
@@ -32,26 +43,34 @@ use crate::core::{
 pub(crate) struct ReplicationMessages {
     connected_clients: ConnectedClients,
     data: Vec<(InitMessage, UpdateMessage)>,
+    compressor: MessageCompressor,
 }
 
 impl ReplicationMessages {
     /// Initializes messages for each client.
     ///
+    /// Max sizes of init messages are obtained from `server`.
     /// Reuses already allocated messages.
     /// Creates new messages if the number of clients is bigger then the number of allocated messages.
     /// If there are more messages than the number of clients, then the extra messages remain untouched
     /// and iteration methods will not include them.
-    pub(super) fn prepare(&mut self, connected_clients: ConnectedClients) {
+    pub(super) fn prepare(&mut self, connected_clients: ConnectedClients, server: &RepliconServer) {
         self.data
             .reserve(connected_clients.len().saturating_sub(self.data.len()));
 
-        for index in 0..connected_clients.len() {
+        for (index, client) in connected_clients.iter().enumerate() {
             if let Some((init_message, update_message)) = self.data.get_mut(index) {
                 init_message.reset();
                 update_message.reset();
             } else {
                 self.data.push(Default::default());
             }
+
+            let (init_message, _) = &mut self.data[index];
+            // Reserve space for the length of the array that is currently written.
+            init_message.max_size = server
+                .max_payload_size(client.id(), ReplicationChannel::Init)
+                .map(|max_size| max_size.saturating_sub(INIT_HEADER_SIZE + MAX_LEN_SIZE));
         }
 
         self.connected_clients = connected_clients;
@@ -63,16 +82,31 @@ impl ReplicationMessages {
     }
 
     /// Same as [`Self::iter_mut`], but also includes [`ConnectedClient`].
+    ///
+    /// Skips clients that haven't verified the protocol yet.
     pub(super) fn iter_mut_with_clients(
         &mut self,
     ) -> impl Iterator<Item = (&mut InitMessage, &mut UpdateMessage, &mut ConnectedClient)> {
         self.data
             .iter_mut()
             .zip(self.connected_clients.iter_mut())
+            .filter(|(_, client)| client.is_protocol_verified())
             .map(|((init_message, update_message), client)| (init_message, update_message, client))
     }
 
-    /// Sends cached messages to clients specified in the last [`Self::prepare`] call.
+    /// Returns iterator over clients that haven't verified the protocol yet.
+    ///
+    /// Messages for these clients are not written or sent.
+    pub(super) fn iter_unverified_clients(&mut self) -> impl Iterator<Item = &mut ConnectedClient> {
+        self.connected_clients
+            .iter_mut()
+            .filter(|client| !client.is_protocol_verified())
+    }
+
+    /// Sends cached messages to verified clients specified in the last [`Self::prepare`] call.
+    ///
+    /// Message payloads with at least `compression_threshold` bytes are compressed.
+    /// Clients should be taken back with [`Self::take_clients`].
     ///
     /// The change tick of each client with an init message is updated to equal the latest replicon tick.
     /// messages were sent to clients. If only update messages were sent (or no messages at all) then
@@ -83,26 +117,53 @@ impl ReplicationMessages {
         client_buffers: &mut ClientBuffers,
         server_tick: RepliconTick,
         tick: Tick,
+        compression_threshold: Option<usize>,
         timestamp: Duration,
-    ) -> bincode::Result<ConnectedClients> {
-        for ((init_message, update_message), client) in
-            self.data.iter_mut().zip(self.connected_clients.iter_mut())
+    ) -> bincode::Result<()> {
+        for ((init_message, update_message), client) in self
+            .data
+            .iter_mut()
+            .zip(self.connected_clients.iter_mut())
+            .filter(|(_, client)| client.is_protocol_verified())
         {
-            init_message.send(server, client, server_tick)?;
-            update_message.send(server, client_buffers, client, server_tick, tick, timestamp)?;
+            let init_size = init_message.send(
+                server,
+                &mut self.compressor,
+                compression_threshold,
+                client,
+                server_tick,
+            )?;
+            if let Some(budget) = client.bandwidth_budget() {
+                update_message
+                    .apply_budget(client.priority_mut(), budget.saturating_sub(init_size));
+            }
+            update_message.send(
+                server,
+                &mut self.compressor,
+                compression_threshold,
+                client_buffers,
+                client,
+                server_tick,
+                tick,
+                timestamp,
+            )?;
             client.visibility_mut().update();
+            client.component_visibility_mut().update();
         }
 
-        let connected_clients = mem::take(&mut self.connected_clients);
+        Ok(())
+    }
 
-        Ok(connected_clients)
+    /// Returns clients specified in the last [`Self::prepare`] call.
+    pub(super) fn take_clients(&mut self) -> ConnectedClients {
+        mem::take(&mut self.connected_clients)
     }
 }
 
 /// A reusable message with replicated data.
 ///
 /// Contains tick and mappings, insertions, removals and despawns that
-/// happened on this tick.
+/// happened on this tick, followed by resource removals and changes.
 /// Sent over [`ReplicationChannel::Init`] channel.
 ///
 /// See also [Limits](../index.html#limits)
@@ -111,7 +172,7 @@ pub(super) struct InitMessage {
     cursor: Cursor<Vec<u8>>,
 
     /// Length of the array that updated automatically after writing data.
-    array_len: u16,
+    array_len: usize,
 
     /// Position of the array from last call of [`Self::start_array`].
     array_pos: u64,
@@ -123,13 +184,20 @@ pub(super) struct InitMessage {
     data_entity: Entity,
 
     /// Size in bytes of the component data stored for the currently-being-written entity.
-    entity_data_size: u16,
+    entity_data_size: usize,
 
     /// Position of entity from last call of [`Self::start_entity_data`].
     entity_data_pos: u64,
 
-    /// Position of entity data length from last call of [`Self::write_data_entity`].
+    /// Position of entity data from last call of [`Self::write_data_entity`].
+    ///
+    /// Length will be inserted at this position after writing the data.
     entity_data_size_pos: u64,
+
+    /// Max size of the message payload reported by the messaging backend for the client.
+    ///
+    /// New entities that don't fit are deferred to the next ticks if initial sync budget is set.
+    max_size: Option<usize>,
 }
 
 impl InitMessage {
@@ -141,13 +209,34 @@ impl InitMessage {
         self.trailing_empty_arrays = 0;
     }
 
+    /// Returns size in bytes of all written data.
+    pub(super) fn size(&self) -> usize {
+        self.cursor.position() as usize
+    }
+
     /// Returns size in bytes of the current entity data.
     ///
     /// See also [`Self::start_entity_data`] and [`Self::end_entity_data`].
-    pub(super) fn entity_data_size(&self) -> u16 {
+    pub(super) fn entity_data_size(&self) -> usize {
         self.entity_data_size
     }
 
+    /// Returns the max number of bytes that the message should have.
+    ///
+    /// Obtained from [`RepliconServer::max_payload_size`] excluding the header.
+    pub(super) fn max_size(&self) -> Option<usize> {
+        self.max_size
+    }
+
+    /// Removes the data written for the current entity.
+    ///
+    /// Used to defer the entity to the next tick.
+    /// See also [`Self::start_entity_data`].
+    pub(super) fn discard_entity_data(&mut self) {
+        self.cursor.set_position(self.entity_data_pos);
+        self.entity_data_size = 0;
+    }
+
     /// Starts writing array by remembering its position to write length after.
     ///
     /// Arrays can contain entity data or despawns inside.
@@ -156,29 +245,21 @@ impl InitMessage {
         debug_assert_eq!(self.array_len, 0);
 
         self.array_pos = self.cursor.position();
-        self.cursor
-            .set_position(self.array_pos + mem::size_of_val(&self.array_len) as u64);
     }
 
-    /// Ends writing array by writing its length into the last remembered position.
+    /// Ends writing array by inserting its length into the last remembered position.
     ///
     /// See also [`Self::start_array`].
     pub(super) fn end_array(&mut self) -> bincode::Result<()> {
         if self.array_len != 0 {
-            let previous_pos = self.cursor.position();
-            self.cursor.set_position(self.array_pos);
-
-            bincode::serialize_into(&mut self.cursor, &self.array_len)?;
-
-            self.cursor.set_position(previous_pos);
-            self.array_len = 0;
             self.trailing_empty_arrays = 0;
         } else {
             self.trailing_empty_arrays += 1;
-            self.cursor.set_position(self.array_pos);
-            bincode::serialize_into(&mut self.cursor, &self.array_len)?;
         }
 
+        insert_len(&mut self.cursor, self.array_pos, self.array_len)?;
+        self.array_len = 0;
+
         Ok(())
     }
 
@@ -189,10 +270,7 @@ impl InitMessage {
     pub(super) fn write_client_mapping(&mut self, mapping: &ClientMapping) -> bincode::Result<()> {
         serialize_entity(&mut self.cursor, mapping.server_entity)?;
         serialize_entity(&mut self.cursor, mapping.client_entity)?;
-        self.array_len = self
-            .array_len
-            .checked_add(1)
-            .ok_or(bincode::ErrorKind::SizeLimit)?;
+        self.array_len += 1;
 
         Ok(())
     }
@@ -211,10 +289,45 @@ impl InitMessage {
             serialize_entity(cursor, entity)
         })?;
 
-        self.array_len = self
-            .array_len
-            .checked_add(1)
-            .ok_or(bincode::ErrorKind::SizeLimit)?;
+        self.array_len += 1;
+
+        Ok(())
+    }
+
+    /// Serializes a replicated resource with its ID as an array element.
+    ///
+    /// Reuses previously shared bytes if they exist, or updates them.
+    /// Should be called only inside an array and increases its length by 1.
+    /// See also [`Self::start_array`].
+    pub(super) fn write_resource<'a>(
+        &'a mut self,
+        shared_bytes: &mut Option<&'a [u8]>,
+        resources: &ReplicationResources,
+        ctx: &SerializeCtx,
+        world: &World,
+        index: usize,
+    ) -> bincode::Result<()> {
+        write_with(shared_bytes, &mut self.cursor, |cursor| {
+            resources.write_id(&mut *cursor, index)?;
+            resources.get(index).serialize(ctx, world, cursor)
+        })?;
+
+        self.array_len += 1;
+
+        Ok(())
+    }
+
+    /// Serializes ID of a removed resource as an array element.
+    ///
+    /// Should be called only inside an array and increases its length by 1.
+    /// See also [`Self::start_array`].
+    pub(super) fn write_resource_id(
+        &mut self,
+        resources: &ReplicationResources,
+        index: usize,
+    ) -> bincode::Result<()> {
+        resources.write_id(&mut self.cursor, index)?;
+        self.array_len += 1;
 
         Ok(())
     }
@@ -232,20 +345,19 @@ impl InitMessage {
         self.entity_data_pos = self.cursor.position();
     }
 
-    /// Writes entity for the current data and remembers the position after it to write length later.
+    /// Writes entity for the current data and remembers the position after it to insert length later.
     ///
     /// Should be called only after first data write.
+    /// Overwrites data left after a failed write.
     fn write_data_entity(&mut self) -> bincode::Result<()> {
+        self.cursor.set_position(self.entity_data_pos);
         serialize_entity(&mut self.cursor, self.data_entity)?;
         self.entity_data_size_pos = self.cursor.position();
-        self.cursor.set_position(
-            self.entity_data_size_pos + mem::size_of_val(&self.entity_data_size) as u64,
-        );
 
         Ok(())
     }
 
-    /// Ends writing entity data by writing its length into the last remembered position.
+    /// Ends writing entity data by inserting its length into the last remembered position.
     ///
     /// If the entity data is empty, nothing will be written unless `save_empty` is set to true.
     /// Should be called only inside an array and increases its length by 1.
@@ -261,17 +373,13 @@ impl InitMessage {
             self.write_data_entity()?;
         }
 
-        let previous_pos = self.cursor.position();
-        self.cursor.set_position(self.entity_data_size_pos);
-
-        bincode::serialize_into(&mut self.cursor, &self.entity_data_size)?;
-
-        self.cursor.set_position(previous_pos);
+        insert_len(
+            &mut self.cursor,
+            self.entity_data_size_pos,
+            self.entity_data_size,
+        )?;
         self.entity_data_size = 0;
-        self.array_len = self
-            .array_len
-            .checked_add(1)
-            .ok_or(bincode::ErrorKind::SizeLimit)?;
+        self.array_len += 1;
 
         Ok(())
     }
@@ -284,8 +392,7 @@ impl InitMessage {
     pub(super) fn write_component<'a>(
         &'a mut self,
         shared_bytes: &mut Option<&'a [u8]>,
-        rule_fns: &UntypedRuleFns,
-        component_fns: &ComponentFns,
+        registry: &ReplicationRegistry,
         ctx: &SerializeCtx,
         fns_id: FnsId,
         ptr: Ptr,
@@ -294,16 +401,42 @@ impl InitMessage {
             self.write_data_entity()?;
         }
 
+        let (component_fns, rule_fns) = registry.get(fns_id);
         let size = write_with(shared_bytes, &mut self.cursor, |cursor| {
-            DefaultOptions::new().serialize_into(&mut *cursor, &fns_id)?;
+            registry.write_fns_id(&mut *cursor, fns_id)?;
             // SAFETY: `component_fns`, `ptr` and `rule_fns` were created for the same component type.
             unsafe { component_fns.serialize(ctx, rule_fns, ptr, cursor) }
         })?;
 
-        self.entity_data_size = self
-            .entity_data_size
-            .checked_add(size)
-            .ok_or(bincode::ErrorKind::SizeLimit)?;
+        self.entity_data_size += size;
+
+        Ok(())
+    }
+
+    /// Writes serialized component bytes and their replication functions ID as an element of entity data.
+    ///
+    /// Used for components with delta compression.
+    /// Bytes will be written as a diff if `baseline` is present and the diff is smaller.
+    /// Should be called only inside an entity data and increases its size.
+    /// See also [`Self::start_entity_data`] and [`delta_compression::write_bytes`].
+    pub(super) fn write_delta_component(
+        &mut self,
+        registry: &ReplicationRegistry,
+        fns_id: FnsId,
+        bytes: &[u8],
+        baseline: Option<(RepliconTick, &[u8])>,
+        diff_buffer: &mut Vec<u8>,
+    ) -> bincode::Result<()> {
+        if self.entity_data_size == 0 {
+            self.write_data_entity()?;
+        }
+
+        let previous_pos = self.cursor.position();
+        registry.write_fns_id(&mut self.cursor, fns_id)?;
+        delta_compression::write_bytes(&mut self.cursor, bytes, baseline, diff_buffer)?;
+
+        let size = (self.cursor.position() - previous_pos) as usize;
+        self.entity_data_size += size;
 
         Ok(())
     }
@@ -312,19 +445,20 @@ impl InitMessage {
     ///
     /// Should be called only inside an entity data and increases its size.
     /// See also [`Self::start_entity_data`].
-    pub(super) fn write_fns_id(&mut self, fns_id: FnsId) -> bincode::Result<()> {
+    pub(super) fn write_fns_id(
+        &mut self,
+        registry: &ReplicationRegistry,
+        fns_id: FnsId,
+    ) -> bincode::Result<()> {
         if self.entity_data_size == 0 {
             self.write_data_entity()?;
         }
 
         let previous_pos = self.cursor.position();
-        DefaultOptions::new().serialize_into(&mut self.cursor, &fns_id)?;
+        registry.write_fns_id(&mut self.cursor, fns_id)?;
 
         let id_size = self.cursor.position() - previous_pos;
-        self.entity_data_size = self
-            .entity_data_size
-            .checked_add(id_size as u16)
-            .ok_or(bincode::ErrorKind::SizeLimit)?;
+        self.entity_data_size += id_size as usize;
 
         Ok(())
     }
@@ -343,14 +477,10 @@ impl InitMessage {
             }
 
             let slice = update_message.as_slice();
-            let offset = update_message.entity_data_size_pos as usize
-                + mem::size_of_val(&update_message.entity_data_size);
-            self.cursor.write_all(&slice[offset..]).unwrap();
-
-            self.entity_data_size = self
-                .entity_data_size
-                .checked_add(update_message.entity_data_size)
-                .ok_or(bincode::ErrorKind::SizeLimit)?;
+            let offset = update_message.entity_data_size_pos as usize;
+            self.cursor.write_all(&slice[offset..])?;
+
+            self.entity_data_size += update_message.entity_data_size;
             update_message.entity_data_size = 0;
         }
 
@@ -365,42 +495,59 @@ impl InitMessage {
     fn as_slice(&self) -> &[u8] {
         let slice = self.cursor.get_ref();
         let position = self.cursor.position() as usize;
-        let extra_len = self.trailing_empty_arrays * mem::size_of_val(&self.array_len);
+        // Each empty array is a single byte with zero length.
+        let extra_len = self.trailing_empty_arrays;
         &slice[..position - extra_len]
     }
 
     /// Sends the message, excluding trailing empty arrays, to the specified client.
     ///
     /// Updates change tick for the client if there are data to send.
-    /// Does nothing if there is no data to send.
+    /// Does nothing if there is no data to send, unless the initial sync for the client
+    /// completes on this tick, which is always reported with [`INITIAL_SYNC_FLAG`].
+    /// Without initial sync budget, the completion is reported only with data.
+    /// Returns the number of sent bytes.
     fn send(
         &self,
         server: &mut RepliconServer,
+        compressor: &mut MessageCompressor,
+        compression_threshold: Option<usize>,
         client: &mut ConnectedClient,
         server_tick: RepliconTick,
-    ) -> bincode::Result<()> {
+    ) -> bincode::Result<usize> {
         debug_assert_eq!(self.array_len, 0);
         debug_assert_eq!(self.entity_data_size, 0);
 
         let slice = self.as_slice();
-        if slice.is_empty() {
+        let mut flags = 0;
+        if client.finish_initial_sync_tick(!slice.is_empty()) {
+            debug!("completing initial sync for {:?}", client.id());
+            flags |= INITIAL_SYNC_FLAG;
+        }
+
+        if slice.is_empty() && flags == 0 {
             trace!("no init data to send for {:?}", client.id());
-            return Ok(());
+            return Ok(0);
         }
 
         client.set_init_tick(server_tick);
 
-        let mut header = [0; mem::size_of::<RepliconTick>()];
+        let mut header = [0; INIT_HEADER_SIZE - mem::size_of::<u8>()];
         bincode::serialize_into(&mut header[..], &server_tick)?;
 
         trace!("sending init message to {:?}", client.id());
-        server.send(
-            client.id(),
-            ReplicationChannel::Init,
-            Bytes::from([&header, slice].concat()),
-        );
+        let payload = match compressor.compress(slice, compression_threshold)? {
+            Some(compressed) => {
+                flags |= COMPRESSED_FLAG;
+                compressed
+            }
+            None => slice,
+        };
+        let message = [&[flags], &header[..], payload].concat();
+        let size = message.len();
+        server.send(client.id(), ReplicationChannel::Init, message);
 
-        Ok(())
+        Ok(size)
     }
 }
 
@@ -415,6 +562,7 @@ impl Default for InitMessage {
             entity_data_pos: Default::default(),
             entity_data_size_pos: Default::default(),
             data_entity: Entity::PLACEHOLDER,
+            max_size: Default::default(),
         }
     }
 }
@@ -434,20 +582,37 @@ pub(super) struct UpdateMessage {
     /// Serialized data.
     cursor: Cursor<Vec<u8>>,
 
-    /// Entities and their sizes in the message with data.
-    entities: Vec<(Entity, usize)>,
+    /// Entities in the message with data.
+    entities: Vec<UpdateEntity>,
+
+    /// Entity indexes with their data offsets and accumulated priorities.
+    ///
+    /// Used by [`Self::apply_budget`] to reuse allocated capacity.
+    priorities: Vec<(usize, usize, f32)>,
+
+    /// Buffer for data of entities that fit into the budget.
+    ///
+    /// Used by [`Self::apply_budget`] to reuse allocated capacity.
+    budget_buffer: Vec<u8>,
 
     /// Entity from last call of [`Self::start_entity_data`].
     data_entity: Entity,
 
     /// Size in bytes of the component data stored for the currently-being-written entity.
-    entity_data_size: u16,
+    entity_data_size: usize,
 
     /// Position of entity from last call of [`Self::start_entity_data`].
     entity_data_pos: u64,
 
-    /// Position of entity data length from last call of [`Self::write_data_entity`].
+    /// Position of entity data from last call of [`Self::write_data_entity`].
+    ///
+    /// Length will be inserted at this position after writing the data.
     entity_data_size_pos: u64,
+
+    /// Tick to acknowledge for the currently-being-written entity if some of its changes were skipped.
+    ///
+    /// See also [`Self::skip_change`].
+    skipped_tick: Option<Tick>,
 }
 
 impl UpdateMessage {
@@ -469,40 +634,67 @@ impl UpdateMessage {
 
         self.data_entity = entity;
         self.entity_data_pos = self.cursor.position();
+        self.skipped_tick = None;
+    }
+
+    /// Marks a change of the current entity's component as skipped.
+    ///
+    /// The entity will be acknowledged with a tick before `changed` so that the change
+    /// will be detected and sent later.
+    pub(super) fn skip_change(&mut self, changed: Tick, this_run: Tick) {
+        let tick = Tick::new(changed.get().wrapping_sub(1));
+        match &mut self.skipped_tick {
+            Some(skipped_tick) if skipped_tick.is_newer_than(tick, this_run) => {
+                *skipped_tick = tick
+            }
+            Some(_) => (),
+            None => self.skipped_tick = Some(tick),
+        }
+    }
+
+    /// Returns the tick to acknowledge for the current entity if some of its changes were skipped.
+    ///
+    /// See also [`Self::skip_change`].
+    pub(super) fn skipped_tick(&self) -> Option<Tick> {
+        self.skipped_tick
     }
 
-    /// Writes entity for the current data and remembers the position after it to write length later.
+    /// Writes entity for the current data and remembers the position after it to insert length later.
     ///
     /// Should be called only after first data write.
+    /// Overwrites data left after a failed write.
     fn write_data_entity(&mut self) -> bincode::Result<()> {
+        self.cursor.set_position(self.entity_data_pos);
         serialize_entity(&mut self.cursor, self.data_entity)?;
         self.entity_data_size_pos = self.cursor.position();
-        self.cursor.set_position(
-            self.entity_data_size_pos + mem::size_of_val(&self.entity_data_size) as u64,
-        );
 
         Ok(())
     }
 
-    /// Ends writing entity data by writing its length into the last remembered position.
+    /// Ends writing entity data by inserting its length into the last remembered position.
     ///
     /// If the entity data is empty, nothing will be written and the cursor will reset.
+    /// `priority` is used only for clients with a bandwidth budget, see [`Self::apply_budget`].
     /// See also [`Self::start_array`] and [`Self::write_component`].
-    pub(super) fn end_entity_data(&mut self) -> bincode::Result<()> {
+    pub(super) fn end_entity_data(&mut self, priority: f32) -> bincode::Result<()> {
         if self.entity_data_size == 0 {
             self.cursor.set_position(self.entity_data_pos);
             return Ok(());
         }
 
-        let previous_pos = self.cursor.position();
-        self.cursor.set_position(self.entity_data_size_pos);
-
-        bincode::serialize_into(&mut self.cursor, &self.entity_data_size)?;
-
-        self.cursor.set_position(previous_pos);
+        insert_len(
+            &mut self.cursor,
+            self.entity_data_size_pos,
+            self.entity_data_size,
+        )?;
 
         let data_size = self.cursor.position() - self.entity_data_pos;
-        self.entities.push((self.data_entity, data_size as usize));
+        self.entities.push(UpdateEntity {
+            entity: self.data_entity,
+            data_size: data_size as usize,
+            priority,
+            skipped_tick: self.skipped_tick,
+        });
 
         self.entity_data_size = 0;
 
@@ -517,8 +709,7 @@ impl UpdateMessage {
     pub(super) fn write_component<'a>(
         &'a mut self,
         shared_bytes: &mut Option<&'a [u8]>,
-        rule_fns: &UntypedRuleFns,
-        component_fns: &ComponentFns,
+        registry: &ReplicationRegistry,
         ctx: &SerializeCtx,
         fns_id: FnsId,
         ptr: Ptr,
@@ -527,16 +718,42 @@ impl UpdateMessage {
             self.write_data_entity()?;
         }
 
+        let (component_fns, rule_fns) = registry.get(fns_id);
         let size = write_with(shared_bytes, &mut self.cursor, |cursor| {
-            DefaultOptions::new().serialize_into(&mut *cursor, &fns_id)?;
+            registry.write_fns_id(&mut *cursor, fns_id)?;
             // SAFETY: `component_fns`, `ptr` and `rule_fns` were created for the same component type.
             unsafe { component_fns.serialize(ctx, rule_fns, ptr, cursor) }
         })?;
 
-        self.entity_data_size = self
-            .entity_data_size
-            .checked_add(size)
-            .ok_or(bincode::ErrorKind::SizeLimit)?;
+        self.entity_data_size += size;
+
+        Ok(())
+    }
+
+    /// Writes serialized component bytes and their replication functions ID as an element of entity data.
+    ///
+    /// Used for components with delta compression.
+    /// Bytes will be written as a diff if `baseline` is present and the diff is smaller.
+    /// Should be called only inside an entity data and increases its size.
+    /// See also [`Self::start_entity_data`] and [`delta_compression::write_bytes`].
+    pub(super) fn write_delta_component(
+        &mut self,
+        registry: &ReplicationRegistry,
+        fns_id: FnsId,
+        bytes: &[u8],
+        baseline: Option<(RepliconTick, &[u8])>,
+        diff_buffer: &mut Vec<u8>,
+    ) -> bincode::Result<()> {
+        if self.entity_data_size == 0 {
+            self.write_data_entity()?;
+        }
+
+        let previous_pos = self.cursor.position();
+        registry.write_fns_id(&mut self.cursor, fns_id)?;
+        delta_compression::write_bytes(&mut self.cursor, bytes, baseline, diff_buffer)?;
+
+        let size = (self.cursor.position() - previous_pos) as usize;
+        self.entity_data_size += size;
 
         Ok(())
     }
@@ -548,12 +765,73 @@ impl UpdateMessage {
         &slice[..position]
     }
 
+    /// Leaves only entities with the highest accumulated priority that fit into `budget` bytes.
+    ///
+    /// Priority of deferred entities is accumulated, so they will be sent on the next ticks.
+    /// Deferred entities won't be registered in the update, so their change ticks remain untouched.
+    /// See also [`ConnectedClient::set_bandwidth_budget`].
+    fn apply_budget(&mut self, priority: &mut ClientPriority, budget: usize) {
+        debug_assert_eq!(self.entity_data_size, 0);
+
+        let data_size = self.cursor.position() as usize;
+        if data_size <= budget {
+            for update_entity in &self.entities {
+                priority.reset_accumulated(update_entity.entity);
+            }
+            return;
+        }
+
+        self.priorities.clear();
+        let mut offset = 0;
+        for (index, update_entity) in self.entities.iter().enumerate() {
+            let accumulated = priority.accumulate(update_entity.entity, update_entity.priority);
+            self.priorities.push((index, offset, accumulated));
+            offset += update_entity.data_size;
+        }
+        self.priorities
+            .sort_unstable_by(|(.., a), (.., b)| b.total_cmp(a));
+
+        let mut used = 0;
+        self.priorities.retain(|&(index, ..)| {
+            let update_entity = &self.entities[index];
+            if (used == 0 && budget > 0) || used + update_entity.data_size <= budget {
+                used += update_entity.data_size;
+                priority.reset_accumulated(update_entity.entity);
+                true
+            } else {
+                false
+            }
+        });
+        self.priorities.sort_unstable_by_key(|&(index, ..)| index);
+
+        trace!(
+            "deferring {} entities out of {} due to the budget of {budget} bytes",
+            self.entities.len() - self.priorities.len(),
+            self.entities.len(),
+        );
+
+        self.budget_buffer.clear();
+        let data = &self.cursor.get_ref()[..data_size];
+        for (new_index, &(index, offset, _)) in self.priorities.iter().enumerate() {
+            let update_entity = self.entities[index];
+            self.budget_buffer
+                .extend_from_slice(&data[offset..offset + update_entity.data_size]);
+            self.entities[new_index] = update_entity;
+        }
+        self.entities.truncate(self.priorities.len());
+
+        mem::swap(self.cursor.get_mut(), &mut self.budget_buffer);
+        self.cursor.set_position(self.cursor.get_ref().len() as u64);
+    }
+
     /// Splits message according to entities inside it and sends it to the specified client.
     ///
     /// Does nothing if there is no data to send.
     fn send(
         &mut self,
         server: &mut RepliconServer,
+        compressor: &mut MessageCompressor,
+        compression_threshold: Option<usize>,
         client_buffers: &mut ClientBuffers,
         client: &mut ConnectedClient,
         server_tick: RepliconTick,
@@ -573,45 +851,59 @@ impl UpdateMessage {
         let mut header = [0; TICKS_SIZE + mem::size_of::<u16>()];
         bincode::serialize_into(&mut header[..], &(client.init_tick(), server_tick))?;
 
+        let header_size = header.len();
         let mut message_size = 0;
         let client_id = client.id();
+        let max_size = server
+            .max_payload_size(client_id, ReplicationChannel::Update)
+            .unwrap_or(DEFAULT_UPDATE_SPLIT_SIZE);
         let (mut update_index, mut entities) =
-            client.register_update(client_buffers, tick, timestamp);
-        for &(entity, data_size) in &self.entities {
+            client.register_update(client_buffers, tick, server_tick, timestamp);
+        for update_entity in &self.entities {
+            let data_size = update_entity.data_size;
+            let entity_tick = update_entity.skipped_tick.unwrap_or(tick);
             // Try to pack back first, then try to pack forward.
             if message_size == 0
-                || can_pack(header.len(), message_size, data_size)
-                || can_pack(header.len(), data_size, message_size)
+                || can_pack(max_size, header_size, message_size, data_size)
+                || can_pack(max_size, header_size, data_size, message_size)
             {
-                entities.push(entity);
+                entities.push((update_entity.entity, entity_tick));
                 message_size += data_size;
             } else {
                 let (message, remaining) = slice.split_at(message_size);
                 slice = remaining;
                 message_size = data_size;
 
-                bincode::serialize_into(&mut header[TICKS_SIZE..], &update_index)?;
-
                 server.send(
                     client_id,
                     ReplicationChannel::Update,
-                    Bytes::from([&header, message].concat()),
+                    build_update_message(
+                        compressor,
+                        compression_threshold,
+                        &mut header,
+                        update_index,
+                        message,
+                    )?,
                 );
 
                 if !slice.is_empty() {
                     (update_index, entities) =
-                        client.register_update(client_buffers, tick, timestamp);
+                        client.register_update(client_buffers, tick, server_tick, timestamp);
                 }
             }
         }
 
         if !slice.is_empty() {
-            bincode::serialize_into(&mut header[TICKS_SIZE..], &update_index)?;
-
             server.send(
                 client_id,
                 ReplicationChannel::Update,
-                Bytes::from([&header, slice].concat()),
+                build_update_message(
+                    compressor,
+                    compression_threshold,
+                    &mut header,
+                    update_index,
+                    slice,
+                )?,
             );
         }
 
@@ -624,29 +916,50 @@ impl Default for UpdateMessage {
         Self {
             cursor: Default::default(),
             entities: Default::default(),
+            priorities: Default::default(),
+            budget_buffer: Default::default(),
             entity_data_size: Default::default(),
             entity_data_pos: Default::default(),
             entity_data_size_pos: Default::default(),
             data_entity: Entity::PLACEHOLDER,
+            skipped_tick: None,
         }
     }
 }
 
+/// Information about an entity with data in [`UpdateMessage`].
+#[derive(Clone, Copy)]
+struct UpdateEntity {
+    entity: Entity,
+
+    /// Size of the entity data in bytes.
+    data_size: usize,
+
+    /// Base priority for clients with a bandwidth budget.
+    priority: f32,
+
+    /// Tick to acknowledge if some changes were skipped.
+    skipped_tick: Option<Tick>,
+}
+
 /// Writes new data into a cursor and returns the serialized size.
 ///
 /// Reuses previously shared bytes if they exist, or updates them.
-/// Serialized size should be less then [`u16`].
+/// On error the cursor is moved back, so partially written data will be overwritten.
 fn write_with<'a>(
     shared_bytes: &mut Option<&'a [u8]>,
     cursor: &'a mut Cursor<Vec<u8>>,
     write_fn: impl FnOnce(&mut Cursor<Vec<u8>>) -> bincode::Result<()>,
-) -> bincode::Result<u16> {
+) -> bincode::Result<usize> {
     let bytes = if let Some(bytes) = shared_bytes {
         cursor.write_all(bytes)?;
         bytes
     } else {
         let previous_pos = cursor.position() as usize;
-        (write_fn(cursor))?;
+        if let Err(e) = write_fn(cursor) {
+            cursor.set_position(previous_pos as u64);
+            return Err(e);
+        }
         let current_pos = cursor.position() as usize;
 
         let buffer = cursor.get_ref();
@@ -656,19 +969,56 @@ fn write_with<'a>(
         bytes
     };
 
-    let size = bytes
-        .len()
-        .try_into()
-        .map_err(|_| bincode::ErrorKind::SizeLimit)?;
+    Ok(bytes.len())
+}
+
+/// Inserts `len` as a varint at `pos`, shifting the data written after it.
+///
+/// Lengths are inserted after writing the data because their size is unknown in advance.
+/// Data after the cursor position is discarded.
+fn insert_len(cursor: &mut Cursor<Vec<u8>>, pos: u64, len: usize) -> bincode::Result<()> {
+    let mut len_cursor = Cursor::new([0; MAX_LEN_SIZE]);
+    DefaultOptions::new().serialize_into(&mut len_cursor, &len)?;
+    let len_size = len_cursor.position() as usize;
+    let len_bytes = &len_cursor.get_ref()[..len_size];
+
+    let end = cursor.position();
+    let buffer = cursor.get_mut();
+    buffer.truncate(end as usize);
+    buffer.splice(pos as usize..pos as usize, len_bytes.iter().copied());
+    cursor.set_position(end + len_size as u64);
 
-    Ok(size)
+    Ok(())
 }
 
-fn can_pack(header_size: usize, base: usize, add: usize) -> bool {
-    const MAX_PACKET_SIZE: usize = 1200; // TODO: make it configurable by the messaging backend.
+/// Concatenates the update message header and payload, compressing the payload if needed.
+///
+/// Update index is written into the last bytes of the header.
+///
+/// The compression is indicated by [`COMPRESSED_UPDATE_BIT`] in the update index,
+/// so uncompressed messages have no overhead.
+fn build_update_message(
+    compressor: &mut MessageCompressor,
+    compression_threshold: Option<usize>,
+    header: &mut [u8],
+    update_index: u16,
+    payload: &[u8],
+) -> bincode::Result<Vec<u8>> {
+    debug_assert_eq!(update_index & COMPRESSED_UPDATE_BIT, 0);
+    let (update_index, payload) = match compressor.compress(payload, compression_threshold)? {
+        Some(compressed) => (update_index | COMPRESSED_UPDATE_BIT, compressed),
+        None => (update_index, payload),
+    };
+    let index_pos = header.len() - mem::size_of::<u16>();
+    bincode::serialize_into(&mut header[index_pos..], &update_index)?;
+
+    Ok([header, payload].concat())
+}
 
-    let dangling = (base + header_size) % MAX_PACKET_SIZE;
-    (dangling > 0) && ((dangling + add) <= MAX_PACKET_SIZE)
+/// Returns `true` if `add` bytes can be appended to `base` bytes without crossing the `max_size` boundary.
+fn can_pack(max_size: usize, header_size: usize, base: usize, add: usize) -> bool {
+    let dangling = (base + header_size) % max_size;
+    (dangling > 0) && ((dangling + add) <= max_size)
 }
 
 /// Serializes `entity` by writing its index and generation as separate varints.
@@ -694,16 +1044,48 @@ fn serialize_entity(cursor: &mut Cursor<Vec<u8>>, entity: Entity) -> bincode::Re
 mod tests {
     use super::*;
 
+    #[test]
+    fn length_insertion() {
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.write_all(&[1, 2, 3]).unwrap();
+        insert_len(&mut cursor, 1, 2).unwrap();
+        assert_eq!(cursor.get_ref(), &[1, 2, 2, 3]);
+        assert_eq!(cursor.position(), 4);
+
+        let data = vec![0; u16::MAX as usize + 1];
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.write_all(&data).unwrap();
+        insert_len(&mut cursor, 0, data.len()).unwrap();
+
+        let mut cursor = Cursor::new(cursor.into_inner());
+        let len: usize = DefaultOptions::new().deserialize_from(&mut cursor).unwrap();
+        assert_eq!(len, data.len());
+        assert_eq!(
+            cursor.get_ref().len() as u64 - cursor.position(),
+            len as u64
+        );
+    }
+
     #[test]
     fn packing() {
-        assert!(can_pack(10, 0, 5));
-        assert!(can_pack(10, 0, 1190));
-        assert!(!can_pack(10, 0, 1191));
-        assert!(!can_pack(10, 0, 3000));
-
-        assert!(can_pack(10, 1189, 1));
-        assert!(!can_pack(10, 1190, 0));
-        assert!(!can_pack(10, 1190, 1));
-        assert!(!can_pack(10, 1190, 3000));
+        assert!(can_pack(1200, 10, 0, 5));
+        assert!(can_pack(1200, 10, 0, 1190));
+        assert!(!can_pack(1200, 10, 0, 1191));
+        assert!(!can_pack(1200, 10, 0, 3000));
+
+        assert!(can_pack(1200, 10, 1189, 1));
+        assert!(!can_pack(1200, 10, 1190, 0));
+        assert!(!can_pack(1200, 10, 1190, 1));
+        assert!(!can_pack(1200, 10, 1190, 3000));
+    }
+
+    #[test]
+    fn packing_with_custom_size() {
+        assert!(can_pack(500, 10, 0, 490));
+        assert!(!can_pack(500, 10, 0, 491));
+
+        assert!(can_pack(9000, 10, 1190, 1));
+        assert!(can_pack(9000, 10, 0, 8990));
+        assert!(!can_pack(9000, 10, 0, 8991));
     }
 }
@@ -1,4 +1,4 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 use bytes::Bytes;
 
 use crate::core::ClientId;
@@ -11,6 +11,10 @@ use crate::core::ClientId;
 /// A system to forward messages from the backend to Replicon should run in [`ServerSet::ReceivePackets`](super::ServerSet::ReceivePackets).
 /// - For sending messages, [`Self::drain_sent`] should be used to drain all sent messages.
 /// A system to forward messages from Replicon to the backend should run in [`ServerSet::SendPackets`](super::ServerSet::SendPackets).
+/// - For reporting how many bytes can be sent in a single message without fragmentation,
+///   [`Self::set_max_payload_size`] should be used after a client connects.
+/// - For disconnecting clients requested by Replicon, [`Self::drain_disconnects`] should be used.
+///   It should be called in [`ServerSet::SendPackets`](super::ServerSet::SendPackets) after sending messages.
 #[derive(Resource, Default)]
 pub struct RepliconServer {
     /// Indicates if the server is open for connections.
@@ -26,6 +30,14 @@ pub struct RepliconServer {
 
     /// List of sent messages for each channel since the last tick.
     sent_messages: Vec<(ClientId, u8, Bytes)>,
+
+    /// Max payload sizes reported by the messaging backend for each client.
+    ///
+    /// Inner index is channel ID, [`None`] means that the size wasn't reported.
+    max_payload_sizes: HashMap<ClientId, Vec<Option<usize>>>,
+
+    /// Clients that should be disconnected.
+    disconnects: Vec<ClientId>,
 }
 
 impl RepliconServer {
@@ -36,11 +48,17 @@ impl RepliconServer {
 
     /// Removes a disconnected client.
     pub(super) fn remove_client(&mut self, client_id: ClientId) {
+        self.remove_received(client_id);
+        self.sent_messages
+            .retain(|&(sender_id, ..)| sender_id != client_id);
+        self.max_payload_sizes.remove(&client_id);
+    }
+
+    /// Removes all received messages from a client.
+    pub(super) fn remove_received(&mut self, client_id: ClientId) {
         for receive_channel in &mut self.received_messages {
             receive_channel.retain(|&(sender_id, _)| sender_id != client_id);
         }
-        self.sent_messages
-            .retain(|&(sender_id, ..)| sender_id != client_id);
     }
 
     /// Receives all available messages from clients over a channel.
@@ -76,8 +94,63 @@ impl RepliconServer {
             return;
         }
 
-        self.sent_messages
-            .push((client_id, channel_id.into(), message.into()));
+        let channel_id = channel_id.into();
+        let message = message.into();
+        if let Some(max_size) = self.max_payload_size(client_id, channel_id) {
+            if message.len() > max_size {
+                debug!(
+                    "sending message of {} bytes to {client_id:?} over channel {channel_id} that exceeds max payload size of {max_size} bytes",
+                    message.len()
+                );
+            }
+        }
+
+        self.sent_messages.push((client_id, channel_id, message));
+    }
+
+    /// Sets the max number of bytes that can be sent to a client over a channel in a single message
+    /// without fragmentation.
+    ///
+    /// Used to split update messages and drop oversized events.
+    /// If [`ServerPlugin::initial_sync_budget`](super::ServerPlugin::initial_sync_budget) is set,
+    /// new entities that don't fit into an init message are also deferred to the next ticks.
+    /// If not set, the size is considered unlimited.
+    ///
+    /// Should be called only from the messaging backend.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn set_max_payload_size<I: Into<u8>>(
+        &mut self,
+        client_id: ClientId,
+        channel_id: I,
+        size: usize,
+    ) {
+        assert_ne!(size, 0, "max payload size should be positive");
+        let channel_id = channel_id.into() as usize;
+        let sizes = self.max_payload_sizes.entry(client_id).or_default();
+        if sizes.len() <= channel_id {
+            sizes.resize(channel_id + 1, None);
+        }
+        sizes[channel_id] = Some(size);
+    }
+
+    /// Returns the max number of bytes that can be sent to a client over a channel in a single message.
+    ///
+    /// Returns [`None`] if the messaging backend didn't report it.
+    ///
+    /// See also [`Self::set_max_payload_size`].
+    pub fn max_payload_size<I: Into<u8>>(
+        &self,
+        client_id: ClientId,
+        channel_id: I,
+    ) -> Option<usize> {
+        self.max_payload_sizes
+            .get(&client_id)
+            .and_then(|sizes| sizes.get(channel_id.into() as usize))
+            .copied()
+            .flatten()
     }
 
     /// Marks the server as running or stopped.
@@ -91,6 +164,8 @@ impl RepliconServer {
                 receive_channel.clear();
             }
             self.sent_messages.clear();
+            self.max_payload_sizes.clear();
+            self.disconnects.clear();
         }
 
         self.running = running;
@@ -104,7 +179,7 @@ impl RepliconServer {
 
     /// Retains only the messages specified by the predicate.
     ///
-    /// Used for testing.
+    /// Used for testing and recording.
     pub(crate) fn retain_sent<F>(&mut self, f: F)
     where
         F: FnMut(&(ClientId, u8, Bytes)) -> bool,
@@ -119,6 +194,24 @@ impl RepliconServer {
         self.sent_messages.drain(..)
     }
 
+    /// Requests a disconnect for a client.
+    ///
+    /// The disconnect will be performed by the messaging backend after sending all pending messages.
+    /// See also [`Self::drain_disconnects`].
+    pub fn disconnect(&mut self, client_id: ClientId) {
+        debug!("requesting disconnect for {client_id:?}");
+        if !self.disconnects.contains(&client_id) {
+            self.disconnects.push(client_id);
+        }
+    }
+
+    /// Removes all requested disconnects, returning them as an iterator.
+    ///
+    /// Should be called only from the messaging backend.
+    pub fn drain_disconnects(&mut self) -> impl Iterator<Item = ClientId> + '_ {
+        self.disconnects.drain(..)
+    }
+
     /// Adds a message from a client to the list of received messages.
     ///
     /// Should be called only from the messaging backend.
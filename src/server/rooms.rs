@@ -0,0 +1,245 @@
+use bevy::{
+    ecs::entity::{Entities, EntityHashMap, EntityHashSet},
+    prelude::*,
+    utils::HashMap,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{connected_clients::ConnectedClients, VisibilityPolicy};
+
+/// Unique room ID.
+///
+/// See also [`Rooms`].
+#[derive(
+    Debug, Clone, Copy, Hash, PartialEq, Eq, Ord, PartialOrd, Serialize, Deserialize, Reflect,
+)]
+pub struct RoomId(u64);
+
+impl RoomId {
+    /// Creates a new ID wrapping the given value.
+    pub const fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// Gets the value of this ID.
+    pub fn get(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Rooms in which an entity is located.
+///
+/// An entity is visible for a client if they share at least one room.
+/// Clients join and leave rooms via [`ConnectedClients::join_room`] and [`ConnectedClients::leave_room`].
+///
+/// Visibility is derived by calling [`ClientVisibility::set_visibility`](super::connected_clients::client_visibility::ClientVisibility::set_visibility)
+/// only when an entity enters or leaves the first shared room, so it's intended to be used with
+/// [`VisibilityPolicy::Whitelist`]. With other policies rooms are ignored and a warning is logged.
+/// Changes are applied in [`ServerSet::Send`](super::ServerSet::Send).
+#[derive(Component, Default, Debug, Clone, PartialEq, Eq)]
+pub struct Rooms(Vec<RoomId>);
+
+impl Rooms {
+    /// Creates rooms from an iterator of room IDs.
+    pub fn new(rooms: impl IntoIterator<Item = RoomId>) -> Self {
+        let mut rooms: Vec<_> = rooms.into_iter().collect();
+        rooms.sort_unstable();
+        rooms.dedup();
+        Self(rooms)
+    }
+
+    /// Adds the entity to a room.
+    ///
+    /// Returns `false` if the entity was already in this room.
+    pub fn insert(&mut self, room_id: RoomId) -> bool {
+        match self.0.binary_search(&room_id) {
+            Ok(_) => false,
+            Err(index) => {
+                self.0.insert(index, room_id);
+                true
+            }
+        }
+    }
+
+    /// Removes the entity from a room.
+    ///
+    /// Returns `false` if the entity wasn't in this room.
+    pub fn remove(&mut self, room_id: RoomId) -> bool {
+        match self.0.binary_search(&room_id) {
+            Ok(index) => {
+                self.0.remove(index);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Returns `true` if the entity is in the room.
+    pub fn contains(&self, room_id: RoomId) -> bool {
+        self.0.binary_search(&room_id).is_ok()
+    }
+
+    /// Returns an iterator over rooms.
+    pub fn iter(&self) -> impl Iterator<Item = RoomId> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// Returns rooms as a sorted slice.
+    pub fn as_slice(&self) -> &[RoomId] {
+        &self.0
+    }
+}
+
+/// Entities of each room.
+///
+/// Stored inside [`ConnectedClients`] to update visibility immediately when a client joins or leaves a room.
+#[derive(Default)]
+pub(super) struct RoomIndex {
+    /// Entities in each room.
+    entities: HashMap<RoomId, EntityHashSet>,
+
+    /// Last processed rooms of each entity.
+    ///
+    /// Used to calculate the difference when [`Rooms`] changes.
+    entity_rooms: EntityHashMap<Vec<RoomId>>,
+}
+
+impl RoomIndex {
+    /// Returns entities in a room.
+    pub(super) fn entities(&self, room_id: RoomId) -> impl Iterator<Item = Entity> + '_ {
+        self.entities
+            .get(&room_id)
+            .into_iter()
+            .flat_map(|entities| entities.iter().copied())
+    }
+
+    /// Updates rooms of an entity.
+    ///
+    /// Calls `f` for each room from which the entity was removed with `false`
+    /// and for each room to which the entity was added with `true`.
+    pub(super) fn update_entity(
+        &mut self,
+        entity: Entity,
+        rooms: &[RoomId],
+        mut f: impl FnMut(RoomId, bool),
+    ) {
+        let old_rooms = self.entity_rooms.remove(&entity).unwrap_or_default();
+        for &room_id in old_rooms.iter().filter(|room| !rooms.contains(room)) {
+            if let Some(entities) = self.entities.get_mut(&room_id) {
+                entities.remove(&entity);
+                if entities.is_empty() {
+                    self.entities.remove(&room_id);
+                }
+            }
+            f(room_id, false);
+        }
+
+        for &room_id in rooms.iter().filter(|room| !old_rooms.contains(room)) {
+            self.entities.entry(room_id).or_default().insert(entity);
+            f(room_id, true);
+        }
+
+        if !rooms.is_empty() {
+            self.entity_rooms.insert(entity, rooms.to_vec());
+        }
+    }
+
+    /// Removes a despawned entity from all rooms.
+    pub(super) fn remove_despawned(&mut self, entity: Entity) {
+        let Some(rooms) = self.entity_rooms.remove(&entity) else {
+            return;
+        };
+
+        for room_id in rooms {
+            if let Some(entities) = self.entities.get_mut(&room_id) {
+                entities.remove(&entity);
+                if entities.is_empty() {
+                    self.entities.remove(&room_id);
+                }
+            }
+        }
+    }
+}
+
+/// Updates room membership of entities in [`ConnectedClients`].
+///
+/// Ignores all changes if the visibility policy isn't [`VisibilityPolicy::Whitelist`].
+pub(super) fn update_rooms(
+    mut connected_clients: ResMut<ConnectedClients>,
+    mut warned: Local<bool>,
+    changed_rooms: Query<(Entity, &Rooms), Changed<Rooms>>,
+    mut removed_rooms: RemovedComponents<Rooms>,
+    entities: &Entities,
+) {
+    let policy = connected_clients.visibility_policy();
+    if !matches!(policy, VisibilityPolicy::Whitelist) {
+        removed_rooms.clear();
+        if !*warned && !changed_rooms.is_empty() {
+            warn!(
+                "ignoring `Rooms` because they require {:?}, but the policy is {policy:?}",
+                VisibilityPolicy::Whitelist
+            );
+            *warned = true;
+        }
+        return;
+    }
+
+    for entity in removed_rooms.read() {
+        if entities.contains(entity) {
+            connected_clients.update_entity_rooms(entity, &[]);
+        } else {
+            // Visibility for despawned entities will be cleaned up during replication.
+            connected_clients.remove_despawned_from_rooms(entity);
+        }
+    }
+
+    for (entity, rooms) in &changed_rooms {
+        connected_clients.update_entity_rooms(entity, rooms.as_slice());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rooms() {
+        let mut rooms = Rooms::new([RoomId::new(2), RoomId::new(1), RoomId::new(2)]);
+        assert_eq!(rooms.as_slice(), [RoomId::new(1), RoomId::new(2)]);
+
+        assert!(rooms.insert(RoomId::new(0)));
+        assert!(!rooms.insert(RoomId::new(0)));
+        assert!(rooms.contains(RoomId::new(0)));
+
+        assert!(rooms.remove(RoomId::new(1)));
+        assert!(!rooms.remove(RoomId::new(1)));
+        assert_eq!(rooms.as_slice(), [RoomId::new(0), RoomId::new(2)]);
+    }
+
+    #[test]
+    fn index() {
+        let mut index = RoomIndex::default();
+        let mut changes = Vec::new();
+
+        index.update_entity(
+            Entity::PLACEHOLDER,
+            &[RoomId::new(0), RoomId::new(1)],
+            |room_id, added| changes.push((room_id, added)),
+        );
+        assert_eq!(changes, [(RoomId::new(0), true), (RoomId::new(1), true)]);
+        assert_eq!(index.entities(RoomId::new(0)).count(), 1);
+
+        changes.clear();
+        index.update_entity(
+            Entity::PLACEHOLDER,
+            &[RoomId::new(1), RoomId::new(2)],
+            |room_id, added| changes.push((room_id, added)),
+        );
+        assert_eq!(changes, [(RoomId::new(0), false), (RoomId::new(2), true)]);
+        assert_eq!(index.entities(RoomId::new(0)).count(), 0);
+
+        index.remove_despawned(Entity::PLACEHOLDER);
+        assert!(index.entities.is_empty());
+        assert!(index.entity_rooms.is_empty());
+    }
+}
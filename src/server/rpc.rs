@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+
+use super::events::{SendMode, ToClients};
+use crate::{
+    client::{
+        events::FromClient,
+        rpc::{RequestId, RequestMessage, ResponseMessage},
+    },
+    core::ClientId,
+};
+
+/// Converts received request messages into [`ClientRequest`] events.
+pub(crate) fn receive_requests<Req: Send + Sync + 'static>(
+    mut message_events: ResMut<Events<FromClient<RequestMessage<Req>>>>,
+    mut request_events: EventWriter<ClientRequest<Req>>,
+) {
+    for FromClient { client_id, event } in message_events.drain() {
+        request_events.send(ClientRequest {
+            token: ReplyToken {
+                client_id,
+                id: event.id,
+            },
+            request: event.request,
+        });
+    }
+}
+
+/// Converts [`Reply`] events into response messages for the client from the token.
+pub(crate) fn send_replies<Resp: Send + Sync + 'static>(
+    mut replies: ResMut<Events<Reply<Resp>>>,
+    mut response_events: EventWriter<ToClients<ResponseMessage<Resp>>>,
+) {
+    for Reply { token, response } in replies.drain() {
+        response_events.send(ToClients {
+            mode: SendMode::Direct(token.client_id),
+            event: ResponseMessage {
+                id: token.id,
+                response,
+            },
+        });
+    }
+}
+
+/// A request received from a client.
+///
+/// Emitted only on server for requests registered with
+/// [`AppRpcExt::add_rpc`](crate::client::rpc::AppRpcExt::add_rpc).
+/// Answer it by sending [`Reply`] with the same token.
+#[derive(Event)]
+pub struct ClientRequest<Req> {
+    /// Identifies the sender and the request, should be passed to [`Reply`].
+    pub token: ReplyToken,
+
+    /// Request data sent by the client.
+    pub request: Req,
+}
+
+/// A response to [`ClientRequest`] that will be sent to the client from the token.
+#[derive(Event)]
+pub struct Reply<Resp> {
+    /// Token from the [`ClientRequest`] this reply answers.
+    pub token: ReplyToken,
+
+    /// Response data for the client.
+    pub response: Resp,
+}
+
+/// Identifies the client and the request to respond to.
+#[derive(Clone, Copy, Debug)]
+pub struct ReplyToken {
+    client_id: ClientId,
+    id: RequestId,
+}
+
+impl ReplyToken {
+    /// Returns the client that sent the request.
+    pub fn client_id(&self) -> ClientId {
+        self.client_id
+    }
+
+    /// Returns the ID of the request.
+    pub fn id(&self) -> RequestId {
+        self.id
+    }
+}
@@ -0,0 +1,453 @@
+use bevy::{
+    ecs::entity::{EntityHashMap, EntityHashSet},
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{connected_clients::ConnectedClients, ServerEvent, ServerPlugin, ServerSet};
+use crate::core::{common_conditions::server_running, ClientId, Replicated};
+
+/// Drives [`ClientVisibility`](super::connected_clients::client_visibility::ClientVisibility)
+/// automatically based on distance between replicated entities and client viewers.
+///
+/// Maintains a uniform grid of all entities with [`Replicated`] and [`Transform`].
+/// Each client sees entities from cells around its [`Viewer`] entities. A client can have
+/// any number of viewers, an entity is visible if it's in range of at least one of them.
+///
+/// Visibility is updated only when an entity or a viewer moves to a different cell, so the cost is
+/// proportional to movement, not to the world size.
+///
+/// Requires [`VisibilityPolicy::Whitelist`](super::VisibilityPolicy::Whitelist) and shouldn't be combined
+/// with manual visibility changes or [`Rooms`](super::rooms::Rooms) for the same entities.
+/// Only the translation from [`Transform`] is used, so entities are expected to be root entities.
+pub struct SpatialInterestPlugin {
+    /// Size of a single grid cell in world units.
+    ///
+    /// Visibility is computed with cell granularity.
+    pub cell_size: f32,
+
+    /// Distance in world units around a viewer in which entities become visible.
+    ///
+    /// Rounded up to whole cells.
+    pub radius: f32,
+
+    /// Additional distance in world units beyond [`Self::radius`] after which cells
+    /// stop being visible when a viewer moves away from them.
+    ///
+    /// Prevents visibility flickering when a viewer moves back and forth across a cell border.
+    /// Rounded up to whole cells together with the radius.
+    pub hysteresis: f32,
+
+    /// Axes used to build the grid.
+    pub axes: GridAxes,
+}
+
+impl Default for SpatialInterestPlugin {
+    fn default() -> Self {
+        Self {
+            cell_size: 50.0,
+            radius: 100.0,
+            hysteresis: 25.0,
+            axes: Default::default(),
+        }
+    }
+}
+
+impl Plugin for SpatialInterestPlugin {
+    fn build(&self, app: &mut App) {
+        assert!(
+            self.cell_size > 0.0,
+            "cell size should be positive, but it's {}",
+            self.cell_size
+        );
+        assert!(
+            self.radius >= 0.0 && self.hysteresis >= 0.0,
+            "radius and hysteresis can't be negative"
+        );
+
+        let visible_range = (self.radius / self.cell_size).ceil() as i32;
+        let hidden_range = ((self.radius + self.hysteresis) / self.cell_size).ceil() as i32;
+        app.insert_resource(SpatialGrid {
+            cell_size: self.cell_size,
+            visible_range,
+            hidden_range,
+            axes: self.axes,
+            cells: Default::default(),
+            entity_cells: Default::default(),
+            watchers: Default::default(),
+            viewers: Default::default(),
+        })
+        .add_systems(
+            PostUpdate,
+            (
+                Self::reset_connected,
+                Self::update_entities,
+                Self::update_viewers,
+            )
+                .chain()
+                .in_set(ServerSet::Send)
+                .before(ServerPlugin::send_replication)
+                .run_if(server_running),
+        );
+    }
+}
+
+impl SpatialInterestPlugin {
+    /// Shows all watched entities for newly connected clients.
+    ///
+    /// Needed because viewers for a client could be spawned before it's connected.
+    fn reset_connected(
+        mut server_events: EventReader<ServerEvent>,
+        mut connected_clients: ResMut<ConnectedClients>,
+        grid: Res<SpatialGrid>,
+    ) {
+        for event in server_events.read() {
+            let &ServerEvent::ClientConnected { client_id } = event else {
+                continue;
+            };
+            let Some(client) = connected_clients.get_client_mut(client_id) else {
+                continue;
+            };
+
+            for (cell, watchers) in &grid.watchers {
+                if watchers.contains_key(&client_id) {
+                    for entity in grid.entities(*cell) {
+                        client.visibility_mut().set_visibility(entity, true);
+                    }
+                }
+            }
+        }
+    }
+
+    fn update_entities(
+        mut connected_clients: ResMut<ConnectedClients>,
+        mut grid: ResMut<SpatialGrid>,
+        mut removed_replicated: RemovedComponents<Replicated>,
+        mut removed_transforms: RemovedComponents<Transform>,
+        replicated: Query<(), With<Replicated>>,
+        entities: Query<
+            (Entity, &Transform),
+            (
+                With<Replicated>,
+                Or<(Changed<Transform>, Added<Replicated>)>,
+            ),
+        >,
+    ) {
+        for entity in removed_replicated.read() {
+            // Visibility will be cleaned up by replication as for despawned entities.
+            grid.remove_entity(entity);
+        }
+
+        for entity in removed_transforms.read() {
+            let Some(cell) = grid.remove_entity(entity) else {
+                continue;
+            };
+
+            // Entity is still replicated, but no longer tracked by the grid.
+            if replicated.get(entity).is_ok() {
+                if let Some(watchers) = grid.watchers.get(&cell) {
+                    for &client_id in watchers.keys() {
+                        set_visibility(&mut connected_clients, client_id, entity, false);
+                    }
+                }
+            }
+        }
+
+        for (entity, transform) in &entities {
+            let cell = grid.cell(transform.translation);
+            let old_cell = grid.entity_cells.insert(entity, cell);
+            if old_cell == Some(cell) {
+                continue;
+            }
+
+            if let Some(old_cell) = old_cell {
+                grid.remove_from_cell(entity, old_cell);
+            }
+            grid.cells.entry(cell).or_default().insert(entity);
+
+            let old_watchers = old_cell.and_then(|cell| grid.watchers.get(&cell));
+            let new_watchers = grid.watchers.get(&cell);
+            if let Some(old_watchers) = old_watchers {
+                for &client_id in old_watchers
+                    .keys()
+                    .filter(|&client_id| !new_watchers.is_some_and(|w| w.contains_key(client_id)))
+                {
+                    set_visibility(&mut connected_clients, client_id, entity, false);
+                }
+            }
+            if let Some(new_watchers) = new_watchers {
+                for &client_id in new_watchers
+                    .keys()
+                    .filter(|&client_id| !old_watchers.is_some_and(|w| w.contains_key(client_id)))
+                {
+                    set_visibility(&mut connected_clients, client_id, entity, true);
+                }
+            }
+        }
+    }
+
+    fn update_viewers(
+        mut connected_clients: ResMut<ConnectedClients>,
+        mut grid: ResMut<SpatialGrid>,
+        mut removed_viewers: RemovedComponents<Viewer>,
+        viewers: Query<(Entity, &Viewer, &Transform), Or<(Changed<Viewer>, Changed<Transform>)>>,
+    ) {
+        for entity in removed_viewers.read() {
+            if let Some(state) = grid.viewers.remove(&entity) {
+                for cell in state.cells {
+                    grid.unwatch(&mut connected_clients, cell, state.client_id);
+                }
+            }
+        }
+
+        for (entity, viewer, transform) in &viewers {
+            let center = grid.cell(transform.translation);
+            let mut state = grid.viewers.remove(&entity).unwrap_or_else(|| ViewerState {
+                client_id: viewer.0,
+                center: None,
+                cells: Default::default(),
+            });
+            if state.client_id != viewer.0 {
+                // Client changed, unwatch everything and start over.
+                for cell in state.cells.drain() {
+                    grid.unwatch(&mut connected_clients, cell, state.client_id);
+                }
+                state.center = None;
+            }
+            state.client_id = viewer.0;
+
+            if state.center != Some(center) {
+                state.center = Some(center);
+
+                let mut lost_cells = Vec::new();
+                state.cells.retain(|&cell| {
+                    let keep = grid.distance(cell, center) <= grid.hidden_range;
+                    if !keep {
+                        lost_cells.push(cell);
+                    }
+                    keep
+                });
+                for cell in lost_cells {
+                    grid.unwatch(&mut connected_clients, cell, state.client_id);
+                }
+
+                for cell in grid.cells_in_range(center) {
+                    if state.cells.insert(cell) {
+                        grid.watch(&mut connected_clients, cell, state.client_id);
+                    }
+                }
+            }
+
+            grid.viewers.insert(entity, state);
+        }
+    }
+}
+
+/// Marks an entity as a viewer for a client.
+///
+/// See [`SpatialInterestPlugin`] for details.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Viewer(pub ClientId);
+
+/// Axes used by [`SpatialInterestPlugin`] to build the grid.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridAxes {
+    /// Uses X and Y axes, suitable for 2D games.
+    Xy,
+    /// Uses X and Z axes, suitable for 3D games where the vertical axis can be ignored.
+    Xz,
+    /// Uses all axes.
+    #[default]
+    Xyz,
+}
+
+/// Spatial grid of replicated entities maintained by [`SpatialInterestPlugin`].
+#[derive(Resource)]
+pub struct SpatialGrid {
+    cell_size: f32,
+
+    /// Max distance in cells to a viewer at which a cell becomes watched.
+    visible_range: i32,
+
+    /// Max distance in cells to a viewer at which a watched cell remains watched.
+    hidden_range: i32,
+
+    axes: GridAxes,
+
+    /// Entities in each cell.
+    cells: HashMap<IVec3, EntityHashSet>,
+
+    /// Cell of each entity.
+    entity_cells: EntityHashMap<IVec3>,
+
+    /// Number of viewers that watch a cell for each client.
+    watchers: HashMap<IVec3, HashMap<ClientId, u32>>,
+
+    /// Processed state of each viewer.
+    viewers: EntityHashMap<ViewerState>,
+}
+
+impl SpatialGrid {
+    /// Returns the cell for a position.
+    pub fn cell(&self, translation: Vec3) -> IVec3 {
+        let cell = (translation / self.cell_size).floor().as_ivec3();
+        match self.axes {
+            GridAxes::Xy => cell.with_z(0),
+            GridAxes::Xz => cell.with_y(0),
+            GridAxes::Xyz => cell,
+        }
+    }
+
+    /// Returns the cell of an entity.
+    pub fn entity_cell(&self, entity: Entity) -> Option<IVec3> {
+        self.entity_cells.get(&entity).copied()
+    }
+
+    /// Returns entities in a cell.
+    pub fn entities(&self, cell: IVec3) -> impl Iterator<Item = Entity> + '_ {
+        self.cells
+            .get(&cell)
+            .into_iter()
+            .flat_map(|entities| entities.iter().copied())
+    }
+
+    /// Returns `true` if the cell is watched by any viewer of the client.
+    pub fn is_watched(&self, cell: IVec3, client_id: ClientId) -> bool {
+        self.watchers
+            .get(&cell)
+            .is_some_and(|watchers| watchers.contains_key(&client_id))
+    }
+
+    /// Returns Chebyshev distance between two cells in cells.
+    fn distance(&self, a: IVec3, b: IVec3) -> i32 {
+        (a - b).abs().max_element()
+    }
+
+    /// Returns all cells in the visible range around the center.
+    fn cells_in_range(&self, center: IVec3) -> impl Iterator<Item = IVec3> {
+        let range = self.visible_range;
+        let (y_range, z_range) = match self.axes {
+            GridAxes::Xy => (range, 0),
+            GridAxes::Xz => (0, range),
+            GridAxes::Xyz => (range, range),
+        };
+
+        (-range..=range).flat_map(move |x| {
+            (-y_range..=y_range)
+                .flat_map(move |y| (-z_range..=z_range).map(move |z| center + IVec3::new(x, y, z)))
+        })
+    }
+
+    /// Increments the number of client's viewers for a cell and shows its entities on the first one.
+    fn watch(
+        &mut self,
+        connected_clients: &mut ConnectedClients,
+        cell: IVec3,
+        client_id: ClientId,
+    ) {
+        let count = self
+            .watchers
+            .entry(cell)
+            .or_default()
+            .entry(client_id)
+            .or_default();
+        *count += 1;
+        if *count == 1 {
+            for entity in self.entities(cell) {
+                set_visibility(connected_clients, client_id, entity, true);
+            }
+        }
+    }
+
+    /// Decrements the number of client's viewers for a cell and hides its entities on the last one.
+    fn unwatch(
+        &mut self,
+        connected_clients: &mut ConnectedClients,
+        cell: IVec3,
+        client_id: ClientId,
+    ) {
+        let Some(watchers) = self.watchers.get_mut(&cell) else {
+            return;
+        };
+        let Some(count) = watchers.get_mut(&client_id) else {
+            return;
+        };
+
+        *count -= 1;
+        if *count == 0 {
+            watchers.remove(&client_id);
+            if watchers.is_empty() {
+                self.watchers.remove(&cell);
+            }
+            for entity in self.entities(cell) {
+                set_visibility(connected_clients, client_id, entity, false);
+            }
+        }
+    }
+
+    /// Removes an entity from the grid and returns its last cell.
+    fn remove_entity(&mut self, entity: Entity) -> Option<IVec3> {
+        let cell = self.entity_cells.remove(&entity)?;
+        self.remove_from_cell(entity, cell);
+        Some(cell)
+    }
+
+    fn remove_from_cell(&mut self, entity: Entity, cell: IVec3) {
+        if let Some(entities) = self.cells.get_mut(&cell) {
+            entities.remove(&entity);
+            if entities.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+}
+
+struct ViewerState {
+    client_id: ClientId,
+
+    /// Last processed cell of the viewer.
+    center: Option<IVec3>,
+
+    /// Cells watched by the viewer.
+    cells: HashSet<IVec3>,
+}
+
+/// Sets visibility of an entity for a client if it's connected.
+fn set_visibility(
+    connected_clients: &mut ConnectedClients,
+    client_id: ClientId,
+    entity: Entity,
+    visible: bool,
+) {
+    if let Some(client) = connected_clients.get_client_mut(client_id) {
+        client.visibility_mut().set_visibility(entity, visible);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells_in_range() {
+        let plugin = SpatialInterestPlugin {
+            cell_size: 10.0,
+            radius: 10.0,
+            hysteresis: 0.0,
+            axes: GridAxes::Xy,
+        };
+        let mut app = App::new();
+        plugin.build(&mut app);
+
+        let grid = app.world().resource::<SpatialGrid>();
+        assert_eq!(
+            grid.cell(Vec3::new(-1.0, 15.0, 100.0)),
+            IVec3::new(-1, 1, 0)
+        );
+        assert_eq!(grid.cells_in_range(IVec3::ZERO).count(), 9);
+        assert!(grid
+            .cells_in_range(IVec3::ZERO)
+            .all(|cell| grid.distance(cell, IVec3::ZERO) <= 1 && cell.z == 0));
+    }
+}
@@ -54,7 +54,7 @@ pub trait ServerTestAppExt {
     /// Starts server in [`self`] and connects a client app.
     ///
     /// Can be called multiple times on different client apps.
-    /// Internally updates both apps one time.
+    /// Internally updates both apps twice to perform the protocol handshake.
     ///
     /// # Panics
     ///
@@ -110,6 +110,12 @@ impl ServerTestAppExt for App {
 
         self.update(); // Will update `ConnectedClients`, otherwise next call will assign the same ID.
         client_app.update();
+
+        // Perform protocol handshake.
+        self.exchange_with_client(client_app);
+        self.update();
+        self.exchange_with_client(client_app);
+        client_app.update();
     }
 
     fn disconnect_client(&mut self, client_app: &mut App) {
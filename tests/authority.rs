@@ -0,0 +1,290 @@
+use bevy::{ecs::event::Events, prelude::*};
+use bevy_replicon::{
+    client::authority::{ValidateCtx, Validation},
+    core::{channels::ReplicationChannel, replication_registry::rule_fns::RuleFns},
+    prelude::*,
+    test_app::ServerTestAppExt,
+};
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn owned_change() {
+    let mut server_app = App::new();
+    let mut client_app1 = App::new();
+    let mut client_app2 = App::new();
+    for app in [&mut server_app, &mut client_app1, &mut client_app2] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate_from_client::<DummyComponent>();
+    }
+
+    server_app.connect_client(&mut client_app1);
+    server_app.connect_client(&mut client_app2);
+
+    let client_id = client_app1
+        .world()
+        .resource::<RepliconClient>()
+        .id()
+        .unwrap();
+    let server_entity = server_app
+        .world_mut()
+        .spawn((
+            Replicated,
+            DummyComponent(0),
+            Authority::<DummyComponent>::new(client_id),
+        ))
+        .id();
+
+    for client_app in [&mut client_app1, &mut client_app2] {
+        server_app.update();
+        server_app.exchange_with_client(client_app);
+        client_app.update();
+        server_app.exchange_with_client(client_app);
+    }
+
+    let (mut component, _) = client_app1
+        .world_mut()
+        .query::<(&mut DummyComponent, &Authority<DummyComponent>)>()
+        .single_mut(client_app1.world_mut());
+    component.0 = 1;
+
+    client_app1.update();
+    server_app.exchange_with_client(&mut client_app1);
+    server_app.update();
+
+    let component = server_app
+        .world()
+        .get::<DummyComponent>(server_entity)
+        .unwrap();
+    assert_eq!(component.0, 1, "change from the owner should be applied");
+
+    server_app.exchange_with_client(&mut client_app2);
+    client_app2.update();
+
+    let component = client_app2
+        .world_mut()
+        .query::<&DummyComponent>()
+        .single(client_app2.world());
+    assert_eq!(component.0, 1, "change should be replicated to others");
+
+    // Try to change from a client without authority.
+    let mut component = client_app2
+        .world_mut()
+        .query::<&mut DummyComponent>()
+        .single_mut(client_app2.world_mut());
+    component.0 = 2;
+
+    client_app2.update();
+    server_app.exchange_with_client(&mut client_app2);
+    server_app.update();
+
+    let component = server_app
+        .world()
+        .get::<DummyComponent>(server_entity)
+        .unwrap();
+    assert_eq!(component.0, 1, "change without authority should be ignored");
+}
+
+#[test]
+fn validation() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate_from_client_with(RuleFns::<DummyComponent>::default(), validate);
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let client_id = client_app
+        .world()
+        .resource::<RepliconClient>()
+        .id()
+        .unwrap();
+    let server_entity = server_app
+        .world_mut()
+        .spawn((
+            Replicated,
+            DummyComponent(0),
+            Authority::<DummyComponent>::new(client_id),
+        ))
+        .id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    for (value, expected) in [(MAX_VALUE + 1, MAX_VALUE), (REJECTED_VALUE, MAX_VALUE)] {
+        let mut component = client_app
+            .world_mut()
+            .query::<&mut DummyComponent>()
+            .single_mut(client_app.world_mut());
+        component.0 = value;
+
+        client_app.update();
+        server_app.exchange_with_client(&mut client_app);
+        server_app.update();
+
+        let component = server_app
+            .world()
+            .get::<DummyComponent>(server_entity)
+            .unwrap();
+        assert_eq!(component.0, expected);
+
+        server_app.exchange_with_client(&mut client_app);
+        client_app.update();
+
+        let component = client_app
+            .world_mut()
+            .query::<&DummyComponent>()
+            .single(client_app.world());
+        assert_eq!(
+            component.0, expected,
+            "server value should be replicated back to the owner"
+        );
+    }
+}
+
+#[test]
+fn outdated_echo() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate_from_client::<DummyComponent>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let client_id = client_app
+        .world()
+        .resource::<RepliconClient>()
+        .id()
+        .unwrap();
+    let server_entity = server_app
+        .world_mut()
+        .spawn((
+            Replicated,
+            DummyComponent(0),
+            Authority::<DummyComponent>::new(client_id),
+        ))
+        .id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let mut component = client_app
+        .world_mut()
+        .query::<&mut DummyComponent>()
+        .single_mut(client_app.world_mut());
+    component.0 = 1;
+
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    server_app.update();
+
+    // Change again before receiving the server state with the previous value.
+    let mut component = client_app
+        .world_mut()
+        .query::<&mut DummyComponent>()
+        .single_mut(client_app.world_mut());
+    component.0 = 2;
+
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let component = client_app
+        .world_mut()
+        .query::<&DummyComponent>()
+        .single(client_app.world());
+    assert_eq!(component.0, 2, "outdated value shouldn't overwrite local");
+
+    server_app.exchange_with_client(&mut client_app);
+    server_app.update();
+
+    let component = server_app
+        .world()
+        .get::<DummyComponent>(server_entity)
+        .unwrap();
+    assert_eq!(component.0, 2);
+}
+
+#[test]
+fn not_authority_rule() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, RepliconPlugins))
+            .replicate::<OtherComponent>()
+            .replicate_from_client::<DummyComponent>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let client_id = client_app
+        .world()
+        .resource::<RepliconClient>()
+        .id()
+        .unwrap();
+
+    // Use the ID of the first registered rule, which is not an authority rule.
+    let mut server = server_app.world_mut().resource_mut::<RepliconServer>();
+    server.insert_received(client_id, ReplicationChannel::Authority, vec![0]);
+
+    server_app.update();
+
+    let mut violations = server_app
+        .world_mut()
+        .resource_mut::<Events<ProtocolViolation>>();
+    let [violation] = &violations.drain().collect::<Vec<_>>()[..] else {
+        panic!("server should emit a single violation");
+    };
+    assert_eq!(violation.client_id, client_id);
+    assert!(matches!(
+        violation.kind,
+        ViolationKind::InvalidAuthorityChange(_)
+    ));
+}
+
+const MAX_VALUE: u32 = 10;
+const REJECTED_VALUE: u32 = 5;
+
+fn validate(
+    _ctx: &ValidateCtx,
+    _current: &DummyComponent,
+    new: &DummyComponent,
+) -> Validation<DummyComponent> {
+    if new.0 == REJECTED_VALUE {
+        Validation::Reject
+    } else if new.0 > MAX_VALUE {
+        Validation::Clamp(DummyComponent(MAX_VALUE))
+    } else {
+        Validation::Accept
+    }
+}
+
+#[derive(Component, Deserialize, Serialize)]
+struct DummyComponent(u32);
+
+#[derive(Component, Deserialize, Serialize)]
+struct OtherComponent;
@@ -1,12 +1,21 @@
-use std::io::Cursor;
+use std::{io::Cursor, mem};
 
 use bevy::{ecs::entity::MapEntities, prelude::*, utils::Duration};
 use bevy_replicon::{
-    client::{confirm_history::ConfirmHistory, server_entity_map::ServerEntityMap, ServerInitTick},
+    client::{
+        confirm_history::ConfirmHistory,
+        diagnostics::{ClientDiagnosticsPlugin, ClientStats},
+        server_entity_map::ServerEntityMap,
+        ServerInitTick,
+    },
     core::{
+        channels::ReplicationChannel,
         command_markers::MarkerConfig,
-        ctx::WriteCtx,
-        replication_registry::{command_fns, rule_fns::RuleFns},
+        ctx::{SerializeCtx, WriteCtx},
+        replication_registry::{
+            command_fns,
+            rule_fns::{self, RuleFns},
+        },
     },
     prelude::*,
     server::server_tick::ServerTick,
@@ -477,6 +486,58 @@ fn marker_with_history_old_update() {
     );
 }
 
+#[test]
+fn big_component() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<VecComponent>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    // Exceed the size that fits into `u16`.
+    const VEC_LEN: usize = u16::MAX as usize + 1;
+    let server_entity = server_app
+        .world_mut()
+        .spawn((Replicated, VecComponent(vec![0; VEC_LEN])))
+        .id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let component = client_app
+        .world_mut()
+        .query::<&VecComponent>()
+        .single(client_app.world());
+    assert_eq!(component.0.len(), VEC_LEN);
+
+    let mut component = server_app
+        .world_mut()
+        .get_mut::<VecComponent>(server_entity)
+        .unwrap();
+    component.0 = vec![1; VEC_LEN];
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let component = client_app
+        .world_mut()
+        .query::<&VecComponent>()
+        .single(client_app.world());
+    assert!(component.0.iter().all(|&value| value == 1));
+}
+
 #[test]
 fn many_entities() {
     let mut server_app = App::new();
@@ -528,6 +589,245 @@ fn many_entities() {
     }
 }
 
+#[test]
+fn many_entities_with_custom_payload_size() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<BoolComponent>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    const ENTITIES_COUNT: u32 = 300;
+    server_app
+        .world_mut()
+        .spawn_batch([(Replicated, BoolComponent(false)); ENTITIES_COUNT as usize]);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    assert_eq!(client_app.world().entities().len(), ENTITIES_COUNT);
+
+    const MAX_PAYLOAD_SIZE: usize = 100;
+    let client = client_app.world().resource::<RepliconClient>();
+    let client_id = client.id().unwrap();
+    server_app
+        .world_mut()
+        .resource_mut::<RepliconServer>()
+        .set_max_payload_size(client_id, ReplicationChannel::Update, MAX_PAYLOAD_SIZE);
+
+    for mut component in server_app
+        .world_mut()
+        .query::<&mut BoolComponent>()
+        .iter_mut(server_app.world_mut())
+    {
+        component.0 = true;
+    }
+
+    server_app.update();
+
+    let mut server = server_app.world_mut().resource_mut::<RepliconServer>();
+    let messages: Vec<_> = server.drain_sent().collect();
+    let updates: Vec<_> = messages
+        .iter()
+        .filter(|&&(_, channel_id, _)| channel_id == ReplicationChannel::Update as u8)
+        .map(|(_, _, message)| message)
+        .collect();
+    assert!(updates.len() > 1, "update message should be split");
+    assert!(updates
+        .iter()
+        .all(|message| message.len() <= MAX_PAYLOAD_SIZE));
+
+    for (client_id, channel_id, message) in messages {
+        server.send(client_id, channel_id, message);
+    }
+
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    for component in client_app
+        .world_mut()
+        .query::<&BoolComponent>()
+        .iter(client_app.world())
+    {
+        assert!(component.0);
+    }
+}
+
+#[test]
+fn init_message_with_custom_payload_size() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                // Splitting of init messages is enabled only with initial sync budget.
+                initial_sync_budget: Some(1024),
+                ..Default::default()
+            }),
+        ))
+        .replicate::<BoolComponent>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    const MAX_PAYLOAD_SIZE: usize = 100;
+    let client = client_app.world().resource::<RepliconClient>();
+    let client_id = client.id().unwrap();
+    server_app
+        .world_mut()
+        .resource_mut::<RepliconServer>()
+        .set_max_payload_size(client_id, ReplicationChannel::Init, MAX_PAYLOAD_SIZE);
+
+    const ENTITIES_COUNT: u32 = 50;
+    server_app
+        .world_mut()
+        .spawn_batch([(Replicated, BoolComponent(false)); ENTITIES_COUNT as usize]);
+
+    let mut ticks = 0;
+    while client_app.world().entities().len() < ENTITIES_COUNT {
+        server_app.update();
+
+        let mut server = server_app.world_mut().resource_mut::<RepliconServer>();
+        let messages: Vec<_> = server.drain_sent().collect();
+        for (_, channel_id, message) in &messages {
+            if *channel_id == ReplicationChannel::Init as u8 {
+                assert!(message.len() <= MAX_PAYLOAD_SIZE);
+            }
+        }
+        for (client_id, channel_id, message) in messages {
+            server.send(client_id, channel_id, message);
+        }
+
+        server_app.exchange_with_client(&mut client_app);
+        client_app.update();
+        server_app.exchange_with_client(&mut client_app);
+
+        ticks += 1;
+        assert!(ticks < ENTITIES_COUNT, "entities should be sent in batches");
+    }
+
+    assert!(ticks > 1, "init message should be split across ticks");
+}
+
+#[test]
+fn init_message_with_custom_payload_size_without_budget() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<BoolComponent>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let client = client_app.world().resource::<RepliconClient>();
+    let client_id = client.id().unwrap();
+    server_app
+        .world_mut()
+        .resource_mut::<RepliconServer>()
+        .set_max_payload_size(client_id, ReplicationChannel::Init, 100);
+
+    const ENTITIES_COUNT: u32 = 50;
+    server_app
+        .world_mut()
+        .spawn_batch([(Replicated, BoolComponent(false)); ENTITIES_COUNT as usize]);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    assert_eq!(
+        client_app.world().entities().len(),
+        ENTITIES_COUNT,
+        "init message shouldn't be split without initial sync budget"
+    );
+}
+
+#[test]
+fn compression() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                compression_policy: CompressionPolicy::Threshold(64),
+                ..Default::default()
+            }),
+        ))
+        .replicate::<VecComponent>();
+    }
+    client_app.add_plugins(ClientDiagnosticsPlugin);
+
+    server_app.connect_client(&mut client_app);
+
+    // Ignore the message that completed the initial sync.
+    *client_app.world_mut().resource_mut::<ClientStats>() = Default::default();
+
+    const ENTITIES_COUNT: u32 = 10;
+    const VEC_LEN: usize = 100;
+    for _ in 0..ENTITIES_COUNT {
+        server_app
+            .world_mut()
+            .spawn((Replicated, VecComponent(vec![0; VEC_LEN])));
+    }
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    assert_eq!(client_app.world().entities().len(), ENTITIES_COUNT);
+
+    let stats = client_app.world().resource::<ClientStats>();
+    assert_eq!(stats.messages, 1);
+    assert!(
+        stats.bytes < (ENTITIES_COUNT as usize * VEC_LEN) as u64,
+        "init message should be compressed"
+    );
+
+    for mut component in server_app
+        .world_mut()
+        .query::<&mut VecComponent>()
+        .iter_mut(server_app.world_mut())
+    {
+        component.0 = vec![1; VEC_LEN];
+    }
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    for component in client_app
+        .world_mut()
+        .query::<&VecComponent>()
+        .iter(client_app.world())
+    {
+        assert_eq!(component.0, [1; VEC_LEN]);
+    }
+}
+
 #[test]
 fn with_insertion() {
     let mut server_app = App::new();
@@ -786,6 +1086,306 @@ fn old_ignored() {
     );
 }
 
+#[test]
+fn delta_compression() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate_with(RuleFns::<VecComponent>::default().with_delta_compression());
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    const DATA_SIZE: usize = 500;
+    let server_entity = server_app
+        .world_mut()
+        .spawn((Replicated, VecComponent(vec![0; DATA_SIZE])))
+        .id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    for index in [10, 400] {
+        let mut component = server_app
+            .world_mut()
+            .get_mut::<VecComponent>(server_entity)
+            .unwrap();
+        component.0[index] = 1;
+
+        server_app.update();
+
+        let mut server = server_app.world_mut().resource_mut::<RepliconServer>();
+        let messages: Vec<_> = server.drain_sent().collect();
+        let (_, _, update) = messages
+            .iter()
+            .find(|&&(_, channel_id, _)| channel_id == ReplicationChannel::Update as u8)
+            .expect("change should be sent as an update");
+        assert!(
+            update.len() < DATA_SIZE / 10,
+            "only the difference should be sent"
+        );
+
+        for (client_id, channel_id, message) in messages {
+            server.send(client_id, channel_id, message);
+        }
+
+        server_app.exchange_with_client(&mut client_app);
+        client_app.update();
+        server_app.exchange_with_client(&mut client_app);
+
+        let component = client_app
+            .world_mut()
+            .query::<&VecComponent>()
+            .single(client_app.world());
+        let server_component = server_app
+            .world()
+            .get::<VecComponent>(server_entity)
+            .unwrap();
+        assert_eq!(component.0, server_component.0);
+    }
+}
+
+#[test]
+fn delta_compression_without_ack() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate_with(RuleFns::<VecComponent>::default().with_delta_compression());
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app
+        .world_mut()
+        .spawn((Replicated, VecComponent(vec![0; 100])))
+        .id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    for index in 0..3 {
+        let mut component = server_app
+            .world_mut()
+            .get_mut::<VecComponent>(server_entity)
+            .unwrap();
+        component.0[index] = 1;
+
+        server_app.update();
+        server_app.exchange_with_client(&mut client_app);
+        client_app.update();
+
+        // Drop acks to force the server to send diffs against the same baseline.
+        let mut client = client_app.world_mut().resource_mut::<RepliconClient>();
+        client.drain_sent().count();
+    }
+
+    let component = client_app
+        .world_mut()
+        .query::<&VecComponent>()
+        .single(client_app.world());
+    assert_eq!(component.0[..4], [1, 1, 1, 0]);
+}
+
+#[test]
+fn old_ignored_with_delta_compression() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<BoolComponent>()
+        .replicate_with(RuleFns::<VecComponent>::default().with_delta_compression());
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app
+        .world_mut()
+        .spawn((Replicated, BoolComponent(false), VecComponent(vec![0; 10])))
+        .id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    // Generate two updates and process them on client together, so the older one will be ignored.
+    for value in [true, false] {
+        let mut component = server_app
+            .world_mut()
+            .get_mut::<BoolComponent>(server_entity)
+            .unwrap();
+        component.0 = value;
+
+        server_app.update();
+        server_app.exchange_with_client(&mut client_app);
+    }
+
+    client_app.update();
+
+    let mut client = client_app.world_mut().resource_mut::<RepliconClient>();
+    let (_, acks) = client
+        .drain_sent()
+        .find(|&(channel_id, _)| channel_id == ReplicationChannel::Init as u8)
+        .expect("client should send acknowledgments");
+    assert_eq!(
+        acks.len(),
+        2 * mem::size_of::<u16>(),
+        "ignored update without delta compressed values should be acknowledged"
+    );
+}
+
+#[test]
+#[allow(clippy::manual_is_multiple_of)]
+fn periodic() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate_periodic::<BoolComponent>(4)
+        .replicate::<VecComponent>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app
+        .world_mut()
+        .spawn((Replicated, BoolComponent(false), VecComponent::default()))
+        .id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let mut component = server_app
+        .world_mut()
+        .get_mut::<BoolComponent>(server_entity)
+        .unwrap();
+    component.0 = true;
+
+    let mut sent = false;
+    for value in 0..4 {
+        // Change the other component every tick to make sure it doesn't consume the periodic change.
+        let mut component = server_app
+            .world_mut()
+            .get_mut::<VecComponent>(server_entity)
+            .unwrap();
+        component.0 = vec![value];
+
+        server_app.update();
+        server_app.exchange_with_client(&mut client_app);
+        client_app.update();
+        server_app.exchange_with_client(&mut client_app);
+
+        let tick = **server_app.world().resource::<ServerTick>();
+        if (tick.get() + server_entity.index()) % 4 == 0 {
+            sent = true;
+        }
+
+        let (bool_component, vec_component) = client_app
+            .world_mut()
+            .query::<(&BoolComponent, &VecComponent)>()
+            .single(client_app.world());
+        assert_eq!(
+            bool_component.0, sent,
+            "periodic component should be sent only when due"
+        );
+        assert_eq!(
+            vec_component.0,
+            [value],
+            "other components should be sent every tick"
+        );
+    }
+
+    assert!(sent);
+}
+
+#[test]
+fn reliable() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate_reliable::<BoolComponent>()
+        .replicate::<VecComponent>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app
+        .world_mut()
+        .spawn((Replicated, BoolComponent(false), VecComponent::default()))
+        .id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let mut entity = server_app.world_mut().entity_mut(server_entity);
+    entity.get_mut::<BoolComponent>().unwrap().0 = true;
+    entity.get_mut::<VecComponent>().unwrap().0 = vec![1];
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let mut client = client_app.world_mut().resource_mut::<RepliconClient>();
+    assert_eq!(
+        client.receive(ReplicationChannel::Update).count(),
+        0,
+        "other changes of the entity should be sent together"
+    );
+
+    client_app.update();
+
+    let tick = **server_app.world().resource::<ServerTick>();
+    let init_tick = **client_app.world().resource::<ServerInitTick>();
+    assert_eq!(tick, init_tick, "change should be sent in init message");
+
+    let (bool_component, vec_component) = client_app
+        .world_mut()
+        .query::<(&BoolComponent, &VecComponent)>()
+        .single(client_app.world());
+    assert!(bool_component.0);
+    assert_eq!(vec_component.0, [1]);
+}
+
 #[test]
 fn acknowledgment() {
     let mut server_app = App::new();
@@ -867,6 +1467,60 @@ fn acknowledgment() {
     );
 }
 
+#[test]
+fn serialization_error() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate_with(RuleFns::new(
+            serialize_only_false,
+            rule_fns::default_deserialize::<BoolComponent>,
+        ))
+        .replicate::<VecComponent>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app
+        .world_mut()
+        .spawn((Replicated, BoolComponent(false), VecComponent::default()))
+        .id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let mut entity = server_app.world_mut().entity_mut(server_entity);
+    entity.get_mut::<BoolComponent>().unwrap().0 = true;
+    entity.get_mut::<VecComponent>().unwrap().0 = vec![1];
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let (bool_component, vec_component) = client_app
+        .world_mut()
+        .query::<(&BoolComponent, &VecComponent)>()
+        .single(client_app.world());
+    assert!(
+        !bool_component.0,
+        "component that failed to serialize should be skipped"
+    );
+    assert_eq!(
+        vec_component.0,
+        [1],
+        "other components should still be replicated"
+    );
+}
+
 #[derive(Component, Deserialize, Serialize)]
 struct DummyComponent;
 
@@ -933,3 +1587,16 @@ fn write_history(
 
     Ok(())
 }
+
+/// Serializes [`BoolComponent`], but fails on `true`.
+fn serialize_only_false(
+    _ctx: &SerializeCtx,
+    component: &BoolComponent,
+    cursor: &mut Cursor<Vec<u8>>,
+) -> bincode::Result<()> {
+    if component.0 {
+        return Err(bincode::ErrorKind::Custom("unable to serialize `true`".into()).into());
+    }
+
+    bincode::serialize_into(cursor, component)
+}
@@ -4,7 +4,10 @@ use bevy::{
     time::TimePlugin,
 };
 use bevy_replicon::{
-    client::server_entity_map::ServerEntityMap, prelude::*, test_app::ServerTestAppExt,
+    client::server_entity_map::ServerEntityMap,
+    prelude::*,
+    server::event_limit::{EventLimit, EventLimitExceeded, LimitAction},
+    test_app::ServerTestAppExt,
 };
 use serde::{Deserialize, Serialize};
 
@@ -53,6 +56,47 @@ fn sending_receiving() {
     assert_eq!(client_events.len(), 1);
 }
 
+#[test]
+fn limit() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, RepliconPlugins))
+            .add_client_event::<DummyEvent>(ChannelKind::Ordered)
+            .limit_client_event::<DummyEvent>(EventLimit {
+                events_per_second: Some(2),
+                action: LimitAction::DropAndEmit,
+                ..Default::default()
+            });
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    client_app
+        .world_mut()
+        .send_event_batch([DummyEvent, DummyEvent, DummyEvent]);
+
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    server_app.update();
+
+    let client_events = server_app
+        .world()
+        .resource::<Events<FromClient<DummyEvent>>>();
+    assert_eq!(client_events.len(), 2);
+
+    let client = client_app.world().resource::<RepliconClient>();
+    let client_id = client.id().unwrap();
+    let mut exceeded_events = server_app
+        .world_mut()
+        .resource_mut::<Events<EventLimitExceeded>>();
+    let [exceeded] = exceeded_events.drain().collect::<Vec<_>>()[..] else {
+        panic!("limit should be exceeded once");
+    };
+    assert_eq!(exceeded.client_id, client_id);
+    assert_eq!(exceeded.dropped, 1);
+}
+
 #[test]
 fn mapping_and_sending_receiving() {
     let mut server_app = App::new();
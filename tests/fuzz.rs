@@ -0,0 +1,204 @@
+//! Feeds random and corrupted messages to the client to ensure that decoding never panics.
+
+use bevy::prelude::*;
+use bevy_replicon::{
+    client::{MessageKind, ReceiveError, ReceiveErrorPolicy},
+    core::{
+        channels::{ReplicationChannel, RepliconChannels},
+        replication_registry::rule_fns::RuleFns,
+    },
+    prelude::*,
+    test_app::ServerTestAppExt,
+};
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+const ITERATIONS: usize = 2000;
+
+#[test]
+fn random_bytes() {
+    let (_, mut client_app) = setup();
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+
+    for _ in 0..ITERATIONS {
+        let mut client = client_app.world_mut().resource_mut::<RepliconClient>();
+        for channel_id in [ReplicationChannel::Init, ReplicationChannel::Update] {
+            let len = rng.below(64);
+            let mut message: Vec<_> = (0..len).map(|_| rng.next() as u8).collect();
+            // Use valid init flags to reach the payload more often.
+            if let Some(flags) = message
+                .first_mut()
+                .filter(|_| matches!(channel_id, ReplicationChannel::Init))
+            {
+                *flags %= 4;
+            }
+            client.insert_received(channel_id, message);
+        }
+
+        client_app.update();
+    }
+
+    let errors = client_app.world().resource::<ReceiveErrors>();
+    assert!(errors.init > 0);
+    assert!(errors.update > 0);
+}
+
+#[test]
+fn corrupted_messages() {
+    let (mut server_app, mut client_app) = setup();
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+
+    for index in 0..8 {
+        server_app.world_mut().spawn((
+            Replicated,
+            DummyComponent(index),
+            DeltaComponent(vec![index as u8; index as usize]),
+        ));
+    }
+    server_app.world_mut().insert_resource(DummyResource(1));
+    server_app.world_mut().send_event(ToClients {
+        mode: SendMode::Broadcast,
+        event: DummyEvent(2),
+    });
+    server_app.update();
+
+    let messages: Vec<_> = server_app
+        .world_mut()
+        .resource_mut::<RepliconServer>()
+        .drain_sent()
+        .map(|(_, channel_id, message)| (channel_id, message))
+        .collect();
+    assert!(!messages.is_empty());
+
+    for _ in 0..ITERATIONS {
+        let (channel_id, message) = &messages[rng.below(messages.len())];
+        let message = corrupt(&mut rng, message);
+        client_app
+            .world_mut()
+            .resource_mut::<RepliconClient>()
+            .insert_received(*channel_id, message);
+
+        client_app.update();
+    }
+
+    let errors = client_app.world().resource::<ReceiveErrors>();
+    assert!(errors.init > 0);
+}
+
+#[test]
+fn random_event_bytes() {
+    let (_, mut client_app) = setup();
+    let mut rng = Rng(0xd1b5_4a32_d192_ed03);
+
+    let channel_id = client_app
+        .world()
+        .resource::<RepliconChannels>()
+        .server_channels()
+        .len()
+        - 1;
+    for _ in 0..ITERATIONS {
+        let len = rng.below(16);
+        let message: Vec<_> = (0..len).map(|_| rng.next() as u8).collect();
+        client_app
+            .world_mut()
+            .resource_mut::<RepliconClient>()
+            .insert_received(channel_id as u8, message);
+
+        client_app.update();
+    }
+
+    let errors = client_app.world().resource::<ReceiveErrors>();
+    assert!(errors.event > 0);
+}
+
+fn setup() -> (App, App) {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<DummyComponent>()
+        .replicate_with(RuleFns::<DeltaComponent>::default().with_delta_compression())
+        .replicate_resource::<DummyResource>()
+        .add_server_event::<DummyEvent>(ChannelKind::Ordered);
+    }
+    client_app
+        .insert_resource(ReceiveErrorPolicy::Skip)
+        .init_resource::<ReceiveErrors>()
+        .add_systems(Update, count_errors);
+
+    server_app.connect_client(&mut client_app);
+
+    (server_app, client_app)
+}
+
+/// Flips, removes or appends random bytes.
+fn corrupt(rng: &mut Rng, message: &Bytes) -> Vec<u8> {
+    let mut message = message.to_vec();
+    for _ in 0..=rng.below(4) {
+        match rng.below(3) {
+            0 if !message.is_empty() => {
+                let index = rng.below(message.len());
+                message[index] = rng.next() as u8;
+            }
+            1 if !message.is_empty() => {
+                let len = rng.below(message.len());
+                message.truncate(len);
+            }
+            _ => message.push(rng.next() as u8),
+        }
+    }
+
+    message
+}
+
+fn count_errors(mut errors: EventReader<ReceiveError>, mut counts: ResMut<ReceiveErrors>) {
+    for error in errors.read() {
+        match error.message {
+            MessageKind::Init => counts.init += 1,
+            MessageKind::Update => counts.update += 1,
+            MessageKind::Event { .. } => counts.event += 1,
+            MessageKind::Authority => (),
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+struct ReceiveErrors {
+    init: usize,
+    update: usize,
+    event: usize,
+}
+
+/// Xorshift generator to avoid extra dependencies and keep tests deterministic.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, max: usize) -> usize {
+        (self.next() % max as u64) as usize
+    }
+}
+
+#[derive(Component, Deserialize, Serialize)]
+struct DummyComponent(u32);
+
+#[derive(Component, Deserialize, Serialize)]
+struct DeltaComponent(Vec<u8>);
+
+#[derive(Resource, Deserialize, Serialize)]
+struct DummyResource(u32);
+
+#[derive(Deserialize, Event, Serialize)]
+struct DummyEvent(u32);
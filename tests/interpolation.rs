@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_replicon::{client::interpolation::*, prelude::*, test_app::ServerTestAppExt};
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn interpolation() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<Position>();
+    }
+    client_app
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO))
+        .add_plugins(InterpolationPlugin {
+            delay: 0.5,
+            ..Default::default()
+        })
+        .interpolate::<Position>();
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app
+        .world_mut()
+        .spawn((Replicated, Position(0.0)))
+        .id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let client_entity = client_app
+        .world_mut()
+        .query_filtered::<Entity, With<Position>>()
+        .single(client_app.world());
+    client_app
+        .world_mut()
+        .entity_mut(client_entity)
+        .insert(Interpolated);
+
+    // Send values for two consecutive ticks.
+    for value in [2.0, 4.0] {
+        server_app
+            .world_mut()
+            .get_mut::<Position>(server_entity)
+            .unwrap()
+            .0 = value;
+
+        server_app.update();
+        server_app.exchange_with_client(&mut client_app);
+    }
+    client_app.update();
+
+    let buffer = client_app
+        .world()
+        .get::<SnapshotBuffer<Position>>(client_entity)
+        .unwrap();
+    assert_eq!(buffer.snapshots().len(), 2);
+
+    let position = client_app.world().get::<Position>(client_entity).unwrap();
+    assert_eq!(
+        position.0, 3.0,
+        "should be rendered between the last two snapshots"
+    );
+}
+
+#[derive(Component, Deserialize, Serialize, Clone)]
+struct Position(f32);
+
+impl Interpolate for Position {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self(self.0.interpolate(&other.0, t))
+    }
+}
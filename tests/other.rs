@@ -1,4 +1,4 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, time::TimeUpdateStrategy, utils::Duration};
 use bevy_replicon::{
     core::channels::ReplicationChannel, prelude::*, server::server_tick::ServerTick,
     test_app::ServerTestAppExt,
@@ -95,6 +95,235 @@ fn connect_disconnect() {
     assert!(connected_clients.is_empty());
 }
 
+#[test]
+fn protocol_verified() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<DummyComponent>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let status = client_app.world().resource::<ProtocolStatus>();
+    assert_eq!(*status, ProtocolStatus::Verified);
+
+    let mut server = server_app.world_mut().resource_mut::<RepliconServer>();
+    assert_eq!(server.drain_disconnects().count(), 0);
+}
+
+#[test]
+fn protocol_mismatch() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ));
+    }
+    server_app.replicate::<DummyComponent>();
+
+    server_app.connect_client(&mut client_app);
+
+    let client_hash = *client_app.world().resource::<ProtocolHash>();
+    let server_hash = *server_app.world().resource::<ProtocolHash>();
+    assert_ne!(client_hash, server_hash);
+
+    let status = client_app.world().resource::<ProtocolStatus>();
+    assert_eq!(*status, ProtocolStatus::Mismatch { server_hash });
+
+    let mut server = server_app.world_mut().resource_mut::<RepliconServer>();
+    let client_id = client_app
+        .world()
+        .resource::<RepliconClient>()
+        .id()
+        .unwrap();
+    assert_eq!(server.drain_disconnects().collect::<Vec<_>>(), [client_id]);
+
+    let mut mismatch_events = server_app.world_mut().resource_mut::<Events<ServerEvent>>();
+    let event = mismatch_events
+        .drain()
+        .find(|event| matches!(event, ServerEvent::ProtocolMismatch { .. }))
+        .expect("server should emit a mismatch event");
+    let ServerEvent::ProtocolMismatch {
+        client_id: event_client_id,
+        client_hash: event_client_hash,
+        server_hash: event_server_hash,
+    } = event
+    else {
+        unreachable!();
+    };
+    assert_eq!(event_client_id, client_id);
+    assert_eq!(event_client_hash, client_hash);
+    assert_eq!(event_server_hash, server_hash);
+
+    server_app.world_mut().spawn(Replicated);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let mut replicated = client_app.world_mut().query::<&Replicated>();
+    assert_eq!(
+        replicated.iter(client_app.world()).count(),
+        0,
+        "replication shouldn't be applied with a different protocol"
+    );
+}
+
+#[test]
+fn handshake_timeout() {
+    const TIMEOUT: Duration = Duration::from_millis(200);
+
+    let mut server_app = App::new();
+    server_app
+        .add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                handshake_timeout: TIMEOUT,
+                ..Default::default()
+            }),
+        ))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(TIMEOUT / 2))
+        .replicate::<DummyComponent>();
+
+    const CLIENT_ID: ClientId = ClientId::new(1);
+    let mut server = server_app.world_mut().resource_mut::<RepliconServer>();
+    server.set_running(true);
+    server_app
+        .world_mut()
+        .send_event(ServerEvent::ClientConnected {
+            client_id: CLIENT_ID,
+        });
+    server_app.world_mut().spawn((Replicated, DummyComponent));
+
+    server_app.update();
+
+    let mut server = server_app.world_mut().resource_mut::<RepliconServer>();
+    assert_eq!(
+        server.drain_sent().count(),
+        0,
+        "replication shouldn't be sent before the handshake"
+    );
+
+    // Send a malformed acknowledgment.
+    server.insert_received(CLIENT_ID, ReplicationChannel::Init, vec![0]);
+
+    server_app.update();
+
+    let mut server = server_app.world_mut().resource_mut::<RepliconServer>();
+    assert_eq!(server.drain_disconnects().count(), 0);
+    let violations = server_app.world().resource::<Events<ProtocolViolation>>();
+    assert!(
+        violations.is_empty(),
+        "messages before the handshake should be ignored"
+    );
+
+    server_app.update();
+
+    let mut server = server_app.world_mut().resource_mut::<RepliconServer>();
+    assert_eq!(server.drain_disconnects().collect::<Vec<_>>(), [CLIENT_ID]);
+}
+
+#[test]
+fn type_hash_ids() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins
+                .set(RepliconCorePlugin {
+                    id_policy: IdPolicy::TypeHash,
+                })
+                .set(ServerPlugin {
+                    tick_policy: TickPolicy::EveryFrame,
+                    ..Default::default()
+                }),
+        ));
+    }
+
+    // Register in different order and with an additional server-only event.
+    server_app
+        .replicate::<DummyComponent>()
+        .replicate::<BoolComponent>()
+        .add_server_event::<ServerOnlyEvent>(ChannelKind::Ordered)
+        .add_server_event::<DummyEvent>(ChannelKind::Ordered)
+        .add_client_event::<ClientDummyEvent>(ChannelKind::Ordered);
+    client_app
+        .add_client_event::<ClientDummyEvent>(ChannelKind::Ordered)
+        .add_server_event::<DummyEvent>(ChannelKind::Ordered)
+        .replicate::<BoolComponent>()
+        .replicate::<DummyComponent>();
+
+    server_app.connect_client(&mut client_app);
+
+    let status = client_app.world().resource::<ProtocolStatus>();
+    assert_eq!(*status, ProtocolStatus::Verified);
+
+    server_app
+        .world_mut()
+        .spawn((Replicated, DummyComponent, BoolComponent(true)));
+    server_app.world_mut().send_event(ToClients {
+        mode: SendMode::Broadcast,
+        event: ServerOnlyEvent,
+    });
+    server_app.world_mut().send_event(ToClients {
+        mode: SendMode::Broadcast,
+        event: DummyEvent,
+    });
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let mut components = client_app
+        .world_mut()
+        .query::<(&DummyComponent, &BoolComponent)>();
+    let (_, bool_component) = components.single(client_app.world());
+    assert!(bool_component.0);
+
+    let dummy_events = client_app.world().resource::<Events<DummyEvent>>();
+    assert_eq!(dummy_events.len(), 1);
+
+    client_app.world_mut().send_event(ClientDummyEvent);
+
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    server_app.update();
+
+    let client_events = server_app
+        .world()
+        .resource::<Events<FromClient<ClientDummyEvent>>>();
+    assert_eq!(client_events.len(), 1);
+}
+
+#[test]
+#[should_panic]
+fn duplicate_type_hash_event() {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        RepliconPlugins.set(RepliconCorePlugin {
+            id_policy: IdPolicy::TypeHash,
+        }),
+    ))
+    .add_server_event::<DummyEvent>(ChannelKind::Ordered)
+    .add_server_event::<DummyEvent>(ChannelKind::Unordered);
+}
+
 #[test]
 fn client_cleanup_on_disconnect() {
     let mut app = App::new();
@@ -263,8 +492,20 @@ fn diagnostics() {
     assert_eq!(stats.mappings, 1);
     assert_eq!(stats.despawns, 1);
     assert_eq!(stats.messages, 2);
-    assert_eq!(stats.bytes, 33);
+    assert_eq!(stats.bytes, 28);
 }
 
 #[derive(Component, Deserialize, Serialize)]
 struct DummyComponent;
+
+#[derive(Component, Deserialize, Serialize)]
+struct BoolComponent(bool);
+
+#[derive(Event, Deserialize, Serialize)]
+struct DummyEvent;
+
+#[derive(Event, Deserialize, Serialize)]
+struct ClientDummyEvent;
+
+#[derive(Event, Deserialize, Serialize)]
+struct ServerOnlyEvent;
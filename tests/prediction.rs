@@ -0,0 +1,187 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_replicon::{
+    client::{confirm_history::ConfirmHistory, prediction::*},
+    prelude::*,
+    test_app::ServerTestAppExt,
+};
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn rollback() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<Counter>();
+    }
+    client_app
+        .add_plugins(PredictionPlugin {
+            tick_policy: TickPolicy::EveryFrame,
+        })
+        .predict::<Counter>()
+        .add_systems(PredictionSchedule, increment);
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app.world_mut().spawn((Replicated, Counter(0))).id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let client_entity = client_app
+        .world_mut()
+        .query_filtered::<Entity, With<Counter>>()
+        .single(client_app.world());
+    client_app
+        .world_mut()
+        .entity_mut(client_entity)
+        .insert(Predicted);
+
+    for _ in 0..3 {
+        client_app.update();
+    }
+
+    let counter = client_app.world().get::<Counter>(client_entity).unwrap();
+    assert_eq!(counter.0, 3, "should be simulated locally");
+
+    server_app
+        .world_mut()
+        .get_mut::<Counter>(server_entity)
+        .unwrap()
+        .0 = 100;
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let confirmed_tick = client_app
+        .world()
+        .get::<ConfirmHistory>(client_entity)
+        .unwrap()
+        .last_tick();
+    let prediction_tick = **client_app.world().resource::<PredictionTick>();
+    let history = client_app
+        .world()
+        .get::<PredictionHistory<Counter>>(client_entity)
+        .unwrap();
+    assert_eq!(history.confirmed(), (confirmed_tick, &Counter(100)));
+
+    let counter = client_app.world().get::<Counter>(client_entity).unwrap();
+    assert_eq!(
+        counter.0,
+        100 + (prediction_tick - confirmed_tick),
+        "should be resimulated from the confirmed value"
+    );
+}
+
+#[test]
+fn different_rates() {
+    const TICK_RATE: u16 = 10;
+    const FRAMES_PER_TICK: u32 = 4;
+    let tick_time = Duration::from_millis(1000 / TICK_RATE as u64);
+
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::MaxTickRate(TICK_RATE),
+                ..Default::default()
+            }),
+        ))
+        .replicate::<Counter>();
+    }
+    server_app.insert_resource(TimeUpdateStrategy::ManualDuration(tick_time));
+    client_app
+        .insert_resource(TimeUpdateStrategy::ManualDuration(
+            tick_time / FRAMES_PER_TICK,
+        ))
+        .add_plugins(PredictionPlugin {
+            tick_policy: TickPolicy::MaxTickRate(TICK_RATE),
+        })
+        .predict::<Counter>()
+        .add_systems(PredictionSchedule, increment);
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app.world_mut().spawn((Replicated, Counter(0))).id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let client_entity = client_app
+        .world_mut()
+        .query_filtered::<Entity, With<Counter>>()
+        .single(client_app.world());
+    client_app
+        .world_mut()
+        .entity_mut(client_entity)
+        .insert(Predicted);
+
+    let start_tick = **client_app.world().resource::<PredictionTick>();
+    for _ in 0..3 * FRAMES_PER_TICK {
+        client_app.update();
+    }
+
+    let prediction_tick = **client_app.world().resource::<PredictionTick>();
+    assert_eq!(
+        prediction_tick - start_tick,
+        3,
+        "tick should advance at the server rate"
+    );
+    let counter = client_app.world().get::<Counter>(client_entity).unwrap();
+    assert_eq!(counter.0, 3, "should be simulated once per tick");
+
+    // Simulate the same ticks on server.
+    for _ in 0..3 {
+        server_app
+            .world_mut()
+            .get_mut::<Counter>(server_entity)
+            .unwrap()
+            .0 += 1;
+        server_app.update();
+    }
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let confirmed_tick = client_app
+        .world()
+        .get::<ConfirmHistory>(client_entity)
+        .unwrap()
+        .last_tick();
+    let history = client_app
+        .world()
+        .get::<PredictionHistory<Counter>>(client_entity)
+        .unwrap();
+    assert_eq!(history.confirmed(), (confirmed_tick, &Counter(3)));
+
+    let prediction_tick = **client_app.world().resource::<PredictionTick>();
+    let counter = client_app.world().get::<Counter>(client_entity).unwrap();
+    assert_eq!(
+        counter.0,
+        3 + (prediction_tick - confirmed_tick),
+        "should continue from the confirmed value"
+    );
+}
+
+fn increment(mut counters: Query<&mut Counter, With<Predicted>>) {
+    for mut counter in &mut counters {
+        counter.0 += 1;
+    }
+}
+
+#[derive(Component, Deserialize, Serialize, Clone, Debug, PartialEq)]
+struct Counter(u32);
@@ -0,0 +1,204 @@
+use bevy::prelude::*;
+use bevy_replicon::{
+    client::server_entity_map::ServerEntityMap, prelude::*, test_app::ServerTestAppExt,
+};
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn without_budget() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<DummyComponent>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entities = spawn_entities(&mut server_app, &mut client_app, [1.0, 2.0]);
+    change_components(&mut server_app, &server_entities, 1);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    assert_eq!(client_values(&client_app, &server_entities), [1, 1]);
+}
+
+#[test]
+fn budget() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<DummyComponent>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entities = spawn_entities(&mut server_app, &mut client_app, [1.0, 2.0]);
+
+    let client = client_app.world().resource::<RepliconClient>();
+    let client_id = client.id().unwrap();
+    let mut connected_clients = server_app.world_mut().resource_mut::<ConnectedClients>();
+    connected_clients
+        .client_mut(client_id)
+        .set_bandwidth_budget(Some(1));
+
+    change_components(&mut server_app, &server_entities, 1);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    assert_eq!(
+        client_values(&client_app, &server_entities),
+        [0, 1],
+        "only the entity with the highest priority should fit"
+    );
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    assert_eq!(
+        client_values(&client_app, &server_entities),
+        [1, 1],
+        "deferred entity should be sent on the next tick"
+    );
+}
+
+#[test]
+fn accumulation() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<DummyComponent>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entities = spawn_entities(&mut server_app, &mut client_app, [1.0, 1.5]);
+
+    let client = client_app.world().resource::<RepliconClient>();
+    let client_id = client.id().unwrap();
+    let mut connected_clients = server_app.world_mut().resource_mut::<ConnectedClients>();
+    connected_clients
+        .client_mut(client_id)
+        .set_bandwidth_budget(Some(1));
+
+    // Change both entities every tick, so the starved entity should win
+    // after accumulating enough priority.
+    for value in 1..=2 {
+        change_components(&mut server_app, &server_entities, value);
+
+        server_app.update();
+        server_app.exchange_with_client(&mut client_app);
+        client_app.update();
+        server_app.exchange_with_client(&mut client_app);
+    }
+
+    assert_eq!(client_values(&client_app, &server_entities), [2, 1]);
+}
+
+#[test]
+fn client_priority() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<DummyComponent>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entities = spawn_entities(&mut server_app, &mut client_app, [1.0, 2.0]);
+
+    let client = client_app.world().resource::<RepliconClient>();
+    let client_id = client.id().unwrap();
+    let mut connected_clients = server_app.world_mut().resource_mut::<ConnectedClients>();
+    let client = connected_clients.client_mut(client_id);
+    client.set_bandwidth_budget(Some(1));
+    client.priority_mut().set_priority(server_entities[0], 4.0);
+
+    change_components(&mut server_app, &server_entities, 1);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    assert_eq!(client_values(&client_app, &server_entities), [1, 0]);
+}
+
+/// Spawns replicated entities with the specified priorities and replicates them to the client.
+fn spawn_entities<const N: usize>(
+    server_app: &mut App,
+    client_app: &mut App,
+    priorities: [f32; N],
+) -> [Entity; N] {
+    let server_entities = priorities.map(|priority| {
+        server_app
+            .world_mut()
+            .spawn((Replicated, ReplicationPriority(priority), DummyComponent(0)))
+            .id()
+    });
+
+    server_app.update();
+    server_app.exchange_with_client(client_app);
+    client_app.update();
+    server_app.exchange_with_client(client_app);
+
+    server_entities
+}
+
+fn change_components(server_app: &mut App, server_entities: &[Entity], value: u8) {
+    for &entity in server_entities {
+        let mut component = server_app
+            .world_mut()
+            .get_mut::<DummyComponent>(entity)
+            .unwrap();
+        component.0 = value;
+    }
+}
+
+fn client_values<const N: usize>(client_app: &App, server_entities: &[Entity; N]) -> [u8; N] {
+    let entity_map = client_app.world().resource::<ServerEntityMap>();
+    server_entities.map(|server_entity| {
+        let client_entity = entity_map.to_client()[&server_entity];
+        client_app
+            .world()
+            .get::<DummyComponent>(client_entity)
+            .unwrap()
+            .0
+    })
+}
+
+#[derive(Component, Deserialize, Serialize)]
+struct DummyComponent(u8);
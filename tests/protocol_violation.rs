@@ -0,0 +1,101 @@
+use bevy::{ecs::event::Events, prelude::*};
+use bevy_replicon::{core::channels::ReplicationChannel, prelude::*, test_app::ServerTestAppExt};
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn invalid_ack() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                violation_policy: ViolationPolicy::Disconnect,
+                ..Default::default()
+            }),
+        ));
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let client = client_app.world().resource::<RepliconClient>();
+    let client_id = client.id().unwrap();
+    let mut server = server_app.world_mut().resource_mut::<RepliconServer>();
+    server.insert_received(client_id, ReplicationChannel::Init, vec![0]);
+
+    server_app.update();
+
+    let mut violations = server_app
+        .world_mut()
+        .resource_mut::<Events<ProtocolViolation>>();
+    let [violation] = &violations.drain().collect::<Vec<_>>()[..] else {
+        panic!("server should emit a single violation");
+    };
+    assert_eq!(violation.client_id, client_id);
+    assert!(matches!(violation.kind, ViolationKind::InvalidAck(_)));
+
+    let mut server = server_app.world_mut().resource_mut::<RepliconServer>();
+    let disconnects: Vec<_> = server.drain_disconnects().collect();
+    assert_eq!(disconnects, [client_id]);
+}
+
+#[test]
+fn ack_from_unknown_client() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, RepliconPlugins));
+    app.update();
+
+    let mut server = app.world_mut().resource_mut::<RepliconServer>();
+    server.set_running(true);
+    server.insert_received(ClientId::new(1), ReplicationChannel::Init, vec![0, 0]);
+
+    app.update();
+
+    let violations = app.world().resource::<Events<ProtocolViolation>>();
+    assert!(violations.is_empty());
+}
+
+#[test]
+fn invalid_event() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, RepliconPlugins))
+            .add_client_event::<DummyEvent>(ChannelKind::Ordered);
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let client = client_app.world().resource::<RepliconClient>();
+    let client_id = client.id().unwrap();
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let channel_id = channels.client_channels().len() as u8 - 1;
+    let mut server = server_app.world_mut().resource_mut::<RepliconServer>();
+    server.insert_received(client_id, channel_id, Vec::new());
+
+    server_app.update();
+
+    let client_events = server_app
+        .world()
+        .resource::<Events<FromClient<DummyEvent>>>();
+    assert!(client_events.is_empty());
+
+    let mut violations = server_app
+        .world_mut()
+        .resource_mut::<Events<ProtocolViolation>>();
+    let [violation] = &violations.drain().collect::<Vec<_>>()[..] else {
+        panic!("server should emit a single violation");
+    };
+    assert_eq!(violation.client_id, client_id);
+    assert!(matches!(violation.kind, ViolationKind::InvalidEvent { .. }));
+
+    let mut server = server_app.world_mut().resource_mut::<RepliconServer>();
+    assert_eq!(
+        server.drain_disconnects().count(),
+        0,
+        "client shouldn't be disconnected with the default policy"
+    );
+}
+
+#[derive(Deserialize, Event, Serialize)]
+struct DummyEvent(u32);
@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+use bevy_replicon::{
+    client::{MessageKind, ReceiveError, ReceiveErrorPolicy},
+    core::channels::ReplicationChannel,
+    prelude::*,
+    test_app::ServerTestAppExt,
+};
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn skip() {
+    let (mut server_app, mut client_app) = setup();
+    client_app
+        .insert_resource(ReceiveErrorPolicy::Skip)
+        .init_resource::<ReceivedErrors>()
+        .add_systems(Update, collect_errors);
+
+    client_app
+        .world_mut()
+        .resource_mut::<RepliconClient>()
+        .insert_received(ReplicationChannel::Init, INVALID_MESSAGE);
+
+    client_app.update();
+
+    let errors = client_app.world().resource::<ReceivedErrors>();
+    assert_eq!(errors.0, [MessageKind::Init]);
+    assert!(!client_app
+        .world_mut()
+        .resource_mut::<RepliconClient>()
+        .take_disconnect_request());
+
+    server_app.world_mut().spawn((Replicated, DummyComponent));
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let components = client_app
+        .world_mut()
+        .query::<&DummyComponent>()
+        .iter(client_app.world())
+        .count();
+    assert_eq!(components, 1, "client should keep receiving messages");
+}
+
+#[test]
+fn disconnect_by_default() {
+    let (_, mut client_app) = setup();
+
+    client_app
+        .world_mut()
+        .resource_mut::<RepliconClient>()
+        .insert_received(ReplicationChannel::Init, INVALID_MESSAGE);
+
+    client_app.update();
+
+    assert!(client_app
+        .world_mut()
+        .resource_mut::<RepliconClient>()
+        .take_disconnect_request());
+}
+
+#[test]
+#[cfg_attr(debug_assertions, should_panic)]
+fn panic_in_debug() {
+    let (_, mut client_app) = setup();
+    client_app.insert_resource(ReceiveErrorPolicy::PanicInDebug);
+
+    client_app
+        .world_mut()
+        .resource_mut::<RepliconClient>()
+        .insert_received(ReplicationChannel::Init, INVALID_MESSAGE);
+
+    client_app.update();
+}
+
+/// Valid flags followed by a truncated tick.
+const INVALID_MESSAGE: &[u8] = &[0, 1];
+
+fn setup() -> (App, App) {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<DummyComponent>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    (server_app, client_app)
+}
+
+fn collect_errors(mut errors: EventReader<ReceiveError>, mut received: ResMut<ReceivedErrors>) {
+    received.0.extend(errors.read().map(|error| error.message));
+}
+
+#[derive(Resource, Default)]
+struct ReceivedErrors(Vec<MessageKind>);
+
+#[derive(Component, Deserialize, Serialize)]
+struct DummyComponent;
@@ -0,0 +1,218 @@
+use bevy::prelude::*;
+use bevy_replicon::{
+    client::{
+        repair::{RepairDespawn, RepairPlugin},
+        server_entity_map::ServerEntityMap,
+    },
+    prelude::*,
+    test_app::ServerTestAppExt,
+};
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn reconnect() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<DummyComponent>();
+    }
+    client_app
+        .add_plugins(RepairPlugin)
+        .init_resource::<RepairDespawns>()
+        .add_systems(Update, collect_despawns);
+
+    server_app.connect_client(&mut client_app);
+
+    let kept_entity = server_app
+        .world_mut()
+        .spawn((Replicated, DummyComponent(0)))
+        .id();
+    let despawned_entity = server_app.world_mut().spawn(Replicated).id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let entity_map = client_app.world().resource::<ServerEntityMap>();
+    let client_kept = entity_map.to_client()[&kept_entity];
+    let client_despawned = entity_map.to_client()[&despawned_entity];
+
+    server_app.disconnect_client(&mut client_app);
+
+    assert!(
+        client_app.world().get_entity(client_kept).is_some(),
+        "entities should be preserved after disconnect"
+    );
+
+    server_app.world_mut().despawn(despawned_entity);
+    server_app
+        .world_mut()
+        .get_mut::<DummyComponent>(kept_entity)
+        .unwrap()
+        .0 = 1;
+    server_app.update();
+
+    server_app.connect_client(&mut client_app);
+
+    let entity_map = client_app.world().resource::<ServerEntityMap>();
+    assert_eq!(
+        entity_map.to_client().get(&kept_entity),
+        Some(&client_kept),
+        "confirmed entity should keep its mapping"
+    );
+    assert!(!entity_map.to_client().contains_key(&despawned_entity));
+
+    let component = client_app
+        .world()
+        .get::<DummyComponent>(client_kept)
+        .unwrap();
+    assert_eq!(
+        component.0, 1,
+        "confirmed entity should receive the current state"
+    );
+    assert!(client_app.world().get_entity(client_despawned).is_none());
+
+    let despawns = client_app.world().resource::<RepairDespawns>();
+    let [despawn] = despawns.0[..] else {
+        panic!(
+            "only one entity should be despawned, but got {:?}",
+            despawns.0
+        );
+    };
+    assert_eq!(despawn.server_entity, despawned_entity);
+    assert_eq!(despawn.client_entity, client_despawned);
+}
+
+#[test]
+fn different_id() {
+    let mut server_app = App::new();
+    let mut client_app1 = App::new();
+    let mut client_app2 = App::new();
+    for app in [&mut server_app, &mut client_app1, &mut client_app2] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<DummyComponent>();
+    }
+    client_app1
+        .add_plugins(RepairPlugin)
+        .init_resource::<RepairDespawns>()
+        .add_systems(Update, collect_despawns);
+
+    server_app.connect_client(&mut client_app1);
+
+    server_app
+        .world_mut()
+        .spawn((Replicated, DummyComponent(0)));
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app1);
+    client_app1.update();
+
+    let old_entity = client_app1
+        .world_mut()
+        .query_filtered::<Entity, With<DummyComponent>>()
+        .single(client_app1.world());
+
+    server_app.disconnect_client(&mut client_app1);
+
+    // Take the previous ID.
+    server_app.connect_client(&mut client_app2);
+    server_app.connect_client(&mut client_app1);
+
+    assert!(
+        client_app1.world().get_entity(old_entity).is_none(),
+        "preserved entities should be despawned for a different client ID"
+    );
+    let despawns = client_app1.world().resource::<RepairDespawns>();
+    assert_eq!(despawns.0.len(), 1);
+
+    let new_entity = client_app1
+        .world_mut()
+        .query_filtered::<Entity, With<DummyComponent>>()
+        .single(client_app1.world());
+    assert_ne!(new_entity, old_entity);
+}
+
+#[test]
+fn component_removal() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<DummyComponent>()
+        .replicate::<RemovedComponent>();
+    }
+    client_app.add_plugins(RepairPlugin);
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app
+        .world_mut()
+        .spawn((Replicated, DummyComponent(0), RemovedComponent))
+        .id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let client_entity =
+        client_app.world().resource::<ServerEntityMap>().to_client()[&server_entity];
+    assert!(client_app
+        .world()
+        .entity(client_entity)
+        .contains::<RemovedComponent>());
+
+    server_app.disconnect_client(&mut client_app);
+
+    server_app
+        .world_mut()
+        .entity_mut(server_entity)
+        .remove::<RemovedComponent>();
+    server_app.update();
+
+    server_app.connect_client(&mut client_app);
+
+    let client_entity = client_app.world().entity(client_entity);
+    assert!(
+        client_entity.contains::<DummyComponent>(),
+        "confirmed component should be kept"
+    );
+    assert!(
+        !client_entity.contains::<RemovedComponent>(),
+        "component removed during the disconnect should be removed"
+    );
+}
+
+fn collect_despawns(
+    mut repair_despawns: EventReader<RepairDespawn>,
+    mut despawns: ResMut<RepairDespawns>,
+) {
+    despawns.0.extend(repair_despawns.read().copied());
+}
+
+#[derive(Resource, Default)]
+struct RepairDespawns(Vec<RepairDespawn>);
+
+#[derive(Component, Deserialize, Serialize)]
+struct DummyComponent(u32);
+
+#[derive(Component, Deserialize, Serialize)]
+struct RemovedComponent;
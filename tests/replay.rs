@@ -0,0 +1,290 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_replicon::{
+    client::replay::{Replay, ReplayPlugin},
+    core::{recording::Recording, replication_registry::rule_fns::RuleFns},
+    prelude::*,
+    server::recorder::{RecordTarget, RecorderPlugin, ReplicationRecorder},
+};
+use serde::{Deserialize, Serialize};
+
+const FRAME_TIME: Duration = Duration::from_millis(100);
+const UPDATES_COUNT: u32 = 5;
+
+#[test]
+fn record_and_replay() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        setup_app(app);
+    }
+
+    let recording = record(&mut server_app);
+    assert!(recording.messages().iter().any(|message| message.keyframe));
+
+    server_app.update();
+    assert!(
+        server_app.world().resource::<ConnectedClients>().is_empty(),
+        "virtual clients should be removed after stop"
+    );
+
+    let mut bytes = Vec::new();
+    recording.write(&mut bytes).unwrap();
+    let recording = Recording::read(&*bytes).unwrap();
+
+    client_app.insert_resource(Replay::new(recording));
+    while !client_app.world().resource::<Replay>().is_finished() {
+        client_app.update();
+    }
+
+    assert_eq!(counter(&mut client_app), Some(UPDATES_COUNT - 1));
+}
+
+#[test]
+fn pause() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        setup_app(app);
+    }
+
+    let recording = record(&mut server_app);
+    let mut replay = Replay::new(recording);
+    replay.pause();
+    assert!(replay.is_paused());
+    client_app.insert_resource(replay);
+
+    client_app.update();
+    client_app.update();
+
+    let replay = client_app.world().resource::<Replay>();
+    assert_eq!(replay.position(), Duration::ZERO);
+    assert_eq!(
+        counter(&mut client_app),
+        None,
+        "the observer receives the first message on the next frame after recording started"
+    );
+
+    let mut replay = client_app.world_mut().resource_mut::<Replay>();
+    replay.resume();
+    assert!(!replay.is_paused());
+    client_app.update();
+
+    let replay = client_app.world().resource::<Replay>();
+    assert_eq!(replay.position(), FRAME_TIME);
+    assert_eq!(counter(&mut client_app), Some(1));
+}
+
+#[test]
+fn speed() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        setup_app(app);
+    }
+
+    let recording = record(&mut server_app);
+    let mut replay = Replay::new(recording);
+    replay.set_speed(2.0);
+    assert_eq!(replay.speed(), 2.0);
+    client_app.insert_resource(replay);
+
+    // The first frame has zero delta.
+    client_app.update();
+    client_app.update();
+
+    let replay = client_app.world().resource::<Replay>();
+    assert_eq!(replay.position(), FRAME_TIME * 2);
+    assert_eq!(
+        counter(&mut client_app),
+        Some(2),
+        "all messages up to the position should be applied"
+    );
+
+    client_app
+        .world_mut()
+        .resource_mut::<Replay>()
+        .set_speed(0.0);
+    client_app.update();
+
+    let replay = client_app.world().resource::<Replay>();
+    assert_eq!(replay.position(), FRAME_TIME * 2);
+}
+
+#[test]
+fn seek() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        setup_app(app);
+    }
+
+    let recording = record(&mut server_app);
+    let duration = recording.duration();
+    let mut replay = Replay::new(recording);
+    replay.pause();
+    client_app.insert_resource(replay);
+
+    let mut replay = client_app.world_mut().resource_mut::<Replay>();
+    replay.seek(duration);
+    assert_eq!(replay.position(), duration);
+    client_app.update();
+
+    assert_eq!(
+        counter(&mut client_app),
+        Some(UPDATES_COUNT - 1),
+        "state should be restored from the keyframe and the following messages"
+    );
+
+    client_app
+        .world_mut()
+        .resource_mut::<Replay>()
+        .seek(FRAME_TIME * 3);
+    client_app.update();
+
+    assert_eq!(
+        counter(&mut client_app),
+        Some(3),
+        "seeking backward should restore the earlier state"
+    );
+
+    client_app
+        .world_mut()
+        .resource_mut::<Replay>()
+        .seek(Duration::ZERO);
+    client_app.update();
+
+    assert_eq!(
+        counter(&mut client_app),
+        None,
+        "entity should be despawned when seeking before its spawn"
+    );
+}
+
+#[test]
+fn seek_with_big_keyframe() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        setup_app(app);
+        app.replicate::<Payload>();
+    }
+
+    const ENTITIES_COUNT: usize = 50;
+    server_app
+        .world_mut()
+        .spawn_batch(vec![(Replicated, Payload(vec![0; 100])); ENTITIES_COUNT]);
+
+    let recording = record(&mut server_app);
+    let duration = recording.duration();
+    let mut replay = Replay::new(recording);
+    replay.pause();
+    replay.seek(duration);
+    client_app.insert_resource(replay);
+    client_app.update();
+
+    let payloads = client_app
+        .world_mut()
+        .query::<&Payload>()
+        .iter(client_app.world())
+        .count();
+    assert_eq!(
+        payloads, ENTITIES_COUNT,
+        "keyframe should contain the whole world regardless of its size"
+    );
+    assert_eq!(counter(&mut client_app), Some(UPDATES_COUNT - 1));
+}
+
+#[test]
+fn delta_compression() {
+    let mut server_app = App::new();
+    server_app
+        .add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+            RecorderPlugin::default(),
+        ))
+        .replicate_with(RuleFns::<Counter>::default().with_delta_compression());
+
+    server_app
+        .world_mut()
+        .resource_mut::<RepliconServer>()
+        .set_running(true);
+
+    server_app
+        .world_mut()
+        .resource_mut::<ReplicationRecorder>()
+        .start(RecordTarget::Observer);
+
+    server_app.update();
+
+    let recorder = server_app.world().resource::<ReplicationRecorder>();
+    assert!(
+        !recorder.is_recording(),
+        "recording with delta compression should be refused"
+    );
+    assert!(recorder.recording().messages().is_empty());
+    assert!(server_app.world().resource::<ConnectedClients>().is_empty());
+}
+
+fn setup_app(app: &mut App) {
+    app.add_plugins((
+        MinimalPlugins,
+        RepliconPlugins.set(ServerPlugin {
+            tick_policy: TickPolicy::EveryFrame,
+            ..Default::default()
+        }),
+        RecorderPlugin {
+            keyframe_interval: Some(FRAME_TIME * 2),
+        },
+        ReplayPlugin,
+    ))
+    .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME_TIME))
+    .replicate::<Counter>();
+}
+
+/// Records [`UPDATES_COUNT`] frames, each with a new counter value.
+fn record(server_app: &mut App) -> Recording {
+    server_app
+        .world_mut()
+        .resource_mut::<RepliconServer>()
+        .set_running(true);
+    let server_entity = server_app.world_mut().spawn((Replicated, Counter(0))).id();
+
+    server_app
+        .world_mut()
+        .resource_mut::<ReplicationRecorder>()
+        .start(RecordTarget::Observer);
+
+    for value in 0..UPDATES_COUNT {
+        server_app
+            .world_mut()
+            .get_mut::<Counter>(server_entity)
+            .unwrap()
+            .0 = value;
+        server_app.update();
+    }
+
+    server_app
+        .world_mut()
+        .resource_mut::<ReplicationRecorder>()
+        .stop()
+}
+
+fn counter(app: &mut App) -> Option<u32> {
+    app.world_mut()
+        .query::<&Counter>()
+        .get_single(app.world())
+        .ok()
+        .map(|counter| counter.0)
+}
+
+#[derive(Component, Deserialize, Serialize)]
+struct Counter(u32);
+
+#[derive(Component, Clone, Deserialize, Serialize)]
+struct Payload(Vec<u8>);
@@ -0,0 +1,246 @@
+use bevy::{ecs::entity::MapEntities, prelude::*, state::app::StatesPlugin};
+use bevy_replicon::{prelude::*, test_app::ServerTestAppExt};
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn insertion() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate_resource::<DummyResource>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    server_app.world_mut().insert_resource(DummyResource(1));
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let resource = client_app.world().resource::<DummyResource>();
+    assert_eq!(resource.0, 1);
+}
+
+#[test]
+fn change() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate_resource::<DummyResource>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    server_app.world_mut().insert_resource(DummyResource(0));
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    // Update without changes shouldn't send anything.
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.world_mut().resource_mut::<DummyResource>().0 = 2;
+    client_app.update();
+    assert_eq!(client_app.world().resource::<DummyResource>().0, 2);
+
+    server_app.world_mut().resource_mut::<DummyResource>().0 = 1;
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let resource = client_app.world().resource::<DummyResource>();
+    assert_eq!(resource.0, 1);
+}
+
+#[test]
+fn removal() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate_resource::<DummyResource>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    server_app.world_mut().insert_resource(DummyResource(0));
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    assert!(client_app.world().contains_resource::<DummyResource>());
+
+    server_app.world_mut().remove_resource::<DummyResource>();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    assert!(!client_app.world().contains_resource::<DummyResource>());
+}
+
+#[test]
+fn after_connection() {
+    let mut server_app = App::new();
+    let mut client_app1 = App::new();
+    let mut client_app2 = App::new();
+    for app in [&mut server_app, &mut client_app1, &mut client_app2] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate_resource::<DummyResource>();
+    }
+
+    server_app.connect_client(&mut client_app1);
+
+    server_app.world_mut().insert_resource(DummyResource(1));
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app1);
+    client_app1.update();
+    server_app.exchange_with_client(&mut client_app1);
+
+    server_app.connect_client(&mut client_app2);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app2);
+    client_app2.update();
+
+    let resource = client_app2.world().resource::<DummyResource>();
+    assert_eq!(resource.0, 1, "resource should be sent to the new client");
+}
+
+#[test]
+fn mapped() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate_resource_mapped::<MappedResource>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app.world_mut().spawn(Replicated).id();
+    server_app
+        .world_mut()
+        .insert_resource(MappedResource(server_entity));
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let client_entity = client_app
+        .world_mut()
+        .query_filtered::<Entity, With<Replicated>>()
+        .single(client_app.world());
+
+    let resource = client_app.world().resource::<MappedResource>();
+    assert_eq!(resource.0, client_entity);
+}
+
+#[test]
+fn state() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .init_state::<DummyState>()
+        .replicate_state::<DummyState>()
+        .replicate::<DummyComponent>();
+    }
+    client_app.add_systems(
+        OnEnter(DummyState::Enabled),
+        |mut commands: Commands, components: Query<(), With<DummyComponent>>| {
+            assert_eq!(
+                components.iter().len(),
+                1,
+                "entities from the same tick should be applied before the transition"
+            );
+            commands.insert_resource(DummyResource(0));
+        },
+    );
+
+    server_app.connect_client(&mut client_app);
+
+    server_app.world_mut().spawn((Replicated, DummyComponent));
+    server_app
+        .world_mut()
+        .resource_mut::<NextState<DummyState>>()
+        .set(DummyState::Enabled);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let state = client_app.world().resource::<State<DummyState>>();
+    assert_eq!(*state.get(), DummyState::Enabled);
+    assert!(
+        client_app.world().contains_resource::<DummyResource>(),
+        "transition should be triggered"
+    );
+}
+
+#[derive(Resource, Deserialize, Serialize)]
+struct DummyResource(u32);
+
+#[derive(Component, Deserialize, Serialize)]
+struct DummyComponent;
+
+#[derive(States, Default, Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+enum DummyState {
+    #[default]
+    Disabled,
+    Enabled,
+}
+
+#[derive(Resource, Deserialize, Serialize)]
+struct MappedResource(Entity);
+
+impl MapEntities for MappedResource {
+    fn map_entities<T: EntityMapper>(&mut self, mapper: &mut T) {
+        self.0 = mapper.map_entity(self.0);
+    }
+}
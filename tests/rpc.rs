@@ -0,0 +1,165 @@
+use std::time::Duration;
+
+use bevy::{ecs::system::RunSystemOnce, prelude::*, time::TimeUpdateStrategy};
+use bevy_replicon::{prelude::*, test_app::ServerTestAppExt};
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn response() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .add_rpc::<DummyRequest, DummyResponse>(ChannelKind::Ordered);
+    }
+    server_app.add_systems(Update, reply);
+
+    server_app.connect_client(&mut client_app);
+
+    let entity = client_app.world_mut().run_system_once(
+        |mut requests: Requests<DummyRequest, DummyResponse>| requests.send(DummyRequest(1)),
+    );
+
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let result = client_app
+        .world()
+        .get::<RpcResult<DummyResponse>>(entity)
+        .unwrap();
+    assert!(matches!(result, RpcResult::Response(DummyResponse(2))));
+}
+
+#[test]
+fn timeout() {
+    const TIMEOUT: Duration = Duration::from_millis(100);
+
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(TIMEOUT / 2))
+        .add_rpc::<DummyRequest, DummyResponse>(ChannelKind::Ordered);
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let entity = client_app.world_mut().run_system_once(
+        |mut requests: Requests<DummyRequest, DummyResponse>| {
+            requests.send_with_timeout(DummyRequest(1), TIMEOUT)
+        },
+    );
+
+    client_app.update();
+    assert!(
+        client_app
+            .world()
+            .get::<RpcResult<DummyResponse>>(entity)
+            .is_none(),
+        "request shouldn't be resolved before the timeout"
+    );
+
+    client_app.update();
+    client_app.update();
+
+    let result = client_app
+        .world()
+        .get::<RpcResult<DummyResponse>>(entity)
+        .unwrap();
+    assert!(matches!(result, RpcResult::Timeout));
+}
+
+#[test]
+fn disconnect() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .add_rpc::<DummyRequest, DummyResponse>(ChannelKind::Ordered);
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let entity = client_app.world_mut().run_system_once(
+        |mut requests: Requests<DummyRequest, DummyResponse>| requests.send(DummyRequest(1)),
+    );
+
+    client_app.update();
+    server_app.disconnect_client(&mut client_app);
+
+    let result = client_app
+        .world()
+        .get::<RpcResult<DummyResponse>>(entity)
+        .unwrap();
+    assert!(matches!(result, RpcResult::Timeout));
+}
+
+#[test]
+fn local() {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        RepliconPlugins.set(ServerPlugin {
+            tick_policy: TickPolicy::EveryFrame,
+            ..Default::default()
+        }),
+    ))
+    .add_rpc::<DummyRequest, DummyResponse>(ChannelKind::Ordered)
+    .add_systems(Update, reply);
+
+    app.world_mut()
+        .resource_mut::<RepliconServer>()
+        .set_running(true);
+
+    let entity =
+        app.world_mut()
+            .run_system_once(|mut requests: Requests<DummyRequest, DummyResponse>| {
+                requests.send(DummyRequest(1))
+            });
+
+    app.update();
+    app.update();
+    app.update();
+
+    let result = app.world().get::<RpcResult<DummyResponse>>(entity).unwrap();
+    assert!(matches!(result, RpcResult::Response(DummyResponse(2))));
+}
+
+fn reply(
+    mut requests: EventReader<ClientRequest<DummyRequest>>,
+    mut replies: EventWriter<Reply<DummyResponse>>,
+) {
+    for request in requests.read() {
+        replies.send(Reply {
+            token: request.token,
+            response: DummyResponse(request.request.0 + 1),
+        });
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct DummyRequest(u32);
+
+#[derive(Deserialize, Serialize)]
+struct DummyResponse(u32);
@@ -58,9 +58,16 @@ fn sending_receiving() {
         (SendMode::Direct(client_id), 1),
         (SendMode::BroadcastExcept(ClientId::SERVER), 1),
         (SendMode::BroadcastExcept(client_id), 0),
+        (SendMode::Multicast(vec![ClientId::SERVER, client_id]), 1),
+        (SendMode::Multicast(vec![ClientId::SERVER]), 0),
+        (SendMode::BroadcastExceptMany(vec![ClientId::SERVER]), 1),
+        (
+            SendMode::BroadcastExceptMany(vec![ClientId::SERVER, client_id]),
+            0,
+        ),
     ] {
         server_app.world_mut().send_event(ToClients {
-            mode,
+            mode: mode.clone(),
             event: DummyEvent,
         });
 
@@ -78,6 +85,94 @@ fn sending_receiving() {
     }
 }
 
+#[test]
+fn oversized() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .add_server_event::<VecEvent>(ChannelKind::Ordered);
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    const MAX_PAYLOAD_SIZE: usize = 100;
+    let client = client_app.world().resource::<RepliconClient>();
+    let client_id = client.id().unwrap();
+    let channels = server_app.world().resource::<RepliconChannels>();
+    let channel_id = (channels.server_channels().len() - 1) as u8;
+    server_app
+        .world_mut()
+        .resource_mut::<RepliconServer>()
+        .set_max_payload_size(client_id, channel_id, MAX_PAYLOAD_SIZE);
+
+    for (len, events_count) in [(MAX_PAYLOAD_SIZE / 2, 1), (MAX_PAYLOAD_SIZE, 0)] {
+        server_app.world_mut().send_event(ToClients {
+            mode: SendMode::Broadcast,
+            event: VecEvent(vec![0; len]),
+        });
+
+        server_app.update();
+        server_app.exchange_with_client(&mut client_app);
+        client_app.update();
+
+        let mut events = client_app.world_mut().resource_mut::<Events<VecEvent>>();
+        assert_eq!(events.drain().count(), events_count);
+    }
+}
+
+#[test]
+fn sending_to_visible() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                visibility_policy: VisibilityPolicy::Whitelist,
+                ..Default::default()
+            }),
+        ))
+        .add_server_event::<DummyEvent>(ChannelKind::Ordered);
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let client = client_app.world().resource::<RepliconClient>();
+    let client_id = client.id().unwrap();
+    let server_entity = server_app.world_mut().spawn(Replicated).id();
+
+    for (visible, events_count) in [(false, 0), (true, 1)] {
+        let mut connected_clients = server_app.world_mut().resource_mut::<ConnectedClients>();
+        let visibility = connected_clients.client_mut(client_id).visibility_mut();
+        visibility.set_visibility(server_entity, visible);
+
+        server_app.world_mut().send_event(ToClients {
+            mode: SendMode::Visible(server_entity),
+            event: DummyEvent,
+        });
+
+        server_app.update();
+        server_app.exchange_with_client(&mut client_app);
+        client_app.update();
+        server_app.exchange_with_client(&mut client_app);
+
+        let mut dummy_events = client_app.world_mut().resource_mut::<Events<DummyEvent>>();
+        assert_eq!(
+            dummy_events.drain().count(),
+            events_count,
+            "event should be emited {events_count} times when visibility is {visible}"
+        );
+    }
+}
+
 #[test]
 fn sending_receiving_and_mapping() {
     let mut server_app = App::new();
@@ -139,9 +234,17 @@ fn local_resending() {
         (SendMode::Direct(DUMMY_CLIENT_ID), 0),
         (SendMode::BroadcastExcept(ClientId::SERVER), 0),
         (SendMode::BroadcastExcept(DUMMY_CLIENT_ID), 1),
+        (
+            SendMode::Multicast(vec![ClientId::SERVER, DUMMY_CLIENT_ID]),
+            1,
+        ),
+        (SendMode::Multicast(vec![DUMMY_CLIENT_ID]), 0),
+        (SendMode::BroadcastExceptMany(vec![DUMMY_CLIENT_ID]), 1),
+        (SendMode::BroadcastExceptMany(vec![ClientId::SERVER]), 0),
+        (SendMode::Visible(Entity::PLACEHOLDER), 1),
     ] {
         app.world_mut().send_event(ToClients {
-            mode,
+            mode: mode.clone(),
             event: DummyEvent,
         });
 
@@ -274,6 +377,9 @@ struct DummyEvent;
 #[derive(Deserialize, Event, Serialize)]
 struct MappedEvent(Entity);
 
+#[derive(Deserialize, Event, Serialize)]
+struct VecEvent(Vec<u8>);
+
 impl MapEntities for MappedEvent {
     fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
         self.0 = entity_mapper.map_entity(self.0);
@@ -1,4 +1,4 @@
-use bevy::prelude::*;
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
 use bevy_replicon::{
     client::{confirm_history::ConfirmHistory, server_entity_map::ServerEntityMap},
     prelude::*,
@@ -147,6 +147,130 @@ fn before_connection() {
         .single(client_app.world());
 }
 
+#[test]
+fn initial_sync() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<DummyComponent>();
+    }
+
+    server_app.world_mut().spawn((Replicated, DummyComponent));
+
+    server_app.connect_client(&mut client_app);
+
+    assert!(
+        initial_sync_complete_on(&mut client_app),
+        "client should be notified even without a budget"
+    );
+    client_app
+        .world_mut()
+        .query_filtered::<(), (With<Replicated>, With<DummyComponent>)>()
+        .single(client_app.world());
+}
+
+#[test]
+fn initial_sync_in_empty_world() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<DummyComponent>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let client = client_app.world().resource::<RepliconClient>();
+    let client_id = client.id().unwrap();
+    let connected_clients = server_app.world().resource::<ConnectedClients>();
+    assert!(
+        !connected_clients.client(client_id).initial_sync_complete(),
+        "completion shouldn't be sent in a separate message without a budget"
+    );
+    assert!(!initial_sync_complete_on(&mut client_app));
+
+    server_app.world_mut().spawn((Replicated, DummyComponent));
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    assert!(
+        initial_sync_complete_on(&mut client_app),
+        "completion should be sent with the first init message"
+    );
+}
+
+#[test]
+fn initial_sync_with_budget() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                // Allow only one entity per tick.
+                initial_sync_budget: Some(1),
+                ..Default::default()
+            }),
+        ))
+        .replicate::<DummyComponent>();
+    }
+
+    const ENTITIES_COUNT: usize = 5;
+    for _ in 0..ENTITIES_COUNT {
+        server_app.world_mut().spawn((Replicated, DummyComponent));
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let mut replicated = client_app
+        .world_mut()
+        .query_filtered::<(), (With<Replicated>, With<DummyComponent>)>();
+    let mut previous_count = replicated.iter(client_app.world()).count();
+    assert!(previous_count < ENTITIES_COUNT);
+    assert!(!initial_sync_complete_on(&mut client_app));
+
+    for _ in 0..ENTITIES_COUNT {
+        server_app.update();
+        server_app.exchange_with_client(&mut client_app);
+        client_app.update();
+
+        let count = replicated.iter(client_app.world()).count();
+        if initial_sync_complete_on(&mut client_app) {
+            assert_eq!(count, ENTITIES_COUNT);
+            break;
+        }
+
+        assert_eq!(count, previous_count + 1, "entities should be streamed");
+        previous_count = count;
+    }
+
+    assert!(initial_sync_complete_on(&mut client_app));
+
+    let client_id = client_app
+        .world()
+        .resource::<RepliconClient>()
+        .id()
+        .unwrap();
+    let connected_clients = server_app.world().resource::<ConnectedClients>();
+    assert!(connected_clients.client(client_id).initial_sync_complete());
+}
+
 #[test]
 fn pre_spawn() {
     let mut server_app = App::new();
@@ -258,3 +382,7 @@ fn after_despawn() {
 
 #[derive(Component, Deserialize, Serialize)]
 struct DummyComponent;
+
+fn initial_sync_complete_on(app: &mut App) -> bool {
+    app.world_mut().run_system_once(initial_sync_complete)
+}
@@ -298,3 +298,402 @@ fn whitelist_with_despawn() {
 
 #[derive(Component, Deserialize, Serialize)]
 struct DummyComponent;
+
+#[test]
+fn component_visibility() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<DummyComponent>()
+        .replicate::<BoolComponent>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app
+        .world_mut()
+        .spawn((Replicated, DummyComponent, BoolComponent(false)))
+        .id();
+
+    let client = client_app.world().resource::<RepliconClient>();
+    let client_id = client.id().unwrap();
+    let component_id = server_app.world().component_id::<BoolComponent>().unwrap();
+    let mut connected_clients = server_app.world_mut().resource_mut::<ConnectedClients>();
+    let visibility = connected_clients
+        .client_mut(client_id)
+        .component_visibility_mut();
+    visibility.set_visibility(server_entity, component_id, false);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let client_entity = client_app
+        .world_mut()
+        .query_filtered::<Entity, (With<Replicated>, With<DummyComponent>)>()
+        .single(client_app.world());
+    assert!(
+        !client_app
+            .world()
+            .entity(client_entity)
+            .contains::<BoolComponent>(),
+        "hidden component shouldn't be replicated"
+    );
+
+    // Change while hidden.
+    server_app
+        .world_mut()
+        .get_mut::<BoolComponent>(server_entity)
+        .unwrap()
+        .0 = true;
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    assert!(!client_app
+        .world()
+        .entity(client_entity)
+        .contains::<BoolComponent>());
+
+    let mut connected_clients = server_app.world_mut().resource_mut::<ConnectedClients>();
+    let visibility = connected_clients
+        .client_mut(client_id)
+        .component_visibility_mut();
+    visibility.set_visibility(server_entity, component_id, true);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let component = client_app
+        .world()
+        .get::<BoolComponent>(client_entity)
+        .expect("component should be inserted after becoming visible");
+    assert!(component.0);
+
+    let mut connected_clients = server_app.world_mut().resource_mut::<ConnectedClients>();
+    let visibility = connected_clients
+        .client_mut(client_id)
+        .component_visibility_mut();
+    visibility.set_visibility(server_entity, component_id, false);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let client_entity = client_app.world().entity(client_entity);
+    assert!(
+        !client_entity.contains::<BoolComponent>(),
+        "component should be removed after becoming hidden"
+    );
+    assert!(client_entity.contains::<DummyComponent>());
+}
+
+#[derive(Component, Deserialize, Serialize)]
+struct BoolComponent(bool);
+
+#[test]
+fn rooms() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                visibility_policy: VisibilityPolicy::Whitelist,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<DummyComponent>();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let first_room = RoomId::new(0);
+    let second_room = RoomId::new(1);
+    let server_entity = server_app
+        .world_mut()
+        .spawn((Replicated, DummyComponent, Rooms::new([first_room])))
+        .id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    assert!(
+        client_app.world().entities().is_empty(),
+        "client isn't in any room"
+    );
+
+    let client = client_app.world().resource::<RepliconClient>();
+    let client_id = client.id().unwrap();
+    let mut connected_clients = server_app.world_mut().resource_mut::<ConnectedClients>();
+    connected_clients.join_room(client_id, first_room);
+    connected_clients.join_room(client_id, second_room);
+    assert_eq!(
+        connected_clients.client(client_id).rooms(),
+        [first_room, second_room]
+    );
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    client_app
+        .world_mut()
+        .query_filtered::<(), (With<Replicated>, With<DummyComponent>)>()
+        .single(client_app.world());
+
+    // Move to another shared room, the entity should stay visible.
+    let mut rooms = server_app
+        .world_mut()
+        .get_mut::<Rooms>(server_entity)
+        .unwrap();
+    rooms.insert(second_room);
+    rooms.remove(first_room);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    client_app
+        .world_mut()
+        .query_filtered::<(), (With<Replicated>, With<DummyComponent>)>()
+        .single(client_app.world());
+
+    let mut connected_clients = server_app.world_mut().resource_mut::<ConnectedClients>();
+    connected_clients.leave_room(client_id, first_room);
+    assert!(connected_clients
+        .client(client_id)
+        .visibility()
+        .is_visible(server_entity));
+
+    connected_clients.leave_room(client_id, second_room);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    assert!(
+        client_app.world().entities().is_empty(),
+        "entity should be despawned after leaving the last shared room"
+    );
+
+    // Join again and remove rooms from the entity.
+    let mut connected_clients = server_app.world_mut().resource_mut::<ConnectedClients>();
+    connected_clients.join_room(client_id, second_room);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    client_app
+        .world_mut()
+        .query_filtered::<(), (With<Replicated>, With<DummyComponent>)>()
+        .single(client_app.world());
+
+    server_app
+        .world_mut()
+        .entity_mut(server_entity)
+        .remove::<Rooms>();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    assert!(client_app.world().entities().is_empty());
+}
+
+#[test]
+fn spatial_interest() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                visibility_policy: VisibilityPolicy::Whitelist,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<DummyComponent>();
+    }
+    server_app.add_plugins(SpatialInterestPlugin {
+        cell_size: 10.0,
+        radius: 10.0,
+        hysteresis: 10.0,
+        axes: GridAxes::Xy,
+    });
+
+    server_app.connect_client(&mut client_app);
+
+    let client = client_app.world().resource::<RepliconClient>();
+    let client_id = client.id().unwrap();
+    let viewer = server_app
+        .world_mut()
+        .spawn((Viewer(client_id), Transform::default()))
+        .id();
+    let server_entity = server_app
+        .world_mut()
+        .spawn((
+            Replicated,
+            DummyComponent,
+            Transform::from_xyz(15.0, 0.0, 0.0),
+        ))
+        .id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    client_app
+        .world_mut()
+        .query_filtered::<(), (With<Replicated>, With<DummyComponent>)>()
+        .single(client_app.world());
+
+    // Move away, but stay within hysteresis.
+    server_app
+        .world_mut()
+        .get_mut::<Transform>(viewer)
+        .unwrap()
+        .translation
+        .x = -5.0;
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    client_app
+        .world_mut()
+        .query_filtered::<(), (With<Replicated>, With<DummyComponent>)>()
+        .single(client_app.world());
+
+    server_app
+        .world_mut()
+        .get_mut::<Transform>(viewer)
+        .unwrap()
+        .translation
+        .x = -15.0;
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    assert!(
+        client_app.world().entities().is_empty(),
+        "entity should be hidden after the viewer moved beyond hysteresis"
+    );
+
+    // Move the entity closer to the viewer.
+    server_app
+        .world_mut()
+        .get_mut::<Transform>(server_entity)
+        .unwrap()
+        .translation
+        .x = -25.0;
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    client_app
+        .world_mut()
+        .query_filtered::<(), (With<Replicated>, With<DummyComponent>)>()
+        .single(client_app.world());
+
+    server_app.world_mut().despawn(viewer);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    assert!(client_app.world().entities().is_empty());
+}
+
+#[test]
+fn spatial_interest_with_insertion_and_removal() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            RepliconPlugins.set(ServerPlugin {
+                tick_policy: TickPolicy::EveryFrame,
+                visibility_policy: VisibilityPolicy::Whitelist,
+                ..Default::default()
+            }),
+        ))
+        .replicate::<DummyComponent>();
+    }
+    server_app.add_plugins(SpatialInterestPlugin {
+        cell_size: 10.0,
+        radius: 10.0,
+        hysteresis: 10.0,
+        axes: GridAxes::Xy,
+    });
+
+    server_app.connect_client(&mut client_app);
+
+    let client = client_app.world().resource::<RepliconClient>();
+    let client_id = client.id().unwrap();
+    server_app
+        .world_mut()
+        .spawn((Viewer(client_id), Transform::default()));
+    let server_entity = server_app
+        .world_mut()
+        .spawn((DummyComponent, Transform::from_xyz(5.0, 0.0, 0.0)))
+        .id();
+
+    server_app.update();
+
+    // Mark as replicated without changing the transform.
+    server_app
+        .world_mut()
+        .entity_mut(server_entity)
+        .insert(Replicated);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    client_app
+        .world_mut()
+        .query_filtered::<(), (With<Replicated>, With<DummyComponent>)>()
+        .single(client_app.world());
+
+    server_app
+        .world_mut()
+        .entity_mut(server_entity)
+        .remove::<Transform>();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    assert!(
+        client_app.world().entities().is_empty(),
+        "entity should be hidden after losing its transform"
+    );
+}